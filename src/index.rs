@@ -1138,7 +1138,6 @@ impl Index {
     Ok((collections, more))
   }
 
-  #[cfg(test)]
   pub(crate) fn get_children_by_inscription_id(
     &self,
     inscription_id: InscriptionId,
@@ -29,39 +29,34 @@ pub struct Envelope<T> {
 
 impl From<RawEnvelope> for ParsedEnvelope {
   fn from(envelope: RawEnvelope) -> Self {
-    let body = envelope
-      .payload
-      .iter()
-      .enumerate()
-      .position(|(i, push)| i % 2 == 0 && push.is_empty());
-
-    let mut fields: BTreeMap<&[u8], Vec<&[u8]>> = BTreeMap::new();
-
-    let mut incomplete_field = false;
-
-    for item in envelope.payload[..body.unwrap_or(envelope.payload.len())].chunks(2) {
-      match item {
-        [key, value] => fields.entry(key).or_default().push(value),
-        _ => incomplete_field = true,
-      }
-    }
+    let (body, mut fields, incomplete_field) = envelope.fields();
 
     let duplicate_field = fields.iter().any(|(_key, values)| values.len() > 1);
 
+    let collection = Tag::Collection.remove_field(&mut fields);
     let content_encoding = Tag::ContentEncoding.remove_field(&mut fields);
     let content_type = Tag::ContentType.remove_field(&mut fields);
     let delegate = Tag::Delegate.remove_field(&mut fields);
     let metadata = Tag::Metadata.remove_field(&mut fields);
     let metaprotocol = Tag::Metaprotocol.remove_field(&mut fields);
     let parent = Tag::Parent.remove_field(&mut fields);
+    let additional_parents = fields
+      .remove(Tag::Parent.bytes())
+      .unwrap_or_default()
+      .into_iter()
+      .map(<[u8]>::to_vec)
+      .collect();
     let pointer = Tag::Pointer.remove_field(&mut fields);
+    let unbound = Tag::Unbound.remove_field(&mut fields).is_some();
 
-    let unrecognized_even_field = fields
-      .keys()
-      .any(|tag| tag.first().map(|lsb| lsb % 2 == 0).unwrap_or_default());
+    // the unbound tag is even, and it's by being an even tag that indexers don't recognize that it
+    // unbinds an inscription, so it still counts as one here
+    let unrecognized_even_field =
+      unbound || !Tag::unrecognized_even_fields(&fields).is_empty();
 
     Self {
       payload: Inscription {
+        additional_parents,
         body: body.map(|i| {
           envelope.payload[i + 1..]
             .iter()
@@ -69,6 +64,7 @@ impl From<RawEnvelope> for ParsedEnvelope {
             .cloned()
             .collect()
         }),
+        collection,
         content_encoding,
         content_type,
         delegate,
@@ -79,6 +75,7 @@ impl From<RawEnvelope> for ParsedEnvelope {
         parent,
         pointer,
         skip_pointer: false,
+        unbound,
         unrecognized_even_field,
         utxo: None,
       },
@@ -103,9 +100,92 @@ impl ParsedEnvelope {
       })
       .collect()
   }
+
+  // like `from_transaction`, but decoding each envelope with `Tag::decode_all`, so that a single-value tag
+  // appearing more than once is an error instead of being flagged as a duplicate
+  pub(crate) fn from_transaction_strict(transaction: &Transaction) -> anyhow::Result<Vec<Self>> {
+    RawEnvelope::from_transaction(transaction)
+      .into_iter()
+      .enumerate()
+      .map(|(i, envelope)| {
+        Ok(Self {
+          payload: envelope
+            .decode_strict()
+            .with_context(|| format!("failed to decode inscription {i}"))?,
+          input: envelope.input,
+          offset: envelope.offset,
+          pushnum: envelope.pushnum,
+          stutter: envelope.stutter,
+        })
+      })
+      .collect()
+  }
+
+  // the keys of each envelope's unrecognized even fields, in the same order as `from_transaction` returns the
+  // envelopes when not ignoring any
+  pub(crate) fn unrecognized_even_fields(transaction: &Transaction) -> Vec<Vec<Vec<u8>>> {
+    RawEnvelope::from_transaction(transaction)
+      .iter()
+      .map(RawEnvelope::unrecognized_even_fields)
+      .collect()
+  }
 }
 
 impl RawEnvelope {
+  // the position of the body tag, the fields before it, and whether the last of them is missing its value
+  fn fields(&self) -> (Option<usize>, BTreeMap<&[u8], Vec<&[u8]>>, bool) {
+    let body = self
+      .payload
+      .iter()
+      .enumerate()
+      .position(|(i, push)| i % 2 == 0 && push.is_empty());
+
+    let mut fields: BTreeMap<&[u8], Vec<&[u8]>> = BTreeMap::new();
+
+    let mut incomplete_field = false;
+
+    for item in self.payload[..body.unwrap_or(self.payload.len())].chunks(2) {
+      match item {
+        [key, value] => fields.entry(key).or_default().push(value),
+        _ => incomplete_field = true,
+      }
+    }
+
+    (body, fields, incomplete_field)
+  }
+
+  fn decode_strict(&self) -> anyhow::Result<Inscription> {
+    let (body, fields, incomplete_field) = self.fields();
+
+    let (inscription, _unrecognized_even_fields) = Tag::decode_all(fields)?;
+
+    Ok(Inscription {
+      body: body.map(|i| self.payload[i + 1..].iter().flatten().cloned().collect()),
+      incomplete_field,
+      ..inscription
+    })
+  }
+
+  fn unrecognized_even_fields(&self) -> Vec<Vec<u8>> {
+    let (_body, mut fields, _incomplete_field) = self.fields();
+
+    for tag in [
+      Tag::Pointer,
+      Tag::Unbound,
+      Tag::ContentType,
+      Tag::Parent,
+      Tag::Metadata,
+      Tag::Metaprotocol,
+      Tag::ContentEncoding,
+      Tag::Delegate,
+      Tag::Collection,
+    ] {
+      fields.remove(tag.bytes());
+    }
+
+    Tag::unrecognized_even_fields(&fields)
+  }
+
   pub(crate) fn from_transaction(transaction: &Transaction) -> Vec<Self> {
     let mut envelopes = Vec::new();
 
@@ -774,6 +854,31 @@ mod tests {
     );
   }
 
+  #[test]
+  fn round_trip_with_unbound_field() {
+    let unbound = Inscription {
+      unbound: true,
+      ..inscription("foo", [1; 100])
+    };
+
+    let mut witness = Witness::new();
+
+    witness.push(unbound.append_reveal_script(script::Builder::new()));
+
+    witness.push([]);
+
+    assert_eq!(
+      parse(&[witness]),
+      vec![ParsedEnvelope {
+        payload: Inscription {
+          unrecognized_even_field: true,
+          ..unbound
+        },
+        ..Default::default()
+      }],
+    );
+  }
+
   #[test]
   fn unknown_odd_fields_are_ignored() {
     assert_eq!(
@@ -785,6 +890,20 @@ mod tests {
     );
   }
 
+  #[test]
+  fn collection_field() {
+    assert_eq!(
+      parse(&[envelope(&[&PROTOCOL_ID, Tag::Collection.bytes(), b"foo"])]),
+      vec![ParsedEnvelope {
+        payload: Inscription {
+          collection: Some(b"foo".to_vec()),
+          ..Default::default()
+        },
+        ..Default::default()
+      }],
+    );
+  }
+
   #[test]
   fn unknown_even_fields() {
     assert_eq!(
@@ -835,6 +954,7 @@ mod tests {
       parse(&[envelope(&[&PROTOCOL_ID, Tag::Unbound.bytes(), &[1]])]),
       vec![ParsedEnvelope {
         payload: Inscription {
+          unbound: true,
           unrecognized_even_field: true,
           ..Default::default()
         },
@@ -8,7 +8,10 @@ use {
     },
     ScriptBuf,
   },
-  brotli::enc::{writer::CompressorWriter, BrotliEncoderParams},
+  brotli::enc::{
+    backward_references::BrotliEncoderMode, writer::CompressorWriter, BrotliEncoderParams,
+  },
+  flate2::{read::GzDecoder, write::GzEncoder, Compression},
   http::header::HeaderValue,
   io::{Cursor, Read, Write},
   std::str,
@@ -16,7 +19,10 @@ use {
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Default)]
 pub struct Inscription {
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub additional_parents: Vec<Vec<u8>>,
   pub body: Option<Vec<u8>>,
+  pub collection: Option<Vec<u8>>,
   pub content_encoding: Option<Vec<u8>>,
   pub content_type: Option<Vec<u8>>,
   pub delegate: Option<Vec<u8>>,
@@ -27,6 +33,7 @@ pub struct Inscription {
   pub parent: Option<Vec<u8>>,
   pub pointer: Option<Vec<u8>>,
   pub skip_pointer: bool,
+  pub unbound: bool,
   pub unrecognized_even_field: bool,
   pub utxo: Option<OutPoint>,
 }
@@ -45,13 +52,15 @@ impl Inscription {
     chain: Chain,
     delegate: Option<InscriptionId>,
     path: impl AsRef<Path>,
+    content_type: Option<String>,
     parent: Option<InscriptionId>,
     pointer: Option<u64>,
     metaprotocol: Option<String>,
     metadata: Option<Vec<u8>>,
-    compress: bool,
+    content_encoding: Option<&str>,
     skip_pointer_for_none: bool,
     utxo: Option<OutPoint>,
+    unbound: bool,
   ) -> Result<Self, Error> {
     let path = path.as_ref();
 
@@ -60,48 +69,72 @@ impl Inscription {
         body: None,
         content_type: None,
         content_encoding: None,
+        delegate: delegate.map(|id| id.value()),
         metadata,
         metaprotocol: metaprotocol.map(|metaprotocol| metaprotocol.into_bytes()),
         parent: parent.map(|id| id.value()),
         pointer: pointer.map(Self::pointer_value),
         skip_pointer: skip_pointer_for_none,
+        unbound,
         ..Default::default()
       });
     }
 
     let body = fs::read(path).with_context(|| format!("io error reading {}", path.display()))?;
 
-    let (content_type, compression_mode) = Media::content_type_for_path(path)?;
+    // an explicit content type skips the extension lookup, so files with extensions we don't know can still be inscribed
+    let (content_type, compression_mode) = match content_type {
+      Some(content_type) => (content_type, BrotliEncoderMode::BROTLI_MODE_GENERIC),
+      None => {
+        let (content_type, compression_mode) = Media::content_type_for_path(path)?;
+        (content_type.to_string(), compression_mode)
+      }
+    };
 
-    let (body, content_encoding) = if compress {
+    let (body, content_encoding) = if let Some(encoding) = content_encoding {
       let mut compressed = Vec::new();
 
-      {
-        CompressorWriter::with_params(
-          &mut compressed,
-          body.len(),
-          &BrotliEncoderParams {
-            lgblock: 24,
-            lgwin: 24,
-            mode: compression_mode,
-            quality: 11,
-            size_hint: body.len(),
-            ..Default::default()
-          },
-        )
-        .write_all(&body)?;
+      match encoding {
+        "gzip" => {
+          let mut encoder = GzEncoder::new(&mut compressed, Compression::best());
+          encoder.write_all(&body)?;
+          encoder.finish()?;
 
-        let mut decompressor = brotli::Decompressor::new(compressed.as_slice(), compressed.len());
+          let mut decompressed = Vec::new();
 
-        let mut decompressed = Vec::new();
+          GzDecoder::new(compressed.as_slice()).read_to_end(&mut decompressed)?;
 
-        decompressor.read_to_end(&mut decompressed)?;
-
-        ensure!(decompressed == body, "decompression roundtrip failed");
+          ensure!(decompressed == body, "decompression roundtrip failed");
+        }
+        "br" => {
+          CompressorWriter::with_params(
+            &mut compressed,
+            body.len(),
+            &BrotliEncoderParams {
+              lgblock: 24,
+              lgwin: 24,
+              mode: compression_mode,
+              quality: 11,
+              size_hint: body.len(),
+              ..Default::default()
+            },
+          )
+          .write_all(&body)?;
+
+          let mut decompressor = brotli::Decompressor::new(compressed.as_slice(), compressed.len());
+
+          let mut decompressed = Vec::new();
+
+          decompressor.read_to_end(&mut decompressed)?;
+
+          ensure!(decompressed == body, "decompression roundtrip failed");
+        }
+        _ => bail!("unsupported content encoding `{encoding}`"),
       }
 
+      // only keep the encoding if it actually makes the body smaller
       if compressed.len() < body.len() {
-        (compressed, Some("br".as_bytes().to_vec()))
+        (compressed, Some(encoding.as_bytes().to_vec()))
       } else {
         (body, None)
       }
@@ -126,6 +159,7 @@ impl Inscription {
       parent: parent.map(|id| id.value()),
       pointer: pointer.map(Self::pointer_value),
       skip_pointer: false,
+      unbound,
       utxo,
       ..Default::default()
     })
@@ -155,12 +189,19 @@ impl Inscription {
     Tag::ContentEncoding.encode(&mut builder, &self.content_encoding);
     }
     Tag::Metaprotocol.encode(&mut builder, &self.metaprotocol);
+    Tag::Collection.encode(&mut builder, &self.collection);
     Tag::Parent.encode(&mut builder, &self.parent);
+    // further parents repeat the parent tag; with none, the script is the same as for a single parent
+    for parent in &self.additional_parents {
+      Tag::Parent.encode(&mut builder, &Some(parent.clone()));
+    }
     Tag::Delegate.encode(&mut builder, &self.delegate);
     if !self.skip_pointer {
       Tag::Pointer.encode(&mut builder, &self.pointer);
     }
     Tag::Metadata.encode(&mut builder, &self.metadata);
+    // the tag's presence is what counts, so it carries an empty value
+    Tag::Unbound.encode(&mut builder, &self.unbound.then(Vec::new));
 
     if self.delegate.is_none() {
     if let Some(body) = &self.body {
@@ -252,6 +293,38 @@ impl Inscription {
     self.body
   }
 
+  pub(crate) fn decompressed_body(&self) -> Result<Option<Vec<u8>>> {
+    let Some(body) = self.body() else {
+      return Ok(None);
+    };
+
+    let mut decompressed = Vec::new();
+
+    match self.content_encoding.as_deref() {
+      None => return Ok(Some(body.to_vec())),
+      Some(b"br") => {
+        brotli::Decompressor::new(body, 4096)
+          .read_to_end(&mut decompressed)
+          .map_err(|err| anyhow!("corrupt brotli stream: {err}"))?;
+      }
+      Some(b"gzip") => {
+        flate2::read::GzDecoder::new(body)
+          .read_to_end(&mut decompressed)
+          .map_err(|err| anyhow!("corrupt gzip stream: {err}"))?;
+      }
+      Some(encoding) => bail!(
+        "unknown content encoding `{}`",
+        String::from_utf8_lossy(encoding)
+      ),
+    }
+
+    Ok(Some(decompressed))
+  }
+
+  pub(crate) fn collection(&self) -> Option<&str> {
+    str::from_utf8(self.collection.as_ref()?).ok()
+  }
+
   pub(crate) fn content_length(&self) -> Option<usize> {
     Some(self.body()?.len())
   }
@@ -280,6 +353,19 @@ impl Inscription {
     Self::inscription_id_field(&self.parent)
   }
 
+  pub(crate) fn parents(&self) -> Vec<InscriptionId> {
+    self
+      .parent()
+      .into_iter()
+      .chain(
+        self
+          .additional_parents
+          .iter()
+          .filter_map(|parent| Self::inscription_id_field(&Some(parent.clone()))),
+      )
+      .collect()
+  }
+
   pub(crate) fn pointer(&self) -> Option<u64> {
     let value = self.pointer.as_ref()?;
 
@@ -455,6 +541,45 @@ mod tests {
     );
   }
 
+  #[test]
+  fn additional_parents_repeat_the_parent_tag() {
+    let single = Inscription {
+      parent: Some(inscription_id(1).value()),
+      ..Default::default()
+    };
+
+    let multiple = Inscription {
+      additional_parents: vec![inscription_id(2).value(), inscription_id(3).value()],
+      ..single.clone()
+    };
+
+    assert_eq!(
+      multiple.parents(),
+      vec![inscription_id(1), inscription_id(2), inscription_id(3)]
+    );
+
+    assert_eq!(
+      single.append_reveal_script(script::Builder::new()),
+      Inscription {
+        additional_parents: Vec::new(),
+        ..multiple.clone()
+      }
+      .append_reveal_script(script::Builder::new())
+    );
+
+    assert_eq!(
+      multiple
+        .append_reveal_script(script::Builder::new())
+        .instructions()
+        .count(),
+      single
+        .append_reveal_script(script::Builder::new())
+        .instructions()
+        .count()
+        + 4
+    );
+  }
+
   #[test]
   fn inscription_with_no_parent_field_has_no_parent() {
     assert!(Inscription {
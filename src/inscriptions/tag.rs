@@ -3,7 +3,6 @@ use super::*;
 #[derive(Copy, Clone)]
 pub(crate) enum Tag {
   Pointer,
-  #[allow(unused)]
   Unbound,
 
   ContentType,
@@ -12,6 +11,7 @@ pub(crate) enum Tag {
   Metaprotocol,
   ContentEncoding,
   Delegate,
+  Collection,
   #[allow(unused)]
   Nop,
 }
@@ -32,6 +32,8 @@ impl Tag {
       Self::Metaprotocol => &[7],
       Self::ContentEncoding => &[9],
       Self::Delegate => &[11],
+      // odd, so inscriptions carrying it stay blessed on indexers that don't know about it
+      Self::Collection => &[101],
       Self::Nop => &[255],
     }
   }
@@ -101,4 +103,217 @@ impl Tag {
       }
     }
   }
+
+  // the keys of the fields left over once the known tags have been removed that are even, and so mandatory to
+  // understand. odd tags are safe to ignore, so they aren't reported
+  pub(crate) fn unrecognized_even_fields(fields: &BTreeMap<&[u8], Vec<&[u8]>>) -> Vec<Vec<u8>> {
+    fields
+      .keys()
+      .filter(|tag| Self::is_even(tag))
+      .map(|tag| tag.to_vec())
+      .collect()
+  }
+
+  fn is_even(tag: &[u8]) -> bool {
+    tag.first().map(|lsb| lsb % 2 == 0).unwrap_or_default()
+  }
+
+  // decode every known tag out of an envelope's fields, strictly: a single-value tag that appears more than once
+  // is an error, where the envelope parser would just take the first and flag a duplicate. leftover unknown even
+  // fields are returned, and unknown odd fields are dropped, as they're safe to ignore
+  pub(crate) fn decode_all<'a>(
+    mut fields: BTreeMap<&'a [u8], Vec<&'a [u8]>>,
+  ) -> Result<(Inscription, BTreeMap<&'a [u8], Vec<&'a [u8]>>)> {
+    for tag in [
+      Self::Pointer,
+      Self::Unbound,
+      Self::ContentType,
+      Self::Metaprotocol,
+      Self::ContentEncoding,
+      Self::Delegate,
+      Self::Collection,
+    ] {
+      if let Some(values) = fields.get(tag.bytes()) {
+        if values.len() > 1 {
+          bail!(
+            "tag {} appears {} times, but may only appear once",
+            tag.bytes()[0],
+            values.len()
+          );
+        }
+      }
+    }
+
+    let parent = Self::Parent.remove_field(&mut fields);
+
+    // further parents repeat the parent tag
+    let additional_parents = fields
+      .remove(Self::Parent.bytes())
+      .unwrap_or_default()
+      .into_iter()
+      .map(<[u8]>::to_vec)
+      .collect();
+
+    let inscription = Inscription {
+      additional_parents,
+      collection: Self::Collection.remove_field(&mut fields),
+      content_encoding: Self::ContentEncoding.remove_field(&mut fields),
+      content_type: Self::ContentType.remove_field(&mut fields),
+      delegate: Self::Delegate.remove_field(&mut fields),
+      metadata: Self::Metadata.remove_field(&mut fields),
+      metaprotocol: Self::Metaprotocol.remove_field(&mut fields),
+      parent,
+      pointer: Self::Pointer.remove_field(&mut fields),
+      unbound: Self::Unbound.remove_field(&mut fields).is_some(),
+      ..Default::default()
+    };
+
+    fields.retain(|tag, _values| Self::is_even(tag));
+
+    Ok((
+      Inscription {
+        // as in the envelope parser, the unbound tag counts as an unrecognized even field
+        unrecognized_even_field: inscription.unbound || !fields.is_empty(),
+        ..inscription
+      },
+      fields,
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::blockdata::script::Instruction};
+
+  fn fields(script: &script::Script) -> Vec<Vec<u8>> {
+    script
+      .instructions()
+      .map(|instruction| match instruction.unwrap() {
+        Instruction::PushBytes(bytes) => bytes.as_bytes().to_vec(),
+        Instruction::Op(op) => panic!("unexpected opcode {op:?}"),
+      })
+      .collect()
+  }
+
+  fn field_map(pushes: &[Vec<u8>]) -> BTreeMap<&[u8], Vec<&[u8]>> {
+    let mut fields = BTreeMap::<&[u8], Vec<&[u8]>>::new();
+
+    for pair in pushes.chunks(2) {
+      fields.entry(pair[0].as_slice()).or_default().push(pair[1].as_slice());
+    }
+
+    fields
+  }
+
+  #[test]
+  fn decode_all_inverts_encode() {
+    let metadata = (0..1100).map(|i| (i % 256) as u8).collect::<Vec<u8>>();
+
+    let mut builder = script::Builder::new();
+    Tag::ContentType.encode(&mut builder, &Some(b"text/plain".to_vec()));
+    Tag::Parent.encode(&mut builder, &Some(inscription_id(1).value()));
+    Tag::Parent.encode(&mut builder, &Some(inscription_id(2).value()));
+    Tag::Delegate.encode(&mut builder, &Some(inscription_id(3).value()));
+    Tag::Pointer.encode(&mut builder, &Some(vec![1]));
+    Tag::Metadata.encode(&mut builder, &Some(metadata.clone()));
+    Tag::Metaprotocol.encode(&mut builder, &Some(b"foo".to_vec()));
+    Tag::ContentEncoding.encode(&mut builder, &Some(b"br".to_vec()));
+
+    let pushes = fields(&builder.into_script());
+
+    // the metadata is split into three chunks
+    assert_eq!(pushes.len(), 20);
+
+    let (inscription, unknown) = Tag::decode_all(field_map(&pushes)).unwrap();
+
+    assert_eq!(
+      inscription,
+      Inscription {
+        additional_parents: vec![inscription_id(2).value()],
+        content_encoding: Some(b"br".to_vec()),
+        content_type: Some(b"text/plain".to_vec()),
+        delegate: Some(inscription_id(3).value()),
+        metadata: Some(metadata),
+        metaprotocol: Some(b"foo".to_vec()),
+        parent: Some(inscription_id(1).value()),
+        pointer: Some(vec![1]),
+        ..Default::default()
+      }
+    );
+
+    assert!(unknown.is_empty());
+  }
+
+  #[test]
+  fn decode_all_decodes_collection_and_unbound() {
+    let mut builder = script::Builder::new();
+    Tag::Collection.encode(&mut builder, &Some(b"punks".to_vec()));
+    Tag::Unbound.encode(&mut builder, &Some(Vec::new()));
+
+    let (inscription, unknown) = Tag::decode_all(field_map(&fields(&builder.into_script()))).unwrap();
+
+    assert_eq!(
+      inscription,
+      Inscription {
+        collection: Some(b"punks".to_vec()),
+        unbound: true,
+        unrecognized_even_field: true,
+        ..Default::default()
+      }
+    );
+
+    assert!(unknown.is_empty());
+  }
+
+  #[test]
+  fn decode_all_rejects_repeated_collection_tags() {
+    let mut builder = script::Builder::new();
+    Tag::Collection.encode(&mut builder, &Some(b"punks".to_vec()));
+    Tag::Collection.encode(&mut builder, &Some(b"apes".to_vec()));
+
+    assert_eq!(
+      Tag::decode_all(field_map(&fields(&builder.into_script())))
+        .unwrap_err()
+        .to_string(),
+      "tag 101 appears 2 times, but may only appear once"
+    );
+  }
+
+  #[test]
+  fn decode_all_rejects_repeated_single_value_tags() {
+    let mut builder = script::Builder::new();
+    Tag::ContentType.encode(&mut builder, &Some(b"text/plain".to_vec()));
+    Tag::ContentType.encode(&mut builder, &Some(b"text/html".to_vec()));
+
+    assert_eq!(
+      Tag::decode_all(field_map(&fields(&builder.into_script())))
+        .unwrap_err()
+        .to_string(),
+      "tag 1 appears 2 times, but may only appear once"
+    );
+  }
+
+  #[test]
+  fn decode_all_returns_unknown_even_fields() {
+    let pushes = vec![vec![22], vec![1], vec![23], vec![2], vec![1], b"text/plain".to_vec()];
+
+    let (inscription, unknown) = Tag::decode_all(field_map(&pushes)).unwrap();
+
+    assert_eq!(inscription.content_type, Some(b"text/plain".to_vec()));
+    assert!(inscription.unrecognized_even_field);
+    assert_eq!(
+      unknown,
+      BTreeMap::from([(&[22u8][..], vec![&[1u8][..]])])
+    );
+  }
+
+  #[test]
+  fn unrecognized_even_fields_ignores_odd_fields() {
+    let pushes = vec![vec![22], vec![1], vec![23], vec![2], vec![24, 1], vec![3]];
+
+    assert_eq!(
+      Tag::unrecognized_even_fields(&field_map(&pushes)),
+      vec![vec![22], vec![24, 1]]
+    );
+  }
 }
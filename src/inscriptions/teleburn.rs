@@ -91,4 +91,16 @@ mod tests {
       assert_eq!(*addr, Ethereum::from(*inscription_id).0);
     }
   }
+
+  #[test]
+  fn display_is_checksummed() {
+    assert_eq!(
+      Ethereum::from(InscriptionId {
+        txid: Txid::all_zeros(),
+        index: 0,
+      })
+      .to_string(),
+      "0x6db65fD59fd356F6729140571B5BCd6bB3b83492",
+    );
+  }
 }
@@ -64,6 +64,8 @@ pub struct Options {
   pub(crate) no_progress_bar: bool,
   #[arg(long, short, help = "Use regtest. Equivalent to `--chain regtest`.")]
   pub(crate) regtest: bool,
+  #[arg(long, help = "Give up on Bitcoin Core RPC calls that take longer than <RPC_TIMEOUT> seconds. [default: 15]")]
+  pub(crate) rpc_timeout: Option<u64>,
   #[arg(long, help = "Connect to Bitcoin Core RPC at <RPC_URL>.")]
   pub(crate) rpc_url: Option<String>,
   #[arg(long, short, help = "Use signet. Equivalent to `--chain signet`.")]
@@ -222,6 +224,26 @@ impl Options {
     }
   }
 
+  pub(crate) fn rpc_timeout(&self) -> Duration {
+    Duration::from_secs(self.rpc_timeout.unwrap_or(15))
+  }
+
+  fn rpc_client(rpc_url: &str, auth: Auth, timeout: Duration) -> Result<Client> {
+    let (user, pass) = auth.get_user_pass()?;
+
+    let mut builder = bitcoincore_rpc::jsonrpc::simple_http::Builder::new()
+      .timeout(timeout)
+      .url(rpc_url)?;
+
+    if let Some(user) = user {
+      builder = builder.auth(user, pass);
+    }
+
+    Ok(Client::from_jsonrpc(
+      bitcoincore_rpc::jsonrpc::Client::with_transport(builder.build()),
+    ))
+  }
+
   pub(crate) fn bitcoin_rpc_client(&self, wallet: Option<String>) -> Result<Client> {
     let rpc_url = self.rpc_url(wallet);
 
@@ -242,7 +264,7 @@ impl Options {
       );
     }
 
-    let client = Client::new(&rpc_url, auth)
+    let client = Self::rpc_client(&rpc_url, auth, self.rpc_timeout())
       .with_context(|| format!("failed to connect to Bitcoin Core RPC at {rpc_url}"))?;
 
     let rpc_chain = match client.get_blockchain_info()?.chain.as_str() {
@@ -284,6 +306,28 @@ mod tests {
     );
   }
 
+  #[test]
+  fn rpc_timeout_defaults_to_fifteen_seconds() {
+    assert_eq!(
+      Arguments::try_parse_from(["ord", "index", "update"])
+        .unwrap()
+        .options
+        .rpc_timeout(),
+      Duration::from_secs(15)
+    );
+  }
+
+  #[test]
+  fn rpc_timeout_can_be_overridden() {
+    assert_eq!(
+      Arguments::try_parse_from(["ord", "--rpc-timeout=120", "index", "update"])
+        .unwrap()
+        .options
+        .rpc_timeout(),
+      Duration::from_secs(120)
+    );
+  }
+
   #[test]
   fn cookie_file_overrides_network() {
     assert_eq!(
@@ -8,4 +8,5 @@ pub(crate) struct ServerConfig {
   pub(crate) domain: Option<String>,
   pub(crate) index_sats: bool,
   pub(crate) is_json_api_enabled: bool,
+  pub(crate) nostr_key: Option<bitcoin::secp256k1::SecretKey>,
 }
@@ -1,7 +1,10 @@
 use super::*;
 
+pub mod address_of;
 pub mod balances;
+pub mod children;
 pub mod decode;
+pub mod decode_reveal_psbt;
 pub mod epochs;
 pub mod find;
 pub mod index;
@@ -15,14 +18,22 @@ pub mod supply;
 pub mod teleburn;
 pub mod traits;
 pub mod transfer;
+pub mod verify_content;
+pub mod verify_delegate;
 pub mod wallet;
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
+  #[command(about = "Find the address currently holding an inscription")]
+  AddressOf(address_of::AddressOf),
   #[command(about = "List all rune balances")]
   Balances,
+  #[command(about = "List an inscription's children")]
+  Children(children::Children),
   #[command(about = "Decode a transaction")]
   Decode(decode::Decode),
+  #[command(about = "Show what a reveal PSBT would inscribe")]
+  DecodeRevealPsbt(decode_reveal_psbt::DecodeRevealPsbt),
   #[command(about = "List the first satoshis of each reward epoch")]
   Epochs,
   #[command(about = "Find a satoshi's current location")]
@@ -49,6 +60,10 @@ pub(crate) enum Subcommand {
   Traits(traits::Traits),
   #[command(about = "Modify transfer log table")]
   Transfer(transfer::Transfer),
+  #[command(about = "Check that an inscription's content matches a file")]
+  VerifyContent(verify_content::VerifyContent),
+  #[command(about = "Check that an inscription's delegate exists and has content")]
+  VerifyDelegate(verify_delegate::VerifyDelegate),
   #[command(about = "Wallet commands")]
   Wallet(wallet::Wallet),
 }
@@ -56,8 +71,11 @@ pub(crate) enum Subcommand {
 impl Subcommand {
   pub(crate) fn run(self, options: Options) -> SubcommandResult {
     match self {
+      Self::AddressOf(address_of) => address_of.run(options),
       Self::Balances => balances::run(options),
+      Self::Children(children) => children.run(options),
       Self::Decode(decode) => decode.run(options),
+      Self::DecodeRevealPsbt(decode_reveal_psbt) => decode_reveal_psbt.run(options),
       Self::Epochs => epochs::run(),
       Self::Find(find) => find.run(options),
       Self::Index(index) => index.run(options),
@@ -73,9 +91,11 @@ impl Subcommand {
       }
       Self::Subsidy(subsidy) => subsidy.run(),
       Self::Supply => supply::run(),
-      Self::Teleburn(teleburn) => teleburn.run(),
+      Self::Teleburn(teleburn) => teleburn.run(options),
       Self::Traits(traits) => traits.run(),
       Self::Transfer(transfer) => transfer.run(options),
+      Self::VerifyContent(verify_content) => verify_content.run(options),
+      Self::VerifyDelegate(verify_delegate) => verify_delegate.run(options),
       Self::Wallet(wallet) => wallet.run(options),
     }
   }
@@ -0,0 +1,67 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct AddressOf {
+  #[arg(help = "Find the address holding <INSCRIPTION_ID>.")]
+  inscription_id: InscriptionId,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub satpoint: SatPoint,
+  pub address: Option<Address<NetworkUnchecked>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub note: Option<String>,
+}
+
+impl AddressOf {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let satpoint = index
+      .get_inscription_satpoint_by_id(self.inscription_id)?
+      .ok_or_else(|| anyhow!("inscription {} not found", self.inscription_id))?;
+
+    if satpoint.outpoint == unbound_outpoint() {
+      return Ok(Box::new(Output {
+        satpoint,
+        address: None,
+        note: Some("inscription is unbound".into()),
+      }));
+    }
+
+    if satpoint.outpoint == OutPoint::null() {
+      return Ok(Box::new(Output {
+        satpoint,
+        address: None,
+        note: Some("inscription was lost to fees".into()),
+      }));
+    }
+
+    let output = index
+      .get_transaction(satpoint.outpoint.txid)?
+      .ok_or_else(|| anyhow!("transaction {} not found", satpoint.outpoint.txid))?
+      .output
+      .into_iter()
+      .nth(satpoint.outpoint.vout.try_into().unwrap())
+      .ok_or_else(|| anyhow!("output {} not found", satpoint.outpoint))?;
+
+    match options.chain().address_from_script(&output.script_pubkey) {
+      Ok(address) => Ok(Box::new(Output {
+        satpoint,
+        address: Some(Address::new(address.network, address.payload)),
+        note: None,
+      })),
+      Err(_) => Ok(Box::new(Output {
+        satpoint,
+        address: None,
+        note: Some(if output.script_pubkey.is_op_return() {
+          "inscription is in an OP_RETURN output".into()
+        } else {
+          format!("output script {} has no address", output.script_pubkey)
+        }),
+      })),
+    }
+  }
+}
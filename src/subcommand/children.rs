@@ -0,0 +1,77 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Children {
+  #[arg(help = "List the children of inscription <PARENT>.")]
+  parent: InscriptionId,
+  #[arg(long, help = "List each child's own children too.")]
+  recursive: bool,
+  #[arg(
+    long,
+    requires = "recursive",
+    help = "Only list descendants down to <DEPTH> generations below <PARENT>."
+  )]
+  depth: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub inscription: InscriptionId,
+  pub satpoint: Option<SatPoint>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub children: Option<Vec<Output>>,
+}
+
+impl Children {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    if index.get_inscription_entry(self.parent)?.is_none() {
+      bail!("inscription {} not found", self.parent);
+    }
+
+    let depth = if self.recursive {
+      self.depth.unwrap_or(usize::MAX)
+    } else {
+      1
+    };
+
+    let mut visited = BTreeSet::from([self.parent]);
+
+    Ok(Box::new(self.children(&index, self.parent, depth, &mut visited)?))
+  }
+
+  fn children(
+    &self,
+    index: &Index,
+    parent: InscriptionId,
+    depth: usize,
+    visited: &mut BTreeSet<InscriptionId>,
+  ) -> Result<Vec<Output>> {
+    let mut children = Vec::new();
+
+    if depth == 0 {
+      return Ok(children);
+    }
+
+    for inscription in index.get_children_by_inscription_id(parent)? {
+      // provenance shouldn't loop back on itself, but malformed data could make it, so never revisit an inscription
+      if !visited.insert(inscription) {
+        continue;
+      }
+
+      children.push(Output {
+        inscription,
+        satpoint: index.get_inscription_satpoint_by_id(inscription)?,
+        children: if self.recursive {
+          Some(self.children(index, inscription, depth - 1, visited)?)
+        } else {
+          None
+        },
+      });
+    }
+
+    Ok(children)
+  }
+}
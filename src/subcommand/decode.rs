@@ -8,6 +8,8 @@ pub struct CompactOutput {
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
 pub struct RawOutput {
   pub inscriptions: Vec<ParsedEnvelope>,
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub unrecognized_even_fields: BTreeMap<usize, Vec<String>>,
 }
 
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
@@ -15,11 +17,17 @@ pub struct CompactInscription {
   #[serde(default, skip_serializing_if = "Option::is_none")]
   pub body: Option<String>,
   #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub collection: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
   pub content_encoding: Option<String>,
   #[serde(default, skip_serializing_if = "Option::is_none")]
   pub content_type: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub decoded_size: Option<usize>,
   #[serde(default, skip_serializing_if = "std::ops::Not::not")]
   pub duplicate_field: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub encoded_size: Option<usize>,
   #[serde(default, skip_serializing_if = "std::ops::Not::not")]
   pub incomplete_field: bool,
   #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -32,6 +40,8 @@ pub struct CompactInscription {
   pub pointer: Option<u64>,
   #[serde(default, skip_serializing_if = "std::ops::Not::not")]
   pub unrecognized_even_field: bool,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub unrecognized_even_fields: Vec<String>,
 }
 
 impl TryFrom<Inscription> for CompactInscription {
@@ -39,6 +49,7 @@ impl TryFrom<Inscription> for CompactInscription {
 
   fn try_from(inscription: Inscription) -> Result<Self> {
     Ok(Self {
+      collection: inscription.collection().map(str::to_string),
       content_encoding: inscription
         .content_encoding()
         .map(|header_value| header_value.to_str().map(str::to_string))
@@ -48,10 +59,13 @@ impl TryFrom<Inscription> for CompactInscription {
       parent: inscription.parent(),
       pointer: inscription.pointer(),
       body: inscription.body.map(hex::encode),
+      decoded_size: None,
       duplicate_field: inscription.duplicate_field,
+      encoded_size: None,
       incomplete_field: inscription.incomplete_field,
       metadata: inscription.metadata.map(hex::encode),
       unrecognized_even_field: inscription.unrecognized_even_field,
+      unrecognized_even_fields: Vec::new(),
     })
   }
 }
@@ -71,6 +85,23 @@ pub(crate) struct Decode {
     help = "Serialize inscriptions in a compact, human-readable format."
   )]
   compact: bool,
+  #[arg(
+    long,
+    requires = "compact",
+    help = "Decompress brotli or gzip encoded inscription bodies."
+  )]
+  decompress: bool,
+  #[arg(
+    long,
+    requires = "decompress",
+    help = "Write the decompressed body to <OUTPUT>. The transaction must contain exactly one inscription."
+  )]
+  output: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Fail if a tag that may only appear once is repeated, instead of using its first value and flagging a duplicate field."
+  )]
+  strict: bool,
 }
 
 impl Decode {
@@ -85,18 +116,74 @@ impl Decode {
       Transaction::consensus_decode(&mut io::stdin())?
     };
 
-    let inscriptions = ParsedEnvelope::from_transaction(&transaction, false);
+    let inscriptions = if self.strict {
+      ParsedEnvelope::from_transaction_strict(&transaction)?
+    } else {
+      ParsedEnvelope::from_transaction(&transaction, false)
+    };
+
+    let unrecognized_even_fields = ParsedEnvelope::unrecognized_even_fields(&transaction)
+      .into_iter()
+      .map(|tags| tags.iter().map(hex::encode).collect::<Vec<String>>())
+      .collect::<Vec<Vec<String>>>();
+
+    if self.decompress {
+      if self.output.is_some() && inscriptions.len() != 1 {
+        bail!(
+          "--output needs exactly one inscription, but the transaction contains {}",
+          inscriptions.len()
+        );
+      }
+
+      let mut compact = Vec::new();
+
+      for (i, envelope) in inscriptions.into_iter().enumerate() {
+        let encoded_size = envelope.payload.content_length();
+
+        let body = envelope
+          .payload
+          .decompressed_body()
+          .with_context(|| format!("failed to decompress inscription {i}"))?;
+
+        if let (Some(output), Some(body)) = (&self.output, &body) {
+          fs::write(output, body)?;
+        }
 
-    if self.compact {
+        let mut inscription: CompactInscription = envelope.payload.try_into()?;
+        inscription.decoded_size = body.as_ref().map(Vec::len);
+        inscription.encoded_size = encoded_size;
+        inscription.body = body.map(hex::encode);
+        inscription.unrecognized_even_fields = unrecognized_even_fields[i].clone();
+
+        compact.push(inscription);
+      }
+
+      Ok(Box::new(CompactOutput {
+        inscriptions: compact,
+      }))
+    } else if self.compact {
       Ok(Box::new(CompactOutput {
         inscriptions: inscriptions
           .clone()
           .into_iter()
-          .map(|inscription| inscription.payload.try_into())
+          .zip(unrecognized_even_fields)
+          .map(|(inscription, unrecognized_even_fields)| -> Result<CompactInscription> {
+            Ok(CompactInscription {
+              unrecognized_even_fields,
+              ..CompactInscription::try_from(inscription.payload)?
+            })
+          })
           .collect::<Result<Vec<CompactInscription>>>()?,
       }))
     } else {
-      Ok(Box::new(RawOutput { inscriptions }))
+      Ok(Box::new(RawOutput {
+        inscriptions,
+        unrecognized_even_fields: unrecognized_even_fields
+          .into_iter()
+          .enumerate()
+          .filter(|(_i, tags)| !tags.is_empty())
+          .collect(),
+      }))
     }
   }
 }
@@ -0,0 +1,109 @@
+use {
+  super::*,
+  bitcoin::{
+    psbt::Psbt,
+    secp256k1::constants::SCHNORR_SIGNATURE_SIZE,
+    taproot::LeafVersion,
+    Witness,
+  },
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct DecodeRevealPsbt {
+  #[arg(help = "Decode base64 reveal <PSBT>.")]
+  psbt: String,
+}
+
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+pub struct Output {
+  pub inscriptions: Vec<RevealInscription>,
+  pub outputs: Vec<RevealOutput>,
+}
+
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+pub struct RevealInscription {
+  pub input: usize,
+  pub body_size: Option<usize>,
+  pub content_encoding: Option<String>,
+  pub content_type: Option<String>,
+  pub delegate: Option<InscriptionId>,
+  pub metaprotocol: Option<String>,
+  pub parent: Option<InscriptionId>,
+  pub pointer: Option<u64>,
+}
+
+#[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+pub struct RevealOutput {
+  pub address: Option<String>,
+  pub script_pubkey: String,
+  pub value: u64,
+}
+
+impl DecodeRevealPsbt {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let psbt = Psbt::from_str(&self.psbt).map_err(|e| anyhow!("bad reveal PSBT: {e}"))?;
+
+    let mut transaction = psbt.unsigned_tx.clone();
+
+    // a signed input carries the reveal script in its final witness, an unsigned one in its tapscript leaves
+    for (txin, input) in transaction.input.iter_mut().zip(&psbt.inputs) {
+      if let Some(witness) = &input.final_script_witness {
+        txin.witness = witness.clone();
+      } else if let Some((control_block, (script, _leaf_version))) = input
+        .tap_scripts
+        .iter()
+        .find(|(_control_block, (_script, leaf_version))| *leaf_version == LeafVersion::TapScript)
+      {
+        txin.witness = Witness::from_slice(&[
+          &[0; SCHNORR_SIGNATURE_SIZE][..],
+          script.as_bytes(),
+          &control_block.serialize(),
+        ]);
+      }
+    }
+
+    let inscriptions = ParsedEnvelope::from_transaction(&transaction, false)
+      .into_iter()
+      .map(|envelope| {
+        Ok(RevealInscription {
+          input: envelope.input.try_into().unwrap(),
+          body_size: envelope.payload.body().map(<[u8]>::len),
+          content_encoding: envelope
+            .payload
+            .content_encoding()
+            .map(|header_value| header_value.to_str().map(str::to_string))
+            .transpose()?,
+          content_type: envelope.payload.content_type().map(str::to_string),
+          delegate: envelope.payload.delegate(),
+          metaprotocol: envelope.payload.metaprotocol().map(str::to_string),
+          parent: envelope.payload.parent(),
+          pointer: envelope.payload.pointer(),
+        })
+      })
+      .collect::<Result<Vec<RevealInscription>>>()?;
+
+    if inscriptions.is_empty() {
+      bail!("reveal PSBT doesn't reveal any inscriptions");
+    }
+
+    let chain = options.chain();
+
+    let outputs = transaction
+      .output
+      .iter()
+      .map(|tx_out| RevealOutput {
+        address: chain
+          .address_from_script(&tx_out.script_pubkey)
+          .ok()
+          .map(|address| address.to_string()),
+        script_pubkey: hex::encode(tx_out.script_pubkey.as_bytes()),
+        value: tx_out.value,
+      })
+      .collect();
+
+    Ok(Box::new(Output {
+      inscriptions,
+      outputs,
+    }))
+  }
+}
@@ -86,6 +86,7 @@ impl Preview {
     super::wallet::create::Create {
       passphrase: "".into(),
       address_type: super::wallet::AddressType::Bech32m,
+      words: 12,
     }
     .run("ord".into(), options.clone())?;
 
@@ -106,10 +107,13 @@ impl Preview {
           subcommand: Subcommand::Wallet(super::wallet::Wallet {
             name: "ord".into(),
             subcommand: super::wallet::Subcommand::Inscribe(super::wallet::inscribe::Inscribe {
-              batch: None,
+              batch: Vec::new(),
               cbor_metadata: None,
               change: None,
               coin_control: false,
+              collection: None,
+              collect_errors: false,
+              content_type_map: None,
               commit_fee_rate: None,
               commit_input: Vec::new(),
               commit_only: false,
@@ -119,24 +123,48 @@ impl Preview {
               destination: None,
               dump: false,
               dry_run: false,
+              export_signed: None,
+              fee_bump_reveal: None,
+              change_value: None,
+              allow_dust_reveal_output: false,
+              reveal_change_target: None,
+              internal_key: None,
+              budget: None,
+              offline_reveal: false,
+              commit_outpoint: None,
+              commit_value: None,
+              double_check_fees: false,
               fee_rate: FeeRate::try_from(1.0).unwrap(),
               file: Some(file),
+              inspect_commit: None,
               json_metadata: None,
               key: None,
+              max_inscription_postage: None,
               metaprotocol: None,
+              metaprotocols: None,
               next_batch: None,
               next_file: None,
               no_backup: true,
               no_broadcast: false,
               no_limit: false,
               no_wallet: false,
+              test_accept: false,
+              emit_recovery: false,
+              only_common: false,
               parent: None,
               parent_satpoint: None,
               parent_destination: None,
+              parent_value: None,
               postage: Some(TARGET_POSTAGE),
+              recovery_key_origin: None,
               reinscribe: false,
+              resume_from: None,
               reveal_fee: None,
               reveal_input: Vec::new(),
+              reveal_input_value: Vec::new(),
+              reveal_input_script: Vec::new(),
+              reveal_input_sighash: None,
+              reveal_psbt_out: None,
               satpoint: None,
               sat: None,
               skip_pointer_for_none: false,
@@ -157,10 +185,13 @@ impl Preview {
           subcommand: Subcommand::Wallet(super::wallet::Wallet {
             name: "ord".into(),
             subcommand: super::wallet::Subcommand::Inscribe(super::wallet::inscribe::Inscribe {
-              batch: Some(batch),
+              batch: vec![batch],
               cbor_metadata: None,
               change: None,
               coin_control: false,
+              collection: None,
+              collect_errors: false,
+              content_type_map: None,
               commit_fee_rate: None,
               commit_input: Vec::new(),
               commit_only: false,
@@ -170,24 +201,48 @@ impl Preview {
               destination: None,
               dump: false,
               dry_run: false,
+              export_signed: None,
+              fee_bump_reveal: None,
+              change_value: None,
+              allow_dust_reveal_output: false,
+              reveal_change_target: None,
+              internal_key: None,
+              budget: None,
+              offline_reveal: false,
+              commit_outpoint: None,
+              commit_value: None,
+              double_check_fees: false,
               fee_rate: FeeRate::try_from(1.0).unwrap(),
               file: None,
+              inspect_commit: None,
               json_metadata: None,
               key: None,
+              max_inscription_postage: None,
               metaprotocol: None,
+              metaprotocols: None,
               next_batch: None,
               next_file: None,
               no_backup: true,
               no_broadcast: false,
               no_limit: false,
               no_wallet: false,
+              test_accept: false,
+              emit_recovery: false,
+              only_common: false,
               parent: None,
               parent_destination: None,
               parent_satpoint: None,
+              parent_value: None,
               postage: Some(TARGET_POSTAGE),
+              recovery_key_origin: None,
               reinscribe: false,
+              resume_from: None,
               reveal_fee: None,
               reveal_input: Vec::new(),
+              reveal_input_value: Vec::new(),
+              reveal_input_script: Vec::new(),
+              reveal_input_sighash: None,
+              reveal_psbt_out: None,
               satpoint: None,
               sat: None,
               skip_pointer_for_none: false,
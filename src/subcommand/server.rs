@@ -16,7 +16,8 @@ use {
       PreviewAudioHtml, PreviewCodeHtml, PreviewFontHtml, PreviewImageHtml, PreviewMarkdownHtml,
       PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml, PreviewVideoHtml,
       RangeHtml, RareTxt, RuneHtml, RuneJson, RunesHtml, RunesJson, SatHtml, SatInscriptionJson,
-      SatInscriptionsJson, SatJson, TransactionHtml,
+      SatInscriptionsJson, SatJson, TransactionHtml, TransferJson, TransfersHtml, TransfersJson,
+      BlockTransfersJson,
     },
   },
   axum::{
@@ -49,6 +50,7 @@ use {
 mod accept_encoding;
 mod accept_json;
 mod error;
+mod nostr;
 
 #[derive(Serialize)]
 pub struct Outputs {
@@ -117,6 +119,16 @@ struct Search {
   query: String,
 }
 
+// the most blocks `/transfers/blocks/:from/:to` returns at once
+const MAX_TRANSFER_RANGE_BLOCKS: u32 = 100;
+
+// `?address=` keeps transfers whose inscription is now held by that address. The index only records which
+// inscriptions moved in a block, not where they moved from, so senders can't be matched.
+#[derive(Deserialize)]
+struct TransfersQuery {
+  address: Option<String>,
+}
+
 #[derive(Serialize)]
 struct MyInscriptionJson {
   number: i32,
@@ -239,6 +251,11 @@ pub(crate) struct Server {
   pub(crate) decompress: bool,
   #[arg(long, alias = "nosync", help = "Do not update the index.")]
   no_sync: bool,
+  #[arg(
+    long,
+    help = "Sign the Nostr events served by `/transfers/<HEIGHT>/nostr` with hex secret key <NOSTR_KEY>."
+  )]
+  nostr_key: Option<bitcoin::secp256k1::SecretKey>,
 }
 
 impl Server {
@@ -269,6 +286,7 @@ impl Server {
         index_sats: index.has_sat_index(),
         is_json_api_enabled: self.enable_json_api,
         decompress: self.decompress,
+        nostr_key: self.nostr_key,
       });
 
       let router = Router::new()
@@ -359,8 +377,13 @@ impl Server {
         .route("/stats", get(Self::stats))
         .route("/status", get(Self::status))
         .route("/transfers/:height", get(Self::inscriptionids_from_height))
+        .route("/transfers/:height/nostr", get(Self::transfers_nostr))
         .route("/transfers/:height/:start", get(Self::inscriptionids_from_height_start))
         .route("/transfers/:height/:start/:end", get(Self::inscriptionids_from_height_start_end))
+        .route("/transfers/block/:height", get(Self::transfers_in_block))
+        .route("/transfers/block/:height/csv", get(Self::transfers_in_block_csv))
+        .route("/transfers/block/:height/:page", get(Self::transfers_in_block_paginated))
+        .route("/transfers/blocks/:from/:to", get(Self::transfers_in_blocks))
         .route("/tx/:txid", get(Self::transaction))
         .layer(Extension(index))
         .layer(Extension(server_config.clone()))
@@ -1064,6 +1087,245 @@ impl Server {
     }
   }
 
+  async fn transfers_in_block(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u32>,
+    Query(query): Query<TransfersQuery>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult<Response> {
+    Self::transfers_in_block_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((height, 0)),
+      Query(query),
+      AcceptJson(accept_json),
+    )
+    .await
+  }
+
+  async fn transfers_in_block_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((height, page_index)): Path<(u32, u32)>,
+    Query(query): Query<TransfersQuery>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      log::info!("GET /transfers/block/{height}/{page_index}");
+      let page_size = 100;
+
+      let inscription_ids = index.get_inscription_ids_by_height(height)?;
+
+      let mut tx_cache = HashMap::new();
+      let mut transfer = |inscription_id: InscriptionId| {
+        Self::transfer_json(server_config.chain, &index, inscription_id, &mut tx_cache)
+      };
+
+      let page_index_usize = usize::try_from(page_index).unwrap_or(usize::MAX);
+      let start = page_index_usize.saturating_mul(page_size);
+
+      // the filter needs every address in the block, so only then are they all looked up
+      let (total, transfers) = match &query.address {
+        Some(address) => {
+          let mut matching = Vec::new();
+          for inscription_id in inscription_ids {
+            let entry = transfer(inscription_id)?;
+            if entry.address == *address {
+              matching.push(entry);
+            }
+          }
+          (
+            matching.len(),
+            matching.into_iter().skip(start).take(page_size).collect(),
+          )
+        }
+        None => (
+          inscription_ids.len(),
+          inscription_ids
+            .into_iter()
+            .skip(start)
+            .take(page_size)
+            .map(&mut transfer)
+            .collect::<ServerResult<Vec<TransferJson>>>()?,
+        ),
+      };
+
+      if page_index > 0 && start >= total {
+        return Err(ServerError::NotFound(format!(
+          "page {page_index} of transfers in block {height} not found"
+        )));
+      }
+
+      let more = start.saturating_add(page_size) < total;
+
+      Ok(if accept_json {
+        Json(TransfersJson {
+          height,
+          transfers,
+          total,
+          more,
+          page_index,
+        })
+        .into_response()
+      } else {
+        TransfersHtml::new(height, query.address, transfers, more, page_index)
+          .page(server_config)
+          .into_response()
+      })
+    })
+  }
+
+  async fn transfers_in_block_csv(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u32>,
+    Query(query): Query<TransfersQuery>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      log::info!("GET /transfers/block/{height}/csv");
+
+      let mut tx_cache = HashMap::new();
+      let mut transfers = Vec::new();
+      for inscription_id in index.get_inscription_ids_by_height(height)? {
+        let transfer = Self::transfer_json(server_config.chain, &index, inscription_id, &mut tx_cache)?;
+        if query.address.as_ref().map_or(true, |address| transfer.address == *address) {
+          transfers.push(transfer);
+        }
+      }
+
+      Ok(
+        (
+          [(header::CONTENT_TYPE, "text/csv")],
+          TransfersJson {
+            height,
+            total: transfers.len(),
+            transfers,
+            more: false,
+            page_index: 0,
+          }
+          .to_csv(),
+        )
+          .into_response(),
+      )
+    })
+  }
+
+  async fn transfers_in_blocks(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((from, to)): Path<(u32, u32)>,
+    Query(query): Query<TransfersQuery>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      log::info!("GET /transfers/blocks/{from}/{to}");
+
+      if from > to {
+        return Err(ServerError::BadRequest(format!(
+          "range start {from} is after range end {to}"
+        )));
+      }
+
+      let blocks = to - from + 1;
+
+      if blocks > MAX_TRANSFER_RANGE_BLOCKS {
+        return Err(ServerError::BadRequest(format!(
+          "range of {blocks} blocks is more than the maximum of {MAX_TRANSFER_RANGE_BLOCKS}"
+        )));
+      }
+
+      let tip = index.block_height()?.ok_or_not_found(|| "genesis block")?;
+
+      if to > tip.n() {
+        return Err(ServerError::NotFound(format!("block {to} not found")));
+      }
+
+      // each block is serialized as soon as it's read, so only one block's transfers are ever held at once, and
+      // blocks without any transfers are still listed, so that gaps are explicit
+      let mut tx_cache = HashMap::new();
+      let mut body = String::from("[");
+
+      for height in from..=to {
+        let mut transfers = Vec::new();
+        for inscription_id in index.get_inscription_ids_by_height(height)? {
+          let transfer = Self::transfer_json(server_config.chain, &index, inscription_id, &mut tx_cache)?;
+          if query.address.as_ref().map_or(true, |address| transfer.address == *address) {
+            transfers.push(transfer);
+          }
+        }
+
+        if height > from {
+          body.push(',');
+        }
+
+        body.push_str(&serde_json::to_string(&BlockTransfersJson { height, transfers }).map_err(|err| anyhow!(err))?);
+
+        tx_cache.clear();
+      }
+
+      body.push(']');
+
+      Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response())
+    })
+  }
+
+  fn transfer_json(
+    chain: Chain,
+    index: &Arc<Index>,
+    inscription_id: InscriptionId,
+    tx_cache: &mut HashMap<Txid, Transaction>,
+  ) -> ServerResult<TransferJson> {
+    let satpoint = index
+      .get_inscription_satpoint_by_id(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    Ok(TransferJson {
+      inscription_id,
+      address: Self::outpoint_to_address(chain, index, satpoint.outpoint, tx_cache)?,
+      satpoint,
+    })
+  }
+
+  async fn transfers_nostr(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u32>,
+  ) -> ServerResult<String> {
+    log::info!("GET /transfers/{height}/nostr");
+
+    let secret_key = server_config
+      .nostr_key
+      .ok_or_not_found(|| "nostr key; start the server with --nostr-key")?;
+
+    task::block_in_place(|| {
+      let created_at = index
+        .get_block_by_height(height)?
+        .ok_or_not_found(|| format!("block {height}"))?
+        .header
+        .time
+        .into();
+
+      let mut ret = String::from("");
+      let mut tx_cache = HashMap::new();
+      for inscription_id in index.get_inscription_ids_by_height(height)? {
+        let satpoint = index
+          .get_inscription_satpoint_by_id(inscription_id)?
+          .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+        let transfer = nostr::Transfer {
+          address: Self::outpoint_to_address(server_config.chain, &index, satpoint.outpoint, &mut tx_cache)?,
+          height,
+          inscription_id,
+        };
+
+        ret += &serde_json::to_string(&nostr::Event::transfer(&secret_key, created_at, &transfer)).unwrap();
+        ret += "\n";
+      }
+
+      Ok(ret)
+    })
+  }
+
   async fn inscriptionids_from_height_inner(
     chain: Chain,
     index: Arc<Index>,
@@ -5663,6 +5925,161 @@ next
     );
   }
 
+  #[test]
+  fn transfers_in_blocks() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--index-transfers"],
+      &["--enable-json-api"],
+    );
+
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      ..Default::default()
+    });
+
+    server.mine_blocks(2);
+
+    let blocks = server.get_json::<Vec<BlockTransfersJson>>("/transfers/blocks/1/3");
+
+    assert_eq!(
+      blocks.iter().map(|block| block.height).collect::<Vec<u32>>(),
+      [1, 2, 3]
+    );
+    assert!(blocks[0].transfers.is_empty());
+    assert_eq!(
+      blocks[1]
+        .transfers
+        .iter()
+        .map(|transfer| transfer.inscription_id)
+        .collect::<Vec<InscriptionId>>(),
+      [InscriptionId { txid, index: 0 }]
+    );
+    assert!(blocks[2].transfers.is_empty());
+
+    server.assert_response(
+      "/transfers/blocks/3/1",
+      StatusCode::BAD_REQUEST,
+      "range start 3 is after range end 1",
+    );
+
+    server.assert_response(
+      "/transfers/blocks/0/100",
+      StatusCode::BAD_REQUEST,
+      "range of 101 blocks is more than the maximum of 100",
+    );
+
+    server.assert_response("/transfers/blocks/1/4", StatusCode::NOT_FOUND, "block 4 not found");
+  }
+
+  #[test]
+  fn transfers_in_block_page() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--index-transfers"],
+      &["--enable-json-api"],
+    );
+
+    for _ in 0..101 {
+      server.mine_blocks(1);
+    }
+
+    let mut inscription_ids = Vec::new();
+
+    for i in 0..101 {
+      let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, inscription("text/foo", "hello").to_witness())],
+        ..Default::default()
+      });
+      inscription_ids.push(InscriptionId { txid, index: 0 });
+    }
+
+    server.mine_blocks(1);
+
+    let first = server.get_json::<TransfersJson>("/transfers/block/102");
+
+    assert_eq!(first.total, 101);
+    assert_eq!(first.transfers.len(), 100);
+    assert!(first.more);
+
+    let second = server.get_json::<TransfersJson>("/transfers/block/102/1");
+
+    assert_eq!(second.total, 101);
+    assert_eq!(second.transfers.len(), 1);
+    assert!(!second.more);
+    assert_eq!(second.page_index, 1);
+
+    let mut returned = first
+      .transfers
+      .iter()
+      .chain(&second.transfers)
+      .map(|transfer| transfer.inscription_id)
+      .collect::<Vec<InscriptionId>>();
+
+    returned.sort();
+    inscription_ids.sort();
+
+    assert_eq!(returned, inscription_ids);
+
+    server.assert_response_regex(
+      "/transfers/block/102/1",
+      StatusCode::OK,
+      r".*<a class=prev href=/transfers/block/102/0>prev</a>.*",
+    );
+
+    let address = second.transfers[0].address.clone();
+
+    let filtered = server.get_json::<TransfersJson>(format!("/transfers/block/102?address={address}"));
+
+    assert!(filtered.total > 0);
+    assert!(filtered
+      .transfers
+      .iter()
+      .all(|transfer| transfer.address == address));
+
+    assert_eq!(
+      server
+        .get_json::<TransfersJson>("/transfers/block/102?address=bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+        .total,
+      0
+    );
+
+    server.assert_response_regex(
+      "/transfers/block/102?address=bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      StatusCode::OK,
+      r".*<title>Transfers in Block 102 for bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</title>.*",
+    );
+
+    let csv = server.get("/transfers/block/102/csv");
+    assert_eq!(csv.status(), StatusCode::OK);
+    assert_eq!(csv.headers().get(header::CONTENT_TYPE).unwrap(), "text/csv");
+
+    let csv = csv.text().unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("inscription_id,address,satpoint"));
+    assert_eq!(lines.count(), 101);
+
+    let transfer = &second.transfers[0];
+    assert!(csv.contains(&format!(
+      "{},{},{}\n",
+      transfer.inscription_id, transfer.address, transfer.satpoint
+    )));
+
+    server.assert_response(
+      "/transfers/block/102/2",
+      StatusCode::NOT_FOUND,
+      "page 2 of transfers in block 102 not found",
+    );
+  }
+
   #[test]
   fn inscription_query_display() {
     assert_eq!(
@@ -0,0 +1,112 @@
+use {
+  super::*,
+  bitcoin::{
+    hashes::sha256,
+    secp256k1::{self, KeyPair, Secp256k1, SecretKey, XOnlyPublicKey},
+  },
+};
+
+// NIP-01 short text note, so relays and clients that know nothing about ordinals still accept and show these
+const KIND: u64 = 1;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct Transfer {
+  pub(crate) address: String,
+  pub(crate) height: u32,
+  pub(crate) inscription_id: InscriptionId,
+}
+
+/// A signed Nostr event, as described in NIP-01.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct Event {
+  pub(crate) id: String,
+  pub(crate) pubkey: String,
+  pub(crate) created_at: u64,
+  pub(crate) kind: u64,
+  pub(crate) tags: Vec<Vec<String>>,
+  pub(crate) content: String,
+  pub(crate) sig: String,
+}
+
+impl Event {
+  pub(crate) fn transfer(secret_key: &SecretKey, created_at: u64, transfer: &Transfer) -> Self {
+    Self::sign(
+      secret_key,
+      created_at,
+      vec![
+        vec!["t".into(), "ordinals".into()],
+        vec!["inscriptionid".into(), transfer.inscription_id.to_string()],
+        vec!["address".into(), transfer.address.clone()],
+      ],
+      serde_json::to_string(transfer).unwrap(),
+    )
+  }
+
+  fn sign(secret_key: &SecretKey, created_at: u64, tags: Vec<Vec<String>>, content: String) -> Self {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = KeyPair::from_secret_key(&secp256k1, secret_key);
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+    let pubkey = hex::encode(public_key.serialize());
+
+    let id = Self::id(&pubkey, created_at, KIND, &tags, &content);
+
+    let sig = secp256k1.sign_schnorr_no_aux_rand(
+      &secp256k1::Message::from_slice(id.as_ref()).unwrap(),
+      &key_pair,
+    );
+
+    Self {
+      id: hex::encode(id),
+      pubkey,
+      created_at,
+      kind: KIND,
+      tags,
+      content,
+      sig: hex::encode(sig.as_ref()),
+    }
+  }
+
+  fn id(pubkey: &str, created_at: u64, kind: u64, tags: &[Vec<String>], content: &str) -> sha256::Hash {
+    sha256::Hash::hash(
+      serde_json::to_string(&serde_json::json!([0, pubkey, created_at, kind, tags, content]))
+        .unwrap()
+        .as_bytes(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::secp256k1::schnorr::Signature};
+
+  #[test]
+  fn transfer_event_is_signed() {
+    let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+
+    let transfer = Transfer {
+      address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+      height: 100,
+      inscription_id: inscription_id(1),
+    };
+
+    let event = Event::transfer(&secret_key, 1_700_000_000, &transfer);
+
+    assert_eq!(event.kind, 1);
+    assert_eq!(serde_json::from_str::<Transfer>(&event.content).unwrap(), transfer);
+    assert_eq!(
+      event.tags[1],
+      vec!["inscriptionid".to_string(), inscription_id(1).to_string()]
+    );
+
+    let id = Event::id(&event.pubkey, event.created_at, event.kind, &event.tags, &event.content);
+    assert_eq!(event.id, hex::encode(id));
+
+    Secp256k1::new()
+      .verify_schnorr(
+        &Signature::from_slice(&hex::decode(&event.sig).unwrap()).unwrap(),
+        &secp256k1::Message::from_slice(id.as_ref()).unwrap(),
+        &XOnlyPublicKey::from_slice(&hex::decode(&event.pubkey).unwrap()).unwrap(),
+      )
+      .unwrap();
+  }
+}
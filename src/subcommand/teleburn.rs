@@ -1,25 +1,77 @@
-use {super::*, crate::index::entry::Entry};
-use base58::ToBase58;
+use {
+  super::*,
+  base58::{FromBase58, ToBase58},
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Teleburn {
-@@ -8,11 +9,15 @@ pub(crate) struct Teleburn {
-#[derive(Debug, PartialEq, Serialize)]
+  #[arg(
+    required_unless_present_any = &["block", "file"],
+    conflicts_with_all = &["block", "file"],
+    help = "Generate teleburn addresses for inscription <RECIPIENT>."
+  )]
+  recipient: Option<InscriptionId>,
+  #[arg(
+    long,
+    conflicts_with = "file",
+    help = "Generate teleburn addresses for every inscription created in block <BLOCK>."
+  )]
+  block: Option<u32>,
+  #[arg(
+    long,
+    help = "Generate teleburn addresses for every inscription listed in <FILE>, one inscription ID per line."
+  )]
+  file: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Output {
-  ethereum: EthereumTeleburnAddress,
-  solana: SolanaTeleburnAddress,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub inscription: Option<InscriptionId>,
+  pub ethereum: teleburn::Ethereum,
+  pub solana: SolanaTeleburnAddress,
+  pub tron: TronTeleburnAddress,
+  pub dogecoin: DogecoinTeleburnAddress,
 }
 
-#[derive(Debug, PartialEq)]
-struct EthereumTeleburnAddress([u8; 20]);
+impl From<InscriptionId> for Output {
+  fn from(inscription_id: InscriptionId) -> Self {
+    Self {
+      inscription: None,
+      ethereum: inscription_id.into(),
+      solana: inscription_id.into(),
+      tron: inscription_id.into(),
+      dogecoin: inscription_id.into(),
+    }
+  }
+}
+
+impl Output {
+  /// An entry in `--block` and `--file` listings, which name the inscription each entry is for.
+  fn listed(inscription_id: InscriptionId) -> Self {
+    Self {
+      inscription: Some(inscription_id),
+      ..inscription_id.into()
+    }
+  }
+}
 
 #[derive(Debug, PartialEq)]
-struct SolanaTeleburnAddress([u8; 32]);
+pub struct SolanaTeleburnAddress([u8; 32]);
 
-impl Serialize for EthereumTeleburnAddress {
-  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-  where
-@@ -34,11 +39,29 @@ impl Display for EthereumTeleburnAddress {
+/// The sha256 of an inscription ID's 32 txid bytes followed by its big-endian
+/// index, from which every teleburn address is taken.
+fn digest(inscription_id: InscriptionId) -> bitcoin::hashes::sha256::Hash {
+  let mut array = [0; 36];
+  let (txid, index) = array.split_at_mut(32);
+  txid.copy_from_slice(inscription_id.txid.as_ref());
+  index.copy_from_slice(&inscription_id.index.to_be_bytes());
+  bitcoin::hashes::sha256::Hash::hash(&array)
+}
+
+impl From<InscriptionId> for SolanaTeleburnAddress {
+  fn from(inscription_id: InscriptionId) -> Self {
+    Self(digest(inscription_id)[0..32].try_into().unwrap())
   }
 }
 
@@ -32,6 +84,29 @@ impl Serialize for SolanaTeleburnAddress {
   }
 }
 
+impl<'de> Deserialize<'de> for SolanaTeleburnAddress {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Ok(DeserializeFromStr::deserialize(deserializer)?.0)
+  }
+}
+
+impl FromStr for SolanaTeleburnAddress {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let bytes = s
+      .from_base58()
+      .map_err(|err| anyhow!("invalid solana teleburn address `{s}`: {err:?}"))?;
+
+    Ok(Self(bytes.try_into().map_err(|bytes: Vec<u8>| {
+      anyhow!("solana teleburn address `{s}` is {} bytes, not 32", bytes.len())
+    })?))
+  }
+}
+
 impl Display for SolanaTeleburnAddress {
   fn fmt(&self, f: &mut Formatter) -> fmt::Result {
     write!(f, "{}", self.0.to_base58())?;
@@ -40,12 +115,166 @@ impl Display for SolanaTeleburnAddress {
   }
 }
 
+/// Decode a Base58Check address made of `version` followed by a 20 byte
+/// payload, as used by Tron and Dogecoin.
+fn decode_versioned(s: &str, version: u8, chain: &str) -> Result<[u8; 20]> {
+  let bytes = bitcoin::base58::decode_check(s)
+    .map_err(|err| anyhow!("invalid {chain} teleburn address `{s}`: {err}"))?;
+
+  if bytes.len() != 21 {
+    bail!("{chain} teleburn address `{s}` is {} bytes, not 21", bytes.len());
+  }
+
+  if bytes[0] != version {
+    bail!(
+      "{chain} teleburn address `{s}` has version byte {:#04x}, not {version:#04x}",
+      bytes[0]
+    );
+  }
+
+  Ok(bytes[1..].try_into().unwrap())
+}
+
+fn encode_versioned(f: &mut Formatter, version: u8, payload: &[u8; 20]) -> fmt::Result {
+  let mut bytes = [0; 21];
+  bytes[0] = version;
+  bytes[1..].copy_from_slice(payload);
+  bitcoin::base58::encode_check_to_fmt(f, &bytes)
+}
+
+/// A Tron address: the `0x41` mainnet prefix followed by the first 20 bytes of
+/// the digest, Base58Check encoded.
+#[derive(Debug, PartialEq)]
+pub struct TronTeleburnAddress([u8; 20]);
+
+impl TronTeleburnAddress {
+  const VERSION: u8 = 0x41;
+}
+
+impl From<InscriptionId> for TronTeleburnAddress {
+  fn from(inscription_id: InscriptionId) -> Self {
+    Self(digest(inscription_id)[0..20].try_into().unwrap())
+  }
+}
+
+impl Serialize for TronTeleburnAddress {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for TronTeleburnAddress {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Ok(DeserializeFromStr::deserialize(deserializer)?.0)
+  }
+}
+
+impl FromStr for TronTeleburnAddress {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Self(decode_versioned(s, Self::VERSION, "tron")?))
+  }
+}
+
+impl Display for TronTeleburnAddress {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    encode_versioned(f, Self::VERSION, &self.0)
+  }
+}
+
+/// A Dogecoin P2PKH address: the `0x1e` mainnet version byte followed by the
+/// first 20 bytes of the digest in place of a hash160, Base58Check encoded.
+#[derive(Debug, PartialEq)]
+pub struct DogecoinTeleburnAddress([u8; 20]);
+
+impl DogecoinTeleburnAddress {
+  const VERSION: u8 = 0x1e;
+}
+
+impl From<InscriptionId> for DogecoinTeleburnAddress {
+  fn from(inscription_id: InscriptionId) -> Self {
+    Self(digest(inscription_id)[0..20].try_into().unwrap())
+  }
+}
+
+impl Serialize for DogecoinTeleburnAddress {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for DogecoinTeleburnAddress {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Ok(DeserializeFromStr::deserialize(deserializer)?.0)
+  }
+}
+
+impl FromStr for DogecoinTeleburnAddress {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Self(decode_versioned(s, Self::VERSION, "dogecoin")?))
+  }
+}
+
+impl Display for DogecoinTeleburnAddress {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    encode_versioned(f, Self::VERSION, &self.0)
+  }
+}
+
 impl Teleburn {
-  pub(crate) fn run(self) -> Result {
-    let digest = bitcoin::hashes::sha256::Hash::hash(&self.recipient.store());
-    print_json(Output {
-      ethereum: EthereumTeleburnAddress(digest[0..20].try_into().unwrap()),
-      solana: SolanaTeleburnAddress(digest[0..32].try_into().unwrap()),
-    })?;
-    Ok(())
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    if let Some(height) = self.block {
+      let index = Index::open(&options)?;
+      index.update()?;
+
+      return Ok(Box::new(
+        index
+          .get_inscriptions_in_block(height)?
+          .into_iter()
+          .map(Output::listed)
+          .collect::<Vec<Output>>(),
+      ));
+    }
+
+    if let Some(path) = self.file {
+      let mut output = Vec::new();
+
+      for (i, line) in fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?
+        .lines()
+        .enumerate()
+      {
+        let line = line.trim();
+
+        if line.is_empty() {
+          continue;
+        }
+
+        let inscription = line
+          .parse::<InscriptionId>()
+          .with_context(|| format!("invalid inscription ID on line {}: `{line}`", i + 1))?;
+
+        output.push(Output::listed(inscription));
+      }
+
+      return Ok(Box::new(output));
+    }
+
+    Ok(Box::new(Output::from(self.recipient.unwrap())))
   }
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct VerifyContent {
+  #[arg(long, help = "Check the content of inscription <INSCRIPTION>.")]
+  inscription: InscriptionId,
+  #[arg(long, help = "Compare the inscription's content against <FILE>.")]
+  file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub inscription: InscriptionId,
+  pub matches: bool,
+  pub content_size: usize,
+  pub file_size: usize,
+  pub first_difference: Option<usize>,
+}
+
+impl VerifyContent {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let mut inscription = index
+      .get_inscription_by_id(self.inscription)?
+      .ok_or_else(|| anyhow!("inscription {} not found", self.inscription))?;
+
+    // the explorer serves a delegate's content in place of the inscription's own, so do the same here
+    if let Some(delegate) = inscription.delegate() {
+      inscription = index
+        .get_inscription_by_id(delegate)?
+        .ok_or_else(|| anyhow!("delegate {delegate} of inscription {} not found", self.inscription))?;
+    }
+
+    let content = inscription
+      .decompressed_body()
+      .with_context(|| format!("failed to decompress inscription {}", self.inscription))?
+      .ok_or_else(|| anyhow!("inscription {} has no content", self.inscription))?;
+
+    let file = fs::read(&self.file)
+      .with_context(|| format!("failed to read {}", self.file.display()))?;
+
+    let first_difference = content
+      .iter()
+      .zip(&file)
+      .position(|(a, b)| a != b)
+      .or_else(|| (content.len() != file.len()).then(|| content.len().min(file.len())));
+
+    Ok(Box::new(Output {
+      inscription: self.inscription,
+      matches: first_difference.is_none(),
+      content_size: content.len(),
+      file_size: file.len(),
+      first_difference,
+    }))
+  }
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct VerifyDelegate {
+  #[arg(help = "Check the delegate of inscription <INSCRIPTION>.")]
+  inscription: InscriptionId,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub inscription: InscriptionId,
+  pub delegate: InscriptionId,
+  pub exists: bool,
+  pub content_type: Option<String>,
+  pub content_length: Option<usize>,
+}
+
+impl VerifyDelegate {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let inscription = index
+      .get_inscription_by_id(self.inscription)?
+      .ok_or_else(|| anyhow!("inscription {} not found", self.inscription))?;
+
+    if inscription.delegate.is_none() {
+      bail!("inscription {} has no delegate", self.inscription);
+    }
+
+    let delegate = inscription
+      .delegate()
+      .ok_or_else(|| anyhow!("inscription {} has a malformed delegate tag", self.inscription))?;
+
+    // a dangling delegate points at an inscription the index has never seen
+    let target = index.get_inscription_by_id(delegate)?;
+
+    Ok(Box::new(Output {
+      inscription: self.inscription,
+      delegate,
+      exists: target.is_some(),
+      content_type: target
+        .as_ref()
+        .and_then(|target| target.content_type())
+        .map(str::to_string),
+      content_length: target.as_ref().and_then(|target| target.content_length()),
+    }))
+  }
+}
@@ -6,28 +6,42 @@ use {
   },
   bitcoin::{
     bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, Fingerprint},
+    sighash::{EcdsaSighashType, TapSighashType},
     Network,
   },
-  bitcoincore_rpc::bitcoincore_rpc_json::{ImportDescriptors, Timestamp},
+  bitcoincore_rpc::bitcoincore_rpc_json::{
+    ImportDescriptors, SigHashType, SignRawTransactionInput, Timestamp,
+  },
   fee_rate::FeeRate,
   miniscript::descriptor::{Descriptor, DescriptorSecretKey, DescriptorXKey, Wildcard},
   transaction_builder::TransactionBuilder,
 };
 
 pub mod balance;
+pub mod broadcast_file;
+pub mod bump_commit;
 pub mod cardinals;
+pub mod change_addresses;
+pub mod consolidate_plan;
+pub mod content_type_histogram;
 pub mod create;
 pub mod etch;
 pub mod inscribe;
 pub mod inscriptions;
+pub mod migrate_from_ordinalswallet;
 pub mod outputs;
 pub mod receive;
 mod restore;
+pub mod runic_outputs;
 pub mod sats;
 pub mod send;
 pub mod sendmany;
+pub mod sendmany_sats;
+pub mod sweep_commits;
 pub mod transaction_builder;
 pub mod transactions;
+pub mod utxo_contents;
+pub mod watch;
 
 #[derive(Debug, Parser)]
 pub(crate) struct Wallet {
@@ -41,6 +55,16 @@ pub(crate) struct Wallet {
 pub(crate) enum Subcommand {
   #[command(about = "Get wallet balance")]
   Balance,
+  #[command(about = "Broadcast a commit and reveal exported with `wallet inscribe --export-signed`")]
+  BroadcastFile(broadcast_file::BroadcastFile),
+  #[command(about = "Replace a stuck commit transaction with one paying a higher fee, and re-sign its reveal")]
+  BumpCommit(bump_commit::BumpCommit),
+  #[command(about = "List the wallet's change addresses and whether they've been used")]
+  ChangeAddresses(change_addresses::ChangeAddresses),
+  #[command(about = "Plan sending the wallet's inscriptions to a few fresh addresses with `send-many`")]
+  ConsolidatePlan(consolidate_plan::ConsolidatePlan),
+  #[command(about = "Count wallet inscriptions by content type")]
+  ContentTypeHistogram,
   #[command(about = "Create new wallet")]
   Create(create::Create),
   #[command(about = "Create rune")]
@@ -49,18 +73,30 @@ pub(crate) enum Subcommand {
   Inscribe(inscribe::Inscribe),
   #[command(about = "List wallet inscriptions")]
   Inscriptions,
+  #[command(about = "Move everything in an ordinalswallet-restored wallet to a new standard wallet")]
+  MigrateFromOrdinalswallet(migrate_from_ordinalswallet::MigrateFromOrdinalswallet),
   #[command(about = "Generate receive address")]
   Receive,
   #[command(about = "Restore wallet")]
   Restore(restore::Restore),
+  #[command(about = "List unspent outputs in wallet that hold runes")]
+  RunicOutputs,
   #[command(about = "List wallet satoshis")]
   Sats(sats::Sats),
   #[command(about = "Send sat or inscription")]
   Send(send::Send),
   #[command(about = "Send multiple inscriptions in a single transaction")]
   SendMany(sendmany::SendMany),
+  #[command(about = "Send sats to multiple addresses in a single transaction")]
+  SendManySats(sendmany_sats::SendManySats),
+  #[command(about = "Sweep all unspent commit outputs to an address")]
+  SweepCommits(sweep_commits::SweepCommits),
   #[command(about = "See wallet transactions")]
   Transactions(transactions::Transactions),
+  #[command(about = "Show the inscriptions, runes, and value of an output")]
+  UtxoContents(utxo_contents::UtxoContents),
+  #[command(about = "Create a watch-only wallet from an account xpub or output descriptors")]
+  Watch(watch::Watch),
   #[command(about = "List all unspent outputs in wallet")]
   Outputs,
   #[command(about = "List unspent cardinal outputs in wallet")]
@@ -79,16 +115,27 @@ impl Wallet {
   pub(crate) fn run(self, options: Options) -> SubcommandResult {
     match self.subcommand {
       Subcommand::Balance => balance::run(self.name, options),
+      Subcommand::BroadcastFile(broadcast_file) => broadcast_file.run(self.name, options),
+      Subcommand::BumpCommit(bump_commit) => bump_commit.run(self.name, options),
+      Subcommand::ChangeAddresses(change_addresses) => change_addresses.run(self.name, options),
+      Subcommand::ConsolidatePlan(consolidate_plan) => consolidate_plan.run(self.name, options),
+      Subcommand::ContentTypeHistogram => content_type_histogram::run(self.name, options),
       Subcommand::Create(create) => create.run(self.name, options),
       Subcommand::Etch(etch) => etch.run(self.name, options),
       Subcommand::Inscribe(inscribe) => inscribe.run(self.name, options),
       Subcommand::Inscriptions => inscriptions::run(self.name, options),
+      Subcommand::MigrateFromOrdinalswallet(migrate) => migrate.run(self.name, options),
       Subcommand::Receive => receive::run(self.name, options),
       Subcommand::Restore(restore) => restore.run(self.name, options),
+      Subcommand::RunicOutputs => runic_outputs::run(self.name, options),
       Subcommand::Sats(sats) => sats.run(self.name, options),
       Subcommand::Send(send) => send.run(self.name, options),
       Subcommand::SendMany(sendmany) => sendmany.run(self.name, options),
+      Subcommand::SendManySats(sendmany_sats) => sendmany_sats.run(self.name, options),
+      Subcommand::SweepCommits(sweep_commits) => sweep_commits.run(self.name, options),
       Subcommand::Transactions(transactions) => transactions.run(self.name, options),
+      Subcommand::UtxoContents(utxo_contents) => utxo_contents.run(self.name, options),
+      Subcommand::Watch(watch) => watch.run(self.name, options),
       Subcommand::Outputs => outputs::run(self.name, options),
       Subcommand::Cardinals => cardinals::run(self.name, options),
     }
@@ -160,6 +207,109 @@ pub(crate) fn get_locked_outputs(client: &Client) -> Result<BTreeSet<OutPoint>>
   )
 }
 
+/// The cardinal utxos, skipping empty, locked, inscribed, and runic outputs, in
+/// outpoint order.
+pub(crate) fn cardinal_utxos(
+  unspent_outputs: &BTreeMap<OutPoint, Amount>,
+  locked_outputs: &BTreeSet<OutPoint>,
+  inscribed_outputs: &BTreeSet<OutPoint>,
+  runic_outputs: &BTreeSet<OutPoint>,
+) -> Result<Vec<(OutPoint, Amount)>> {
+  let cardinals = unspent_outputs
+    .iter()
+    .filter(|(outpoint, amount)| {
+      **amount > Amount::ZERO
+        && !locked_outputs.contains(outpoint)
+        && !inscribed_outputs.contains(outpoint)
+        && !runic_outputs.contains(outpoint)
+    })
+    .map(|(outpoint, amount)| (*outpoint, *amount))
+    .collect::<Vec<(OutPoint, Amount)>>();
+
+  if cardinals.is_empty() {
+    bail!("wallet contains no cardinal utxos");
+  }
+
+  Ok(cardinals)
+}
+
+/// Pick cardinal utxos until they hold at least `target`: the smallest that
+/// covers it on its own if there is one, and otherwise the biggest first.
+pub(crate) fn select_cardinal_utxos(
+  unspent_outputs: &BTreeMap<OutPoint, Amount>,
+  locked_outputs: &BTreeSet<OutPoint>,
+  inscribed_outputs: &BTreeSet<OutPoint>,
+  runic_outputs: &BTreeSet<OutPoint>,
+  target: Amount,
+) -> Result<Vec<(OutPoint, Amount)>> {
+  let mut cardinals = cardinal_utxos(unspent_outputs, locked_outputs, inscribed_outputs, runic_outputs)?;
+
+  // the sort is stable, so equal amounts stay in outpoint order
+  cardinals.sort_by_key(|(_outpoint, amount)| *amount);
+
+  if let Some(cardinal) = cardinals.iter().find(|(_outpoint, amount)| *amount >= target) {
+    return Ok(vec![*cardinal]);
+  }
+
+  let mut selected = Vec::new();
+  let mut selected_value = Amount::ZERO;
+
+  for cardinal in cardinals.iter().rev() {
+    selected.push(*cardinal);
+    selected_value += cardinal.1;
+
+    if selected_value >= target {
+      return Ok(selected);
+    }
+  }
+
+  bail!(
+    "wallet cardinals ({} sats) are too small to cover {} sats",
+    selected_value.to_sat(),
+    target.to_sat()
+  );
+}
+
+pub(crate) fn sign_transaction(
+  client: &Client,
+  tx: &Transaction,
+  utxos: Option<&[SignRawTransactionInput]>,
+) -> Result<Vec<u8>> {
+  sign_transaction_with_sighash(client, tx, utxos, TapSighashType::Default)
+}
+
+// bitcoind takes the sighash type by its ECDSA name and, for taproot inputs, signs with the default
+// type when it isn't given one
+pub(crate) fn sign_transaction_with_sighash(
+  client: &Client,
+  tx: &Transaction,
+  utxos: Option<&[SignRawTransactionInput]>,
+  sighash_type: TapSighashType,
+) -> Result<Vec<u8>> {
+  let sighash_type = match sighash_type {
+    TapSighashType::Default => None,
+    sighash_type => Some(SigHashType::from(
+      EcdsaSighashType::from_standard(sighash_type as u32)
+        .expect("every non-default taproot sighash type is a standard ECDSA one"),
+    )),
+  };
+
+  let signed = client.sign_raw_transaction_with_wallet(tx, utxos, sighash_type)?;
+
+  // a watch-only wallet doesn't fail to sign, it just leaves the transaction incomplete
+  if !signed.complete {
+    let info = client.get_wallet_info()?;
+    if !info.private_keys_enabled {
+      bail!(
+        "wallet \"{}\" is a watch-only wallet and can't sign transactions; use `--psbt` or `--no-wallet` to get an unsigned transaction to sign elsewhere",
+        info.wallet_name
+      );
+    }
+  }
+
+  Ok(signed.hex)
+}
+
 pub(crate) fn get_change_address(client: &Client, chain: Chain) -> Result<Address> {
   Ok(
     client
@@ -318,3 +468,122 @@ fn format_bitcoin_core_version(version: usize) -> String {
     version % 100
   )
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn unspent(amounts: &[u64]) -> BTreeMap<OutPoint, Amount> {
+    amounts
+      .iter()
+      .enumerate()
+      .map(|(i, amount)| (outpoint(u64::try_from(i).unwrap() + 1), Amount::from_sat(*amount)))
+      .collect()
+  }
+
+  #[test]
+  fn cardinal_utxos_keeps_outpoint_order() {
+    assert_eq!(
+      cardinal_utxos(
+        &unspent(&[5_000, 0, 1_000, 3_000]),
+        &[outpoint(3)].into(),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+      )
+      .unwrap(),
+      vec![
+        (outpoint(1), Amount::from_sat(5_000)),
+        (outpoint(4), Amount::from_sat(3_000)),
+      ],
+    );
+
+    assert_eq!(
+      cardinal_utxos(
+        &unspent(&[0]),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "wallet contains no cardinal utxos",
+    );
+  }
+
+  #[test]
+  fn select_cardinal_utxos_picks_smallest_exact_match() {
+    assert_eq!(
+      select_cardinal_utxos(
+        &unspent(&[5_000, 1_000, 3_000]),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        Amount::from_sat(3_000),
+      )
+      .unwrap(),
+      vec![(outpoint(3), Amount::from_sat(3_000))],
+    );
+  }
+
+  #[test]
+  fn select_cardinal_utxos_needs_multiple_inputs() {
+    assert_eq!(
+      select_cardinal_utxos(
+        &unspent(&[5_000, 1_000, 3_000]),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        Amount::from_sat(7_000),
+      )
+      .unwrap(),
+      vec![
+        (outpoint(1), Amount::from_sat(5_000)),
+        (outpoint(3), Amount::from_sat(3_000)),
+      ],
+    );
+  }
+
+  #[test]
+  fn select_cardinal_utxos_skips_excluded_outputs() {
+    assert_eq!(
+      select_cardinal_utxos(
+        &unspent(&[5_000, 4_000, 3_000, 2_000, 0]),
+        &[outpoint(1)].into(),
+        &[outpoint(2)].into(),
+        &[outpoint(3)].into(),
+        Amount::ZERO,
+      )
+      .unwrap(),
+      vec![(outpoint(4), Amount::from_sat(2_000))],
+    );
+  }
+
+  #[test]
+  fn select_cardinal_utxos_insufficient_funds() {
+    assert_eq!(
+      select_cardinal_utxos(
+        &unspent(&[5_000, 1_000]),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        Amount::from_sat(7_000),
+      )
+      .unwrap_err()
+      .to_string(),
+      "wallet cardinals (6000 sats) are too small to cover 7000 sats",
+    );
+
+    assert_eq!(
+      select_cardinal_utxos(
+        &unspent(&[5_000]),
+        &[outpoint(1)].into(),
+        &BTreeSet::new(),
+        &BTreeSet::new(),
+        Amount::ZERO,
+      )
+      .unwrap_err()
+      .to_string(),
+      "wallet contains no cardinal utxos",
+    );
+  }
+}
@@ -0,0 +1,15 @@
+use {super::*, inscribe::Inscribe};
+
+#[derive(Debug, Parser)]
+pub(crate) struct BroadcastFile {
+  #[arg(help = "Broadcast the commit and reveal txs in <PATH>, as written by `wallet inscribe --export-signed`.")]
+  path: PathBuf,
+}
+
+impl BroadcastFile {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+    Ok(Box::new(Inscribe::broadcast_exported(&self.path, &client)?))
+  }
+}
@@ -0,0 +1,274 @@
+use {
+  super::*,
+  bitcoin::{
+    blockdata::opcodes,
+    key::PrivateKey,
+    secp256k1::{self, XOnlyPublicKey},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot::{LeafVersion, Signature, TapLeafHash},
+  },
+  bitcoincore_rpc::bitcoincore_rpc_json::SignRawTransactionInput,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct BumpCommit {
+  #[arg(long, help = "Replace unconfirmed commit transaction <COMMIT>.")]
+  commit: Txid,
+  #[arg(long, help = "Re-sign reveal transaction <REVEAL>, which spends <COMMIT>, against the replacement commit.")]
+  reveal: Txid,
+  #[arg(long, help = "Sign the reveal script with <KEY>, the `--key` the commit was made with.")]
+  key: String,
+  #[arg(long, help = "Pay the replacement commit's fee at <FEE_RATE> sats/vB.")]
+  fee_rate: FeeRate,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub commit: Txid,
+  pub fee: u64,
+  pub inscriptions: Vec<InscriptionId>,
+  pub replaced_fee: u64,
+  pub reveal: Txid,
+}
+
+impl BumpCommit {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+    if client
+      .get_raw_transaction_info(&self.commit, None)?
+      .confirmations
+      .unwrap_or_default()
+      > 0
+    {
+      bail!("commit tx {} is already confirmed", self.commit);
+    }
+
+    let commit_tx = client.get_raw_transaction(&self.commit, None)?;
+    let reveal_tx = client.get_raw_transaction(&self.reveal, None)?;
+
+    if !commit_tx.is_explicitly_rbf() {
+      bail!("commit tx {} doesn't signal replaceability", self.commit);
+    }
+
+    let mut commit_inputs = reveal_tx
+      .input
+      .iter()
+      .enumerate()
+      .filter(|(_i, input)| input.previous_output.txid == self.commit)
+      .map(|(i, _input)| i);
+
+    let (Some(commit_input), None) = (commit_inputs.next(), commit_inputs.next()) else {
+      bail!(
+        "reveal tx {} must spend exactly one output of commit tx {}",
+        self.reveal,
+        self.commit
+      );
+    };
+
+    let commit_vout = reveal_tx.input[commit_input].previous_output.vout as usize;
+
+    let witness = &reveal_tx.input[commit_input].witness;
+
+    let (Some(reveal_script), Some(control_block)) = (witness.tapscript(), witness.last()) else {
+      bail!(
+        "reveal tx {} doesn't spend commit tx {} through its reveal script",
+        self.reveal,
+        self.commit
+      );
+    };
+
+    let reveal_script = reveal_script.to_owned();
+    let control_block = control_block.to_vec();
+
+    // keep whatever sighash type the reveal was signed with, so the re-signed reveal has the same vsize
+    let hash_ty = match witness.nth(0) {
+      Some(signature) if signature.len() == 65 => TapSighashType::from_consensus_u8(signature[64])?,
+      _ => TapSighashType::Default,
+    };
+
+    let secp256k1 = Secp256k1::new();
+    let key_pair = secp256k1::KeyPair::from_secret_key(&secp256k1, &PrivateKey::from_wif(&self.key)?.inner);
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    if !reveal_script.as_bytes().starts_with(
+      ScriptBuf::builder()
+        .push_slice(public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG)
+        .into_script()
+        .as_bytes(),
+    ) {
+      bail!("--key doesn't sign the reveal script of reveal tx {}", self.reveal);
+    }
+
+    let commit_prevouts = Self::prevouts(&client, &commit_tx)?;
+    let reveal_prevouts = Self::prevouts(&client, &reveal_tx)?;
+
+    let commit_fee = Self::fee(&commit_tx, &commit_prevouts)?;
+
+    // replacing the commit evicts the reveal along with it, so BIP125 has the replacement pay more than both
+    let replaced_fee = commit_fee + Self::fee(&reveal_tx, &reveal_prevouts)?;
+
+    // the replacement has the same inputs and outputs, and so the same vsize once signed
+    let fee = self.fee_rate.fee(commit_tx.vsize()).to_sat();
+
+    if fee <= replaced_fee {
+      bail!(
+        "--fee-rate {} sats/vB would pay {fee} sats, but the replacement must pay more than the {replaced_fee} sats paid by the commit and reveal it replaces",
+        self.fee_rate.n()
+      );
+    }
+
+    // BIP125 also has the replacement pay for relaying itself, at the node's incremental relay fee rate, which
+    // is given per 1000 vbytes
+    let incremental_relay_fee =
+      (client.get_network_info()?.incremental_fee.to_sat() * commit_tx.vsize() as u64 + 999) / 1000;
+
+    if fee - replaced_fee < incremental_relay_fee {
+      bail!(
+        "--fee-rate {} sats/vB would pay {fee} sats, only {} sats more than the {replaced_fee} sats it replaces, but the incremental relay fee for the {} vbyte replacement is {incremental_relay_fee} sats",
+        self.fee_rate.n(),
+        fee - replaced_fee,
+        commit_tx.vsize(),
+      );
+    }
+
+    // the reveal is signed over the commit output's value, so the extra fee comes out of the change, which
+    // `wallet inscribe` puts last
+    let change = commit_tx.output.len() - 1;
+
+    if change == commit_vout {
+      bail!("commit tx {} has no change output to pay a higher fee from", self.commit);
+    }
+
+    let extra_fee = fee - commit_fee;
+
+    let mut replacement = commit_tx.clone();
+
+    let dust_value = replacement.output[change].script_pubkey.dust_value().to_sat();
+
+    if replacement.output[change].value < extra_fee + dust_value {
+      bail!(
+        "commit change of {} sats can't pay {extra_fee} sats of extra fee and stay above its dust value of {dust_value} sats",
+        replacement.output[change].value
+      );
+    }
+
+    replacement.output[change].value -= extra_fee;
+
+    for input in &mut replacement.input {
+      input.witness = Witness::new();
+    }
+
+    let signed = client.sign_raw_transaction_with_wallet(&replacement, None, None)?;
+
+    if !signed.complete {
+      bail!("failed to sign replacement commit tx");
+    }
+
+    let replacement = consensus::encode::deserialize::<Transaction>(&signed.hex)?;
+
+    let mut reveal = reveal_tx.clone();
+
+    reveal.input[commit_input].previous_output.txid = replacement.txid();
+
+    let mut prevouts = reveal_prevouts;
+    prevouts[commit_input] = replacement.output[commit_vout].clone();
+
+    // any other reveal inputs, like parents, were signed over the old commit outpoint, so the wallet signs them again
+    if reveal.input.len() > 1 {
+      for input in &mut reveal.input {
+        input.witness = Witness::new();
+      }
+
+      let signed = client.sign_raw_transaction_with_wallet(
+        &reveal,
+        Some(&[SignRawTransactionInput {
+          txid: replacement.txid(),
+          vout: commit_vout.try_into().unwrap(),
+          script_pub_key: prevouts[commit_input].script_pubkey.clone(),
+          redeem_script: None,
+          amount: Some(Amount::from_sat(prevouts[commit_input].value)),
+        }]),
+        None,
+      )?;
+
+      reveal = consensus::encode::deserialize(&signed.hex)?;
+
+      if let Some(i) = (0..reveal.input.len()).find(|i| *i != commit_input && reveal.input[*i].witness.is_empty()) {
+        bail!("failed to re-sign reveal input {i}");
+      }
+    }
+
+    let mut sighash_cache = SighashCache::new(&mut reveal);
+
+    let sighash = sighash_cache.taproot_script_spend_signature_hash(
+      commit_input,
+      &Prevouts::All(&prevouts),
+      TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
+      hash_ty,
+    )?;
+
+    let sig = secp256k1.sign_schnorr(
+      &secp256k1::Message::from_slice(sighash.as_ref())
+        .expect("should be cryptographically secure hash"),
+      &key_pair,
+    );
+
+    let witness = sighash_cache
+      .witness_mut(commit_input)
+      .expect("getting mutable witness reference should work");
+
+    witness.clear();
+    witness.push(Signature { sig, hash_ty }.to_vec());
+    witness.push(&reveal_script);
+    witness.push(&control_block);
+
+    let inscriptions = ParsedEnvelope::from_transaction(&reveal, false).len();
+
+    let commit = client.send_raw_transaction(&replacement)?;
+
+    let reveal = client
+      .send_raw_transaction(&reveal)
+      .with_context(|| format!("replacement commit {commit} was broadcast, but its reveal wasn't"))?;
+
+    Ok(Box::new(Output {
+      commit,
+      fee,
+      inscriptions: (0..inscriptions)
+        .map(|index| InscriptionId {
+          txid: reveal,
+          index: index.try_into().unwrap(),
+        })
+        .collect(),
+      replaced_fee,
+      reveal,
+    }))
+  }
+
+  fn prevouts(client: &Client, tx: &Transaction) -> Result<Vec<TxOut>> {
+    tx.input
+      .iter()
+      .map(|input| {
+        client
+          .get_raw_transaction(&input.previous_output.txid, None)?
+          .output
+          .into_iter()
+          .nth(input.previous_output.vout as usize)
+          .ok_or_else(|| anyhow!("tx {} spends nonexistent output {}", tx.txid(), input.previous_output))
+      })
+      .collect()
+  }
+
+  fn fee(tx: &Transaction, prevouts: &[TxOut]) -> Result<u64> {
+    let input_value = prevouts.iter().map(|prevout| prevout.value).sum::<u64>();
+    let output_value = tx.output.iter().map(|output| output.value).sum::<u64>();
+
+    input_value.checked_sub(output_value).ok_or_else(|| {
+      anyhow!(
+        "tx {} spends {input_value} sats but its outputs are worth {output_value} sats",
+        tx.txid()
+      )
+    })
+  }
+}
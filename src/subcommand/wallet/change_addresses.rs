@@ -0,0 +1,48 @@
+use {super::*, miniscript::descriptor::DescriptorPublicKey};
+
+#[derive(Debug, Parser)]
+pub(crate) struct ChangeAddresses {
+  #[arg(long, default_value = "20", help = "Derive the first <COUNT> change addresses.")]
+  count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub index: u32,
+  pub address: Address<NetworkUnchecked>,
+  pub used: bool,
+}
+
+impl ChangeAddresses {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+    let descriptor = client
+      .list_descriptors(None)?
+      .descriptors
+      .into_iter()
+      .find(|descriptor| descriptor.internal == Some(true) && descriptor.desc.starts_with("tr("))
+      .ok_or_else(|| anyhow!("wallet has no internal taproot descriptor to derive change addresses from"))?;
+
+    let descriptor = Descriptor::<DescriptorPublicKey>::from_str(&descriptor.desc)?;
+
+    let mut output = Vec::new();
+
+    for index in 0..self.count {
+      let address = descriptor
+        .at_derivation_index(index)?
+        .address(options.chain().network())?;
+
+      // any payment to a change address means the wallet handed it out, even if the output has since been spent
+      let used = client.get_received_by_address(&address, Some(0))? > Amount::ZERO;
+
+      output.push(Output {
+        index,
+        address: address.as_unchecked().clone(),
+        used,
+      });
+    }
+
+    Ok(Box::new(output))
+  }
+}
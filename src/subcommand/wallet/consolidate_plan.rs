@@ -0,0 +1,86 @@
+use {super::*, std::io::Write};
+
+#[derive(Debug, Parser)]
+pub(crate) struct ConsolidatePlan {
+  #[arg(long, help = "Spread the wallet's inscriptions over <ADDRESSES> fresh addresses.")]
+  addresses: usize,
+  #[arg(long, help = "Don't send more than <MAX_PER_ADDRESS> inscriptions to any one address.")]
+  max_per_address: Option<usize>,
+  #[arg(long, help = "Write the `inscriptionid`,`destination` pairs to CSV file <CSV>, ready for `wallet send-many`.")]
+  csv: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Destination {
+  pub address: Address<NetworkUnchecked>,
+  pub inscriptions: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub csv: PathBuf,
+  pub destinations: Vec<Destination>,
+}
+
+impl ConsolidatePlan {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    if self.addresses == 0 {
+      bail!("--addresses must be at least 1");
+    }
+
+    if self.max_per_address == Some(0) {
+      bail!("--max-per-address must be at least 1");
+    }
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+    let unspent_outputs = get_unspent_outputs(&client, &index)?;
+
+    // `send-many` refuses to list two inscriptions on the same sat, so only the first one on each sat is listed; the rest travel with it
+    let mut inscriptions: BTreeMap<SatPoint, InscriptionId> = BTreeMap::new();
+    for (satpoint, inscriptionid) in index.get_inscriptions_vector(&unspent_outputs)? {
+      inscriptions.entry(satpoint).or_insert(inscriptionid);
+    }
+
+    if inscriptions.is_empty() {
+      bail!("wallet contains no inscriptions");
+    }
+
+    if let Some(max_per_address) = self.max_per_address {
+      if inscriptions.len() > self.addresses * max_per_address {
+        bail!(
+          "wallet has {} inscriptions, which won't fit in {} addresses of at most {} inscriptions each",
+          inscriptions.len(),
+          self.addresses,
+          max_per_address
+        );
+      }
+    }
+
+    // `send-many` still gives every inscription its own output, so this gathers them under fewer addresses
+    // rather than into fewer UTXOs
+    let mut destinations = Vec::new();
+    for _ in 0..self.addresses.min(inscriptions.len()) {
+      destinations.push(Destination {
+        address: client.get_new_address(None, Some(bitcoincore_rpc::json::AddressType::Bech32m))?,
+        inscriptions: 0,
+      });
+    }
+
+    let mut file = File::create(&self.csv)?;
+    let count = destinations.len();
+
+    for (i, inscriptionid) in inscriptions.values().enumerate() {
+      let destination = &mut destinations[i % count];
+      writeln!(file, "{},{}", inscriptionid, destination.address.clone().assume_checked())?;
+      destination.inscriptions += 1;
+    }
+
+    Ok(Box::new(Output {
+      csv: self.csv,
+      destinations,
+    }))
+  }
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub content_type: Option<String>,
+  pub count: u64,
+}
+
+pub(crate) fn run(wallet: String, options: Options) -> SubcommandResult {
+  let index = Index::open(&options)?;
+  index.update()?;
+
+  let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+  let unspent_outputs = get_unspent_outputs(&client, &index)?;
+
+  let mut counts = BTreeMap::<Option<String>, u64>::new();
+
+  for (_location, inscription_id) in index.get_inscriptions_vector(&unspent_outputs)? {
+    let inscription = index
+      .get_inscription_by_id(inscription_id)?
+      .ok_or_else(|| anyhow!("inscription {inscription_id} not found"))?;
+
+    // a delegating inscription is served with its delegate's content, so count it as that
+    let inscription = match inscription.delegate() {
+      Some(delegate) => index.get_inscription_by_id(delegate)?.unwrap_or(inscription),
+      None => inscription,
+    };
+
+    *counts
+      .entry(inscription.content_type().map(str::to_string))
+      .or_default() += 1;
+  }
+
+  let mut output = counts
+    .into_iter()
+    .map(|(content_type, count)| Output {
+      content_type,
+      count,
+    })
+    .collect::<Vec<Output>>();
+
+  output.sort_by(|a, b| b.count.cmp(&a.count));
+
+  Ok(Box::new(output))
+}
@@ -16,11 +16,26 @@ pub(crate) struct Create {
   pub(crate) passphrase: String,
   #[arg(long, value_enum, default_value="bech32m")]
   pub(crate) address_type: AddressType,
+  #[arg(
+    long,
+    default_value_t = 12,
+    value_parser = parse_words,
+    help = "Generate a seed phrase of <WORDS> words, either 12 or 24."
+  )]
+  pub(crate) words: u16,
+}
+
+fn parse_words(s: &str) -> Result<u16> {
+  match s.parse()? {
+    words @ (12 | 24) => Ok(words),
+    words => bail!("seed phrases must be 12 or 24 words, not {words}"),
+  }
 }
 
 impl Create {
   pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
-    let mut entropy = [0; 16];
+    // BIP39 uses 128 bits of entropy for 12 words and 256 bits for 24
+    let mut entropy = vec![0; usize::from(self.words) * 4 / 3];
     rand::thread_rng().fill_bytes(&mut entropy);
 
     let mnemonic = Mnemonic::from_entropy(&entropy)?;
@@ -118,9 +118,7 @@ impl Etch {
 
     let unsigned_transaction = fund_raw_transaction(&client, self.fee_rate, &unfunded_transaction)?;
 
-    let signed_transaction = client
-      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
-      .hex;
+    let signed_transaction = sign_transaction(&client, &unsigned_transaction, None)?;
 
     let transaction = client.send_raw_transaction(&signed_transaction)?;
 
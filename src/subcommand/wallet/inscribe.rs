@@ -1,7 +1,7 @@
 use {
-  self::batch::{Batch, BatchEntry, Batchfile, Mode},
+  self::batch::{load_content_type_map, mapped_content_type, Batch, BatchEntry, Batchfile, Mode},
   super::*,
-  crate::subcommand::wallet::transaction_builder::Target,
+  crate::subcommand::{decode::CompactInscription, wallet::transaction_builder::Target},
   base64::{Engine as _, engine::general_purpose},
   bitcoin::{
     blockdata::{opcodes, script},
@@ -17,6 +17,7 @@ use {
   bitcoincore_rpc::bitcoincore_rpc_json::{GetRawTransactionResultVout, ImportDescriptors, SignRawTransactionInput, Timestamp},
   bitcoincore_rpc::Client,
   bitcoincore_rpc::RawTx,
+  miniscript::descriptor::SinglePriv,
   reqwest::{header, header::USER_AGENT},
   std::{collections::BTreeSet, io::Write},
   tempfile::tempdir,
@@ -25,6 +26,20 @@ use {
 
 mod batch;
 
+// the label commit recovery descriptors are imported into the wallet with, which `wallet sweep-commits` looks for
+pub(crate) const RECOVERY_KEY_LABEL: &str = "commit tx recovery key";
+
+// the fingerprint bitcoind gives a recovery key, which isn't derived from a master key: the first four bytes of
+// the hash160 of its even-parity compressed form
+pub(crate) fn recovery_key_fingerprint(public_key: XOnlyPublicKey) -> Fingerprint {
+  let mut compressed = vec![0x02];
+  compressed.extend_from_slice(&public_key.serialize());
+
+  let hash = bitcoin::hashes::hash160::Hash::hash(&compressed).to_byte_array();
+
+  Fingerprint::from([hash[0], hash[1], hash[2], hash[3]])
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct InscriptionInfo {
   pub id: InscriptionId,
@@ -39,11 +54,15 @@ fn is_zero(n: &u64) -> bool {
 pub struct Output {
   #[serde(skip_serializing_if = "Option::is_none")]
   pub commit: Option<Txid>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub commit_fee: Option<u64>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub commit_hex: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub commit_psbt: Option<String>,
-  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub commit_vsize: Option<u64>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub inscriptions: Vec<InscriptionInfo>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub message: Option<String>,
@@ -53,12 +72,37 @@ pub struct Output {
   pub recovery_descriptor: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub reveal: Option<Txid>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub reveal_fee: Option<u64>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub reveal_hex: Option<String>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub reveal_psbt: Option<String>,
-  #[serde(skip_serializing_if = "is_zero")]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub reveal_vsize: Option<u64>,
+  #[serde(default, skip_serializing_if = "is_zero")]
   pub total_fees: u64,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub total_postage: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CommitInspection {
+  pub address: Address,
+  pub commit: OutPoint,
+  pub inscriptions: Vec<CompactInscription>,
+  pub value: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OfflineReveal {
+  pub address: Address,
+  pub commit: OutPoint,
+  pub fee: u64,
+  pub inscription: InscriptionId,
+  pub postage: u64,
+  pub reveal: Txid,
+  pub reveal_psbt: String,
 }
 
 #[derive(Clone, Debug)]
@@ -69,7 +113,7 @@ pub(crate) struct ParentInfo {
   tx_out: TxOut,
 }
 
-#[derive(Debug, Parser)]
+#[derive(Clone, Debug, Parser)]
 #[clap(
   group = ArgGroup::new("source")
       .required(true)
@@ -83,7 +127,7 @@ pub(crate) struct Inscribe {
       "cbor_metadata", "destination", "file", "json_metadata", "metaprotocol", "parent", "postage", "reinscribe", "satpoint"
     ]
   )]
-  pub(crate) batch: Option<PathBuf>,
+  pub(crate) batch: Vec<PathBuf>,
   #[arg(
     long,
     help = "Include CBOR in file at <METADATA> as inscription metadata",
@@ -97,21 +141,67 @@ pub(crate) struct Inscribe {
   pub(crate) utxo: Vec<OutPoint>,
   #[arg(long, help = "Only spend outpoints given with --utxo")]
   pub(crate) coin_control: bool,
+  #[arg(long, help = "Tag every inscription with collection identifier <COLLECTION>, using odd tag 101.")]
+  pub(crate) collection: Option<String>,
+  #[arg(
+    long,
+    help = "Use content types from YAML map <CONTENT_TYPE_MAP> of file extension to MIME type, overriding the built-in table. An explicit `content_type` in a batch entry still wins."
+  )]
+  pub(crate) content_type_map: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Allow a reveal output below the dust limit, e.g. for tiny --postage experiments, warning instead of failing. Such reveal txs are nonstandard, so most nodes won't relay them; only use this on networks or with miners that accept them."
+  )]
+  pub(crate) allow_dust_reveal_output: bool,
+  #[arg(
+    long,
+    help = "Fail if postage plus commit and reveal fees come to more than <BUDGET>, e.g. `500000sat`."
+  )]
+  pub(crate) budget: Option<Amount>,
   #[arg(long, help = "Send any change output to <CHANGE>.")]
   pub(crate) change: Option<Address<NetworkUnchecked>>,
+  #[arg(
+    long,
+    conflicts_with_all = &["commit_only", "commitment", "no_wallet"],
+    help = "Make the commit tx's change output exactly <CHANGE_VALUE>, e.g. `100000sat`, with anything left over going to fees."
+  )]
+  pub(crate) change_value: Option<Amount>,
   #[arg(
     long,
     help = "Use <COMMIT_FEE_RATE> sats/vbyte for commit transaction.\nDefaults to <FEE_RATE> if unset."
   )]
   pub(crate) commit_fee_rate: Option<FeeRate>,
+  #[arg(
+    long,
+    help = "Check every batch entry and report all the ones that fail, e.g. missing, unreadable, or too-large files, in one error, instead of stopping at the first."
+  )]
+  pub(crate) collect_errors: bool,
   #[arg(long, help = "Compress inscription content with brotli.")]
   pub(crate) compress: bool,
   #[arg(long, help = "Send inscription to <DESTINATION>.")]
   pub(crate) destination: Option<Address<NetworkUnchecked>>,
   #[arg(long, help = "Don't sign or broadcast transactions.")]
   pub(crate) dry_run: bool,
+  #[arg(
+    long,
+    conflicts_with_all = &["dry_run", "no_broadcast", "no_wallet", "offline_reveal", "resume_from"],
+    help = "Don't broadcast; write the signed commit and reveal txs, along with the recovery descriptor, to <EXPORT_SIGNED>, for broadcasting later with `ord wallet broadcast-file`."
+  )]
+  pub(crate) export_signed: Option<PathBuf>,
   #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
   pub(crate) fee_rate: FeeRate,
+  #[arg(
+    long,
+    requires = "commitment",
+    conflicts_with = "reveal_fee",
+    help = "Pay the reveal fee at <FEE_BUMP_REVEAL> sats/vB instead, taking the difference from the reveal's change output. For use with `--commitment`."
+  )]
+  pub(crate) fee_bump_reveal: Option<FeeRate>,
+  #[arg(
+    long,
+    help = "Recompute the commit and reveal fees from the finished transactions and fail if they differ from the projected fees by more than 1 sat."
+  )]
+  pub(crate) double_check_fees: bool,
   #[arg(long, help = "Inscribe sat with contents of <FILE>.")]
   pub(crate) file: Option<PathBuf>,
   #[arg(
@@ -120,8 +210,20 @@ pub(crate) struct Inscribe {
     conflicts_with = "cbor_metadata"
   )]
   pub(crate) json_metadata: Option<PathBuf>,
+  #[arg(
+    long,
+    conflicts_with = "commit_only",
+    help = "In shared-output and same-sat modes, cap the inscription output at <MAX_INSCRIPTION_POSTAGE>, e.g. `10000sat`, sending the rest of the postage to a change output."
+  )]
+  pub(crate) max_inscription_postage: Option<Amount>,
   #[clap(long, help = "Set inscription metaprotocol to <METAPROTOCOL>.")]
   pub(crate) metaprotocol: Option<String>,
+  #[arg(
+    long,
+    value_delimiter = ',',
+    help = "Refuse to inscribe any metaprotocol not in comma-separated <METAPROTOCOLS>, to catch typos like `brc20` for `brc-20`."
+  )]
+  pub(crate) metaprotocols: Option<Vec<String>>,
   #[arg(long, alias = "nobackup", help = "Do not back up recovery key.")]
   pub(crate) no_backup: bool,
   #[arg(
@@ -136,6 +238,8 @@ pub(crate) struct Inscribe {
   pub(crate) parent_destination: Option<Address<NetworkUnchecked>>,
   #[clap(long, help = "The satpoint of the parent inscription, in case it isn't confirmed yet.")]
   pub(crate) parent_satpoint: Option<SatPoint>,
+  #[clap(long, help = "Shrink the parent inscription's reveal output to <PARENT_VALUE> sats, sending the rest of its value to a change output.")]
+  pub(crate) parent_value: Option<u64>,
   #[arg(
     long,
     help = "Amount of postage to include in the inscription. Default `10000sat`."
@@ -143,12 +247,26 @@ pub(crate) struct Inscribe {
   pub(crate) postage: Option<Amount>,
   #[clap(long, help = "Allow reinscription.")]
   pub(crate) reinscribe: bool,
+  #[arg(
+    long,
+    conflicts_with_all = &["dry_run", "no_broadcast", "no_wallet"],
+    help = "Record the signed transactions and what has been broadcast in <RESUME_FROM>. If <RESUME_FROM> already exists, don't build anything; just broadcast whatever it records as not yet broadcast."
+  )]
+  pub(crate) resume_from: Option<PathBuf>,
   #[arg(long, help = "Specify the reveal tx fee.")]
   pub(crate) reveal_fee: Option<Amount>,
+  #[arg(long, help = "Write the unsigned reveal tx to <REVEAL_PSBT_OUT> as a base64 PSBT, with the commit output's `witness_utxo` filled in, so it can be checked in an external tool.")]
+  pub(crate) reveal_psbt_out: Option<PathBuf>,
   #[arg(long, help = "Inscribe <SATPOINT>.")]
   pub(crate) satpoint: Option<SatPoint>,
   #[clap(long, help = "Use provided recovery key instead of a random one.")]
   pub(crate) key: Option<String>,
+  #[arg(
+    long,
+    requires = "key",
+    help = "Use x-only public key <INTERNAL_KEY> as the commit's taproot internal key, instead of the --key, which still signs the reveal script. The commit output can then only be spent by revealing with the same --key and --internal-key, so no recovery key is backed up or dumped."
+  )]
+  pub(crate) internal_key: Option<XOnlyPublicKey>,
   #[clap(long, help = "Don't make a reveal tx; just create a commit tx that sends all the sats to a new commitment. Either specify --key if you have one, or note the --key it generates for you. Implies --no-backup.")]
   pub(crate) commit_only: bool,
   #[clap(long, help = "Don't make a commit transaction; just create a reveal tx that reveals the inscription committed to by output <COMMITMENT>. Requires the same --key as was used to make the commitment. Implies --no-backup. This doesn't work if the --key has ever been backed up to the wallet. When using --commitment, the reveal tx will create a change output unless --reveal-fee is set to '0 sats', in which case the whole commitment will go to postage and fees.")]
@@ -159,12 +277,74 @@ pub(crate) struct Inscribe {
   pub(crate) next_file: Option<PathBuf>,
   #[clap(long, help = "Use <REVEAL-INPUT> as an extra input to the reveal tx. For use with `--commitment`.")]
   pub(crate) reveal_input: Vec<OutPoint>,
+  #[arg(
+    long,
+    requires = "reveal_input",
+    help = "Take the value of each --reveal-input from <REVEAL_INPUT_VALUE>, e.g. `20000sat`, given once per --reveal-input in the same order, instead of from the index."
+  )]
+  pub(crate) reveal_input_value: Vec<Amount>,
+  #[arg(
+    long,
+    requires = "reveal_input_value",
+    value_parser = ScriptBuf::from_hex,
+    help = "Take the script pubkey of each --reveal-input from hex <REVEAL_INPUT_SCRIPT>, given once per --reveal-input in the same order. Along with --reveal-input-value, this means the reveal inputs' txs needn't be in the index or the wallet, e.g. because they're pruned or external."
+  )]
+  pub(crate) reveal_input_script: Vec<ScriptBuf>,
+  #[arg(
+    long,
+    value_name = "SIGHASH",
+    help = "Have bitcoind sign the --parent and --reveal-input inputs of the reveal tx with sighash <SIGHASH>, e.g. `SIGHASH_ALL` or `SIGHASH_SINGLE|SIGHASH_ANYONECANPAY`, instead of the taproot default. The reveal fee is estimated for signatures of that type."
+  )]
+  pub(crate) reveal_input_sighash: Option<TapSighashType>,
+  #[arg(
+    long,
+    requires = "commitment",
+    help = "Make sure the sat at <REVEAL_CHANGE_TARGET> ends up in the reveal tx's change output. A target outside the commitment and the --reveal-inputs has its wallet output added as a --reveal-input. The target's --reveal-input goes after just enough of the others to fill the inscription outputs, so it starts the change, away from the fee that comes off the change's end. With several destinations, every inscription output comes before the change, so all of their postage has to come before the target; a target in the commitment itself must be past that much postage."
+  )]
+  pub(crate) reveal_change_target: Option<SatPoint>,
   #[clap(long, help = "Dump raw hex transactions and recovery keys to standard output.")]
   pub(crate) dump: bool,
+  #[arg(
+    long,
+    conflicts_with = "internal_key",
+    help = "Output the commit recovery descriptor without the rest of --dump. The recovery key is still backed up to the wallet unless --no-backup is given."
+  )]
+  pub(crate) emit_recovery: bool,
+  #[arg(
+    long,
+    conflicts_with = "internal_key",
+    value_name = "PATH",
+    help = "Give the recovery descriptor a `[fingerprint/<PATH>]` key origin, e.g. `m/86h/0h/0h`, as BIP-32 descriptor wallets expect, both when importing it into the wallet and when outputting it. The fingerprint is the one Bitcoin Core gives the recovery key."
+  )]
+  pub(crate) recovery_key_origin: Option<DerivationPath>,
   #[clap(long, help = "Do not broadcast any transactions. Implies --dump.")]
   pub(crate) no_broadcast: bool,
+  #[arg(
+    long,
+    conflicts_with_all = &["dry_run", "no_broadcast", "no_wallet"],
+    help = "Check with bitcoind's `testmempoolaccept` that the commit and reveal would both be accepted before broadcasting either."
+  )]
+  pub(crate) test_accept: bool,
+  #[arg(
+    long,
+    visible_alias = "no-rare",
+    conflicts_with_all = &["commitment", "sat", "satpoint"],
+    help = "Only inscribe on a cardinal utxo whose first sat is common, skipping rarer ones. Requires an index built with `--index-sats`."
+  )]
+  pub(crate) only_common: bool,
   #[clap(long, help = "Use <COMMIT-INPUT> as an extra input to the commit tx. Useful for forcing CPFP.")]
   pub(crate) commit_input: Vec<OutPoint>,
+  #[arg(
+    long,
+    requires_all = &["commit_outpoint", "commit_value", "key", "file", "destination"],
+    conflicts_with_all = &["commitment", "commit_only", "dry_run", "inspect_commit", "next_batch", "next_file", "parent", "reveal_input", "satpoint", "sat"],
+    help = "Don't talk to bitcoind or the index at all; just build the unsigned reveal tx for <FILE> spending --commit-outpoint, worth --commit-value, which must pay to the commitment made by --key, and output it as a base64 PSBT with the commit's `witness_utxo` filled in. The whole --commit-value, less the reveal fee, goes to --destination as postage."
+  )]
+  pub(crate) offline_reveal: bool,
+  #[arg(long, requires = "offline_reveal", help = "Reveal the commitment in output <COMMIT_OUTPOINT>. For use with `--offline-reveal`.")]
+  pub(crate) commit_outpoint: Option<OutPoint>,
+  #[arg(long, requires = "offline_reveal", help = "The value of the --commit-outpoint, e.g. `20000sat`. For use with `--offline-reveal`.")]
+  pub(crate) commit_value: Option<Amount>,
   #[arg(long, help = "Inscribe <SAT>.", conflicts_with = "satpoint")]
   pub(crate) sat: Option<Sat>,
   #[arg(long, help = "Don't use a local wallet. Leave the commit transaction unsigned instead.")]
@@ -173,10 +353,115 @@ pub(crate) struct Inscribe {
   pub(crate) commit_vsize: Option<u64>,
   #[arg(long, help = "Whether to omit pointer from the envelope of blank inscriptions.")]
   pub(crate) skip_pointer_for_none: bool,
+  #[arg(long, help = "Don't make any transactions; check that commit tx <INSPECT_COMMIT> pays to the commitment made by --key and the given inscriptions, and list the inscriptions its reveal would produce.")]
+  pub(crate) inspect_commit: Option<Txid>,
+}
+
+// what a single `wallet inscribe` produces
+enum Outcome {
+  CommitInspection(CommitInspection),
+  Inscribed(Output),
+  OfflineReveal(OfflineReveal),
 }
 
 impl Inscribe {
   pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    if self.batch.len() > 1 {
+      return self.run_chain(wallet, options);
+    }
+
+    Ok(match self.run_one(wallet, options)? {
+      Outcome::CommitInspection(inspection) => Box::new(inspection),
+      Outcome::Inscribed(output) => Box::new(output),
+      Outcome::OfflineReveal(offline_reveal) => Box::new(offline_reveal),
+    })
+  }
+
+  // inscribe several batchfiles back to back, starting from --commitment, with each reveal's change
+  // committing to the next batchfile's inscriptions under the same --key, and the next reveal spending it
+  fn run_chain(self, wallet: String, options: Options) -> SubcommandResult {
+    if self.commitment.is_none() {
+      bail!("several --batch files can only be chained starting from a --commitment");
+    }
+
+    if self.next_batch.is_some() || self.next_file.is_some() {
+      bail!("several --batch files already chain to each other, so don't work with --next-batch or --next-file");
+    }
+
+    if self.dry_run
+      || self.no_broadcast
+      || self.resume_from.is_some()
+      || self.export_signed.is_some()
+      || self.reveal_psbt_out.is_some()
+      || self.inspect_commit.is_some()
+    {
+      bail!("several --batch files need each reveal broadcast so the next can spend its change, so don't work with --dry-run, --no-broadcast, --resume-from, --export-signed, --reveal-psbt-out, or --inspect-commit");
+    }
+
+    if self.reveal_fee == Some(Amount::from_sat(0)) {
+      bail!("several --batch files need reveal change to fund the next reveal, so don't work with a --reveal-fee of 0");
+    }
+
+    let batchfiles = self
+      .batch
+      .iter()
+      .map(|path| Batchfile::load(path))
+      .collect::<Result<Vec<Batchfile>>>()?;
+
+    for (path, batchfile) in self.batch.iter().zip(&batchfiles) {
+      if batchfile.mode != batchfiles[0].mode {
+        bail!(
+          "batchfile {} uses mode {:?} but {} uses {:?}; chained batchfiles must all use the same mode",
+          path.display(),
+          batchfile.mode,
+          self.batch[0].display(),
+          batchfiles[0].mode,
+        );
+      }
+
+      // the index only sees confirmed transactions, so it can't find a parent moved by an earlier reveal in the chain
+      if batchfile.parent.is_some() {
+        bail!("batchfile {} has a parent, which chained batchfiles don't support", path.display());
+      }
+    }
+
+    let client = bitcoin_rpc_client_for_wallet_command(wallet.clone(), &options)?;
+
+    let mut commitment = self.commitment.unwrap();
+    let mut outputs = Vec::new();
+
+    for (i, batch) in self.batch.iter().enumerate() {
+      let Outcome::Inscribed(output) = Inscribe {
+        batch: vec![batch.clone()],
+        commitment: Some(commitment),
+        next_batch: self.batch.get(i + 1).cloned(),
+        reveal_input: if i == 0 { self.reveal_input.clone() } else { Vec::new() },
+        reveal_input_value: if i == 0 { self.reveal_input_value.clone() } else { Vec::new() },
+        reveal_input_script: if i == 0 { self.reveal_input_script.clone() } else { Vec::new() },
+        ..self.clone()
+      }
+      .run_one(wallet.clone(), options.clone())?
+      else {
+        unreachable!("chained batches always inscribe");
+      };
+
+      let reveal = output
+        .reveal
+        .ok_or_else(|| anyhow!("batchfile {} made no reveal tx", batch.display()))?;
+
+      // the change is always the reveal's last output
+      commitment = OutPoint {
+        txid: reveal,
+        vout: u32::try_from(client.get_raw_transaction(&reveal, None)?.output.len() - 1).unwrap(),
+      };
+
+      outputs.push(output);
+    }
+
+    Ok(Box::new(outputs))
+  }
+
+  fn run_one(self, wallet: String, options: Options) -> Result<Outcome> {
     if self.commitment.is_some() && self.key.is_none() {
       return Err(anyhow!("--commitment only works with --key"));
     }
@@ -201,6 +486,22 @@ impl Inscribe {
       return Err(anyhow!("--reveal-input only works with --commitment"));
     }
 
+    if self.inspect_commit.is_some() && self.key.is_none() {
+      return Err(anyhow!("--inspect-commit only works with --key"));
+    }
+
+    if self.parent_value.is_some() && self.no_wallet && self.change.is_none() {
+      return Err(anyhow!("--parent-value sends the value trimmed off the parent to change, so without a wallet it needs --change"));
+    }
+
+    if self.inspect_commit.is_some() && (self.commit_only || self.commitment.is_some()) {
+      return Err(anyhow!("--inspect-commit doesn't work with --commit-only or --commitment"));
+    }
+
+    if self.offline_reveal {
+      return Ok(Outcome::OfflineReveal(self.offline_reveal(options.chain())?));
+    }
+
     let mut no_backup = self.no_backup;
     if self.commit_only || self.commitment.is_some() {
       no_backup = true;
@@ -251,6 +552,12 @@ impl Inscribe {
     (utxos, locked_utxos, runic_utxos, client)
     };
 
+    if let Some(path) = &self.resume_from {
+      if path.exists() {
+        return Ok(Outcome::Inscribed(Batch::resume(path, &client)?));
+      }
+    }
+
     let chain = options.chain();
 
     let change = match self.change {
@@ -258,31 +565,44 @@ impl Inscribe {
       None => None,
     };
 
+    let content_type_map = match &self.content_type_map {
+      Some(path) => load_content_type_map(path)?,
+      None => BTreeMap::new(),
+    };
+
     let postage;
+    let postages;
     let destinations;
     let fee_utxos;
     let inscribe_on_specific_utxos;
     let inscriptions;
     let mode;
-    let parent_info;
+    let parent_infos;
     let sat;
 
-    let next_inscriptions = if self.next_file.is_some() {
+    let next_inscriptions = if let Some(next_file) = self.next_file {
       vec![Inscription::from_file(
         chain,
         None,
-        self.next_file.unwrap(),
+        &next_file,
+        mapped_content_type(&content_type_map, &next_file),
         self.parent,
         None,
         self.metaprotocol.clone(),
         metadata.clone(),
-        self.compress,
+        self.compress.then_some("br"),
         self.skip_pointer_for_none,
         None,
+        false,
       )?]
     } else if self.next_batch.is_some() {
       let batchfile = Batchfile::load(&self.next_batch.unwrap())?;
-      let parent_info = Inscribe::get_parent_info(batchfile.parent, &index, &utxos, &client, chain, batchfile.parent_satpoint, self.no_wallet, self.parent_destination.clone())?;
+
+      if !self.no_limit {
+        batchfile.check_metadata_size()?;
+      }
+
+      let parent_infos = Inscribe::get_batch_parent_infos(&batchfile, &index, &utxos, &client, chain, self.no_wallet, batchfile.parent_destination(self.parent_destination.clone())?)?;
       let postage = batchfile
           .postage
           .map(Amount::from_sat)
@@ -291,34 +611,51 @@ impl Inscribe {
       batchfile.inscriptions(
         &client,
         chain,
-        parent_info.as_ref().map(|info| info.tx_out.value),
+        Some(parent_infos.iter().map(|info| info.tx_out.value).sum()),
         metadata.clone(),
         postage,
         self.compress,
         self.skip_pointer_for_none,
         &mut utxos,
+        &content_type_map,
+        self.collect_errors,
+        self.metaprotocols.as_deref(),
       )?.0
     } else {
       Vec::new()
     };
 
-    match (self.file, self.batch) {
+    match (self.file, self.batch.into_iter().next()) {
       (Some(file), None) => {
-        parent_info = Inscribe::get_parent_info(self.parent, &index, &utxos, &client, chain, self.parent_satpoint, self.no_wallet, self.parent_destination)?;
+        parent_infos = Inscribe::get_parent_info(self.parent, &index, &utxos, &client, chain, self.parent_satpoint, self.no_wallet, self.parent_destination)?
+          .into_iter()
+          .collect();
 
         postage = self.postage.unwrap_or(TARGET_POSTAGE);
+        postages = Vec::new();
+
+        if let (Some(metaprotocol), Some(metaprotocols)) = (&self.metaprotocol, &self.metaprotocols) {
+          if !metaprotocols.contains(metaprotocol) {
+            bail!(
+              "metaprotocol `{metaprotocol}` isn't one of --metaprotocols {}",
+              metaprotocols.join(",")
+            );
+          }
+        }
 
         inscriptions = vec![Inscription::from_file(
           chain,
           None,
-          file,
+          &file,
+          mapped_content_type(&content_type_map, &file),
           self.parent,
           None,
           self.metaprotocol.clone(),
           metadata.clone(),
-          self.compress,
+          self.compress.then_some("br"),
           self.skip_pointer_for_none,
           None,
+          false,
         )?];
 
         mode = Mode::SeparateOutputs;
@@ -336,22 +673,31 @@ impl Inscribe {
       (None, Some(batch)) => {
         let batchfile = Batchfile::load(&batch)?;
 
-        parent_info = Inscribe::get_parent_info(batchfile.parent, &index, &utxos, &client, chain, batchfile.parent_satpoint, self.no_wallet, self.parent_destination)?;
+        if !self.no_limit {
+          batchfile.check_metadata_size()?;
+        }
+
+        parent_infos = Inscribe::get_batch_parent_infos(&batchfile, &index, &utxos, &client, chain, self.no_wallet, batchfile.parent_destination(self.parent_destination)?)?;
 
         postage = batchfile
           .postage
           .map(Amount::from_sat)
           .unwrap_or(TARGET_POSTAGE);
 
+        postages = batchfile.postages();
+
         (inscriptions, destinations, inscribe_on_specific_utxos, fee_utxos) = batchfile.inscriptions(
           &client,
           chain,
-          parent_info.as_ref().map(|info| info.tx_out.value),
+          Some(parent_infos.iter().map(|info| self.parent_value.unwrap_or(info.tx_out.value)).sum()),
           metadata,
           postage,
           self.compress,
           self.skip_pointer_for_none,
           &mut utxos,
+          &content_type_map,
+          self.collect_errors,
+          self.metaprotocols.as_deref(),
         )?;
 
         mode = batchfile.mode;
@@ -365,6 +711,24 @@ impl Inscribe {
       _ => unreachable!(),
     }
 
+    let inscriptions = Self::tag_collection(inscriptions, &self.collection)?;
+    let next_inscriptions = Self::tag_collection(next_inscriptions, &self.collection)?;
+
+    if let Some(commit) = self.inspect_commit {
+      return Ok(Outcome::CommitInspection(Batch::inspect_commit(
+        &inscriptions,
+        &self.key.unwrap(),
+        self.internal_key,
+        commit,
+        &client,
+        chain,
+      )?));
+    }
+
+    if self.only_common && !index.has_sat_index() {
+      bail!("index must be built with `--index-sats` to use `--only-common`");
+    }
+
     let satpoint = if let Some(sat) = sat {
       if !index.has_sat_index() {
         return Err(anyhow!(
@@ -372,14 +736,54 @@ impl Inscribe {
         ));
       }
       match index.find(sat)? {
-        Some(satpoint) => Some(satpoint),
+        Some(satpoint) => {
+          // the sat has to be in one of our own utxos for the commit to spend it
+          if !self.no_wallet && !utxos.contains_key(&satpoint.outpoint) {
+            bail!("sat {sat} is in output {}, which the wallet doesn't own", satpoint.outpoint);
+          }
+
+          Some(satpoint)
+        }
         None => return Err(anyhow!(format!("could not find sat `{sat}`"))),
       }
     } else {
       self.satpoint
     };
 
-    Ok(Box::new(Batch {
+    let mut reveal_input = self.reveal_input;
+    let reveal_input_values = self
+      .reveal_input_value
+      .iter()
+      .map(|value| value.to_sat())
+      .collect::<Vec<u64>>();
+
+    // the reveal's change only gets sats from the commitment and the --reveal-inputs, so a target
+    // anywhere else is taken from the wallet as an extra --reveal-input
+    if let Some(target) = self.reveal_change_target {
+      if Some(target.outpoint) != self.commitment && !reveal_input.contains(&target.outpoint) {
+        if !reveal_input_values.is_empty() {
+          bail!("--reveal-change-target {target} isn't in the commitment or a --reveal-input; add its output as a --reveal-input with its own --reveal-input-value");
+        }
+
+        if !utxos.contains_key(&target.outpoint) {
+          bail!("--reveal-change-target {target} isn't in the commitment, a --reveal-input, or a wallet output");
+        }
+
+        if locked_utxos.contains(&target.outpoint)
+          || runic_utxos.contains(&target.outpoint)
+          || !index.get_inscriptions_on_output(target.outpoint)?.is_empty()
+        {
+          bail!("--reveal-change-target {target} is in an output that's locked or holds inscriptions or runes, so it can't be spent into the reveal change");
+        }
+
+        reveal_input.push(target.outpoint);
+      }
+    }
+
+    let mut batch = Batch {
+      allow_dust_reveal_output: self.allow_dust_reveal_output,
+      budget: self.budget,
+      change_value: self.change_value,
       commit_fee_rate: self.commit_fee_rate.unwrap_or(self.fee_rate),
       commit_only: self.commit_only,
       commit_vsize: self.commit_vsize,
@@ -390,28 +794,110 @@ impl Inscribe {
         None
       },
       destinations,
+      double_check_fees: self.double_check_fees,
       dump,
       dry_run: self.dry_run,
+      emit_recovery: self.emit_recovery,
+      export_signed: self.export_signed,
+      fee_bump_reveal: self.fee_bump_reveal,
       fee_utxos,
       inscribe_on_specific_utxos,
       inscriptions,
+      internal_key: self.internal_key,
       key: self.key,
+      max_inscription_postage: self.max_inscription_postage,
       mode,
       next_inscriptions,
       no_backup,
       no_broadcast: self.no_broadcast,
       no_limit: self.no_limit,
       no_wallet: self.no_wallet,
-      parent_info,
+      only_common: self.only_common,
+      parent_infos,
+      parent_value: self.parent_value,
       postage,
+      postages,
+      recovery_key_origin: self.recovery_key_origin,
       reinscribe: self.reinscribe,
+      resume_from: self.resume_from,
       reveal_fee: self.reveal_fee,
       reveal_fee_rate: self.fee_rate,
-      reveal_input: self.reveal_input,
+      reveal_change_target: self.reveal_change_target,
+      reveal_input,
+      reveal_input_scripts: self.reveal_input_script,
+      reveal_input_values,
+      reveal_input_sighash_type: self.reveal_input_sighash.unwrap_or(TapSighashType::Default),
       reveal_psbt: None,
+      reveal_psbt_out: self.reveal_psbt_out,
       satpoint,
+      test_accept: self.test_accept,
+    };
+
+    batch.order_reveal_inputs_for_change_target(&index, &client, &utxos)?;
+
+    Ok(Outcome::Inscribed(batch.inscribe(chain, &index, &client, &locked_utxos, runic_utxos, &mut utxos, self.commit_input, change)?))
+  }
+
+  fn offline_reveal(self, chain: Chain) -> Result<OfflineReveal> {
+    let file = self.file.unwrap();
+
+    let content_type_map = match &self.content_type_map {
+      Some(path) => load_content_type_map(path)?,
+      None => BTreeMap::new(),
+    };
+
+    let inscriptions = Self::tag_collection(
+      vec![Inscription::from_file(
+        chain,
+        None,
+        &file,
+        mapped_content_type(&content_type_map, &file),
+        None,
+        None,
+        self.metaprotocol.clone(),
+        Inscribe::parse_metadata(self.cbor_metadata, self.json_metadata)?,
+        self.compress.then_some("br"),
+        self.skip_pointer_for_none,
+        None,
+        false,
+      )?],
+      &self.collection,
+    )?;
+
+    Batch::offline_reveal(
+      &inscriptions,
+      &self.key.unwrap(),
+      self.internal_key,
+      self.commit_outpoint.unwrap(),
+      self.commit_value.unwrap(),
+      self.destination.unwrap().require_network(chain.network())?,
+      self.fee_rate,
+      chain,
+    )
+  }
+
+  fn tag_collection(
+    mut inscriptions: Vec<Inscription>,
+    collection: &Option<String>,
+  ) -> Result<Vec<Inscription>> {
+    if let Some(collection) = collection {
+      if collection.is_empty() {
+        bail!("--collection can't be empty");
+      }
+
+      if collection.len() > MAX_SCRIPT_ELEMENT_SIZE {
+        bail!(
+          "--collection is {} bytes, more than the {MAX_SCRIPT_ELEMENT_SIZE} byte push limit",
+          collection.len()
+        );
+      }
+
+      for inscription in &mut inscriptions {
+        inscription.collection = Some(collection.clone().into_bytes());
+      }
     }
-    .inscribe(chain, &index, &client, &locked_utxos, runic_utxos, &mut utxos, self.commit_input, change)?))
+
+    Ok(inscriptions)
   }
 
   fn parse_metadata(cbor: Option<PathBuf>, json: Option<PathBuf>) -> Result<Option<Vec<u8>>> {
@@ -445,6 +931,14 @@ impl Inscribe {
   ) -> Result<Option<ParentInfo>> {
     if let Some(parent_id) = parent {
       let satpoint = if let Some(satpoint) = satpoint {
+        // an unconfirmed output is unknown to the index, but an indexed one must hold the parent on
+        // exactly this sat, since other inscriptions may share the output
+        let inscriptions = index.get_inscriptions_on_output_with_satpoints(satpoint.outpoint)?;
+
+        if !inscriptions.is_empty() && !inscriptions.contains(&(satpoint, parent_id)) {
+          bail!("parent satpoint {satpoint} does not hold parent {parent_id}");
+        }
+
         satpoint
       } else {
         if let Some(satpoint) = index.get_inscription_satpoint_by_id(parent_id)? {
@@ -485,6 +979,39 @@ impl Inscribe {
     }
   }
 
+  // the batchfile's `parent`, at its `parent_satpoint` if given, then any further `parents`, all of them
+  // returned to the same destination
+  fn get_batch_parent_infos(
+    batchfile: &Batchfile,
+    index: &Index,
+    utxos: &BTreeMap<OutPoint, Amount>,
+    client: &Client,
+    chain: Chain,
+    no_wallet: bool,
+    destination: Option<Address<NetworkUnchecked>>,
+  ) -> Result<Vec<ParentInfo>> {
+    let mut parent_infos = Vec::new();
+
+    for parent in batchfile.parents() {
+      parent_infos.extend(Inscribe::get_parent_info(
+        Some(parent),
+        index,
+        utxos,
+        client,
+        chain,
+        if batchfile.parent == Some(parent) {
+          batchfile.parent_satpoint
+        } else {
+          None
+        },
+        no_wallet,
+        destination.clone(),
+      )?);
+    }
+
+    Ok(parent_infos)
+  }
+
   fn fetch_url_into_file(
     client: &reqwest::blocking::Client,
     url: &str,
@@ -506,6 +1033,10 @@ impl Inscribe {
     }
   }
 
+  pub(crate) fn broadcast_exported(path: &Path, client: &Client) -> Result<Output> {
+    Batch::broadcast_exported(path, client)
+  }
+
   pub(crate) fn get_temporary_key(
     index: &Index,
     chain: Chain,
@@ -664,14 +1195,18 @@ impl Inscribe {
        */
 
       entries.push(BatchEntry {
+        content_encoding: None,
+        content_type: None,
         delegate: None,
         destination: Some(destination),
-        file: tmpfile.into(),
+        file: Some(tmpfile),
         metadata: None,
         metadata_json: metadata,
         metaprotocol: None,
         offset: None,
         pointer: None,
+        postage: None,
+        unbound: false,
         utxo: Some(utxo),
       });
     }
@@ -708,32 +1243,38 @@ impl Inscribe {
     let change = None;
 
     let postage;
+    let postages;
     let destinations;
     let fee_utxos;
     let inscribe_on_specific_utxos;
     let inscriptions;
     let mode;
-    let parent_info;
+    let parent_infos;
     let next_inscriptions;
 
     let compress = false;
 
-        parent_info = Inscribe::get_parent_info(batchfile.parent, &index, &utxos, &client, chain, batchfile.parent_satpoint, no_wallet, None)?;
+        parent_infos = Inscribe::get_batch_parent_infos(&batchfile, &index, &utxos, &client, chain, no_wallet, batchfile.parent_destination(None)?)?;
 
         postage = batchfile
           .postage
           .map(Amount::from_sat)
           .unwrap_or(TARGET_POSTAGE);
 
+        postages = batchfile.postages();
+
         (inscriptions, destinations, inscribe_on_specific_utxos, fee_utxos) = batchfile.inscriptions(
           &client,
           chain,
-          parent_info.as_ref().map(|info| info.tx_out.value),
+          Some(parent_infos.iter().map(|info| info.tx_out.value).sum()),
           None,
           Amount::from_sat(0),
           compress,
           false,
           &mut utxos,
+          &BTreeMap::new(),
+          false,
+          None,
         )?;
         next_inscriptions = Vec::new();
 
@@ -764,32 +1305,52 @@ impl Inscribe {
     };
 
     Batch {
+      allow_dust_reveal_output: false,
+      budget: None,
+      change_value: None,
       commit_fee_rate: FeeRate::try_from(0.0).unwrap(),
       commit_only: false,
       commit_vsize,
       commitment: None,
       commitment_output: None,
       destinations,
+      double_check_fees: false,
       dump: true,
       dry_run: false,
+      emit_recovery: false,
+      export_signed: None,
+      fee_bump_reveal: None,
       fee_utxos,
       inscribe_on_specific_utxos,
       inscriptions,
+      internal_key: None,
       key,
+      max_inscription_postage: None,
       mode,
       next_inscriptions,
       no_backup: true,
       no_broadcast: true,
       no_limit: false,
       no_wallet,
-      parent_info,
+      only_common: false,
+      parent_infos,
+      parent_value: None,
       postage,
+      postages,
+      recovery_key_origin: None,
       reinscribe: false,
+      resume_from: None,
       reveal_fee: None,
       reveal_fee_rate: FeeRate::try_from(0.0).unwrap(),
+      reveal_change_target: None,
       reveal_input: Vec::new(),
+      reveal_input_scripts: Vec::new(),
+      reveal_input_values: Vec::new(),
+      reveal_input_sighash_type: TapSighashType::Default,
       reveal_psbt,
+      reveal_psbt_out: None,
       satpoint,
+      test_accept: false,
     }
     .inscribe(chain, &index, &client, &locked_utxos, runic_utxos, &mut utxos, Vec::new(), change)
   }
@@ -803,6 +1364,249 @@ mod tests {
     serde_yaml::{Mapping, Value},
   };
 
+  #[test]
+  fn reveal_vsize_estimate_matches_signed_vsize() {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let reveal_script = ScriptBuf::builder()
+      .push_slice(public_key.serialize())
+      .push_opcode(opcodes::all::OP_CHECKSIG)
+      .into_script();
+
+    let control_block = TaprootBuilder::new()
+      .add_leaf(0, reveal_script.clone())
+      .unwrap()
+      .finalize(&secp256k1, public_key)
+      .unwrap()
+      .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+      .unwrap();
+
+    for sighash_types in [
+      [TapSighashType::Default, TapSighashType::Default],
+      [TapSighashType::Default, TapSighashType::All],
+      [TapSighashType::All, TapSighashType::SinglePlusAnyoneCanPay],
+    ] {
+      let (mut reveal_tx, _fee, vsize) = Batch::build_reveal_transaction(
+        &control_block,
+        FeeRate::try_from(1.0).unwrap(),
+        vec![outpoint(1), outpoint(2)],
+        0,
+        vec![TxOut {
+          script_pubkey: recipient().script_pubkey(),
+          value: 10_000,
+        }],
+        &reveal_script,
+        &sighash_types,
+      );
+
+      for (input, hash_ty) in reveal_tx.input.iter_mut().zip(sighash_types) {
+        let signature = Signature {
+          sig: secp256k1.sign_schnorr(&secp256k1::Message::from_slice(&[1; 32]).unwrap(), &key_pair),
+          hash_ty,
+        };
+
+        input.witness.push(signature.to_vec());
+      }
+
+      reveal_tx.input[0].witness.push(&reveal_script);
+      reveal_tx.input[0].witness.push(control_block.serialize());
+
+      assert_eq!(reveal_tx.vsize() as u64, vsize, "{sighash_types:?}");
+    }
+  }
+
+  #[test]
+  fn reveal_sighash_types_follow_how_each_input_is_signed() {
+    assert_eq!(
+      Batch::default().reveal_sighash_types(3, 1),
+      [TapSighashType::Default; 3],
+    );
+
+    assert_eq!(
+      Batch {
+        reveal_input_sighash_type: TapSighashType::All,
+        ..Default::default()
+      }
+      .reveal_sighash_types(3, 1),
+      [TapSighashType::All, TapSighashType::Default, TapSighashType::All],
+    );
+  }
+
+  #[test]
+  fn change_target_input_order_fills_postage_ahead_of_the_target() {
+    assert_eq!(
+      Batch::change_target_input_order(&[1000, 2000, 3000], 2, 0, 20_000, 10_000),
+      [2, 0, 1],
+    );
+
+    assert_eq!(
+      Batch::change_target_input_order(&[1000, 2000, 3000], 2, 0, 7_500, 10_000),
+      [0, 1, 2],
+    );
+
+    assert_eq!(
+      Batch::change_target_input_order(&[3000, 2000, 3000], 2, 0, 7_500, 10_000),
+      [0, 2, 1],
+    );
+
+    assert_eq!(
+      Batch::change_target_input_order(&[1000, 2000, 3000], 2, 2500, 7_500, 10_000),
+      [2, 0, 1],
+    );
+  }
+
+  #[test]
+  fn allocate_fee_pool_splits_fees_after_postage_by_vsize() {
+    assert_eq!(
+      Batch::allocate_fee_pool(
+        Amount::from_sat(120_000),
+        Amount::from_sat(20_000),
+        200,
+        &[300, 500],
+      )
+      .unwrap(),
+      [Amount::from_sat(30_000), Amount::from_sat(50_000)],
+    );
+
+    assert_eq!(
+      Batch::allocate_fee_pool(Amount::from_sat(11_001), Amount::from_sat(10_000), 100, &[150]).unwrap(),
+      [Amount::from_sat(601)],
+    );
+  }
+
+  #[test]
+  fn allocate_fee_pool_errors_when_pool_is_short_of_postage_and_minimum_fees() {
+    assert_eq!(
+      Batch::allocate_fee_pool(Amount::from_sat(20_500), Amount::from_sat(20_000), 200, &[400])
+        .unwrap_err()
+        .to_string(),
+      "fee and inscribed utxos total 20500 sats, which doesn't cover postage of 20000 sats plus the minimum relay fee of 600 sats for the commit (200 vB) and reveals (400 vB)",
+    );
+  }
+
+  #[test]
+  fn check_reveal_psbt_fee_allows_up_to_dust_limit_difference() {
+    let tx = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint(1),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        script_pubkey: recipient().script_pubkey(),
+        value: 19_000,
+      }],
+    };
+
+    let prevout_values = [(outpoint(1), 20_000)].into_iter().collect::<BTreeMap<OutPoint, u64>>();
+
+    assert!(Batch::check_reveal_psbt_fee(&tx, &prevout_values, 1_000, 330).is_ok());
+    assert!(Batch::check_reveal_psbt_fee(&tx, &prevout_values, 1_330, 330).is_ok());
+
+    assert_eq!(
+      Batch::check_reveal_psbt_fee(&tx, &prevout_values, 1_331, 330)
+        .unwrap_err()
+        .to_string(),
+      "reveal_psbt pays a fee of 1000 sats, but the reveal should pay 1331 sats",
+    );
+  }
+
+  #[test]
+  fn check_reveal_psbt_fee_rejects_unknown_and_overspent_prevouts() {
+    let mut tx = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint(2),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        script_pubkey: recipient().script_pubkey(),
+        value: 30_000,
+      }],
+    };
+
+    let prevout_values = [(outpoint(1), 20_000)].into_iter().collect::<BTreeMap<OutPoint, u64>>();
+
+    assert_eq!(
+      Batch::check_reveal_psbt_fee(&tx, &prevout_values, 1_000, 330)
+        .unwrap_err()
+        .to_string(),
+      format!("input 0 of reveal_psbt spends unknown output {}", outpoint(2)),
+    );
+
+    tx.input[0].previous_output = outpoint(1);
+
+    assert_eq!(
+      Batch::check_reveal_psbt_fee(&tx, &prevout_values, 1_000, 330)
+        .unwrap_err()
+        .to_string(),
+      "reveal_psbt outputs (30000 sats) exceed its inputs (20000 sats)",
+    );
+  }
+
+  #[test]
+  fn fixture_transactions_are_the_same_every_run() {
+    let build = || {
+      Batch {
+        satpoint: Some(satpoint(1, 0)),
+        inscriptions: vec![inscription("text/plain", "ord")],
+        destinations: vec![recipient()],
+        postage: TARGET_POSTAGE,
+        mode: Mode::SharedOutput,
+        ..Batch::fixture()
+      }
+      .create_fixture_transactions(
+        BTreeMap::new(),
+        Chain::Mainnet,
+        BTreeSet::new(),
+        BTreeSet::new(),
+        [(outpoint(1), Amount::from_sat(20_000))].into_iter().collect(),
+        [change(0), change(1)],
+      )
+      .unwrap()
+    };
+
+    let (commit_tx, reveal_tx, _recovery_key_pair, fees) = build();
+    let (other_commit_tx, other_reveal_tx, _recovery_key_pair, other_fees) = build();
+
+    assert_eq!(commit_tx.txid(), other_commit_tx.txid());
+    assert_eq!(reveal_tx.txid(), other_reveal_tx.txid());
+    assert_eq!(
+      (fees.commit_fee, fees.commit_vsize, fees.reveal_fee, fees.reveal_vsize, fees.total),
+      (
+        other_fees.commit_fee,
+        other_fees.commit_vsize,
+        other_fees.reveal_fee,
+        other_fees.reveal_vsize,
+        other_fees.total,
+      ),
+    );
+
+    let commit = reveal_tx.input[0].previous_output;
+
+    assert_eq!(commit.txid, commit_tx.txid());
+    assert_eq!(fees.reveal_vsize, u64::try_from(reveal_tx.vsize()).unwrap());
+    assert_eq!(
+      fees.commit_fee,
+      20_000 - commit_tx.output.iter().map(|output| output.value).sum::<u64>(),
+    );
+    assert_eq!(
+      fees.reveal_fee,
+      commit_tx.output[usize::try_from(commit.vout).unwrap()].value
+        - reveal_tx.output.iter().map(|output| output.value).sum::<u64>(),
+    );
+    assert_eq!(fees.total, fees.commit_fee + fees.reveal_fee);
+    assert_eq!(fees.total_postage, TARGET_POSTAGE.to_sat());
+  }
+
   #[test]
   fn reveal_transaction_pays_fee() {
     let utxos = vec![(outpoint(1), Amount::from_sat(20000))];
@@ -813,7 +1617,7 @@ mod tests {
 
     let (commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint: Some(satpoint(1, 0)),
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -822,9 +1626,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       BTreeMap::new(),
       Chain::Mainnet,
       BTreeSet::new(),
@@ -854,7 +1658,7 @@ mod tests {
 
     let (commit_tx, reveal_tx, _, _) = Batch {
       satpoint: Some(satpoint(1, 0)),
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -863,9 +1667,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       BTreeMap::new(),
       Chain::Mainnet,
       BTreeSet::new(),
@@ -898,7 +1702,7 @@ mod tests {
 
     let error = Batch {
       satpoint,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -907,9 +1711,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       inscriptions,
       Chain::Mainnet,
       BTreeSet::new(),
@@ -949,7 +1753,7 @@ mod tests {
 
     assert!(Batch {
       satpoint,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -958,9 +1762,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       inscriptions,
       Chain::Mainnet,
       BTreeSet::new(),
@@ -994,7 +1798,7 @@ mod tests {
 
     let (commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(fee_rate).unwrap(),
@@ -1003,9 +1807,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1077,7 +1881,7 @@ mod tests {
 
     let (commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_infos: vec![parent_info.clone()],
       inscriptions: vec![child_inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(fee_rate).unwrap(),
@@ -1086,9 +1890,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1159,7 +1963,7 @@ mod tests {
 
     let (commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(commit_fee_rate).unwrap(),
@@ -1168,9 +1972,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1217,7 +2021,7 @@ mod tests {
 
     let error = Batch {
       satpoint,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -1226,9 +2030,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       BTreeMap::new(),
       Chain::Mainnet,
       BTreeSet::new(),
@@ -1257,7 +2061,7 @@ mod tests {
 
     let (_commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions: vec![inscription],
       destinations: vec![reveal_address],
       commit_fee_rate: FeeRate::try_from(1.0).unwrap(),
@@ -1266,9 +2070,9 @@ mod tests {
       reinscribe: false,
       postage: TARGET_POSTAGE,
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       BTreeMap::new(),
       Chain::Mainnet,
       BTreeSet::new(),
@@ -1346,12 +2150,12 @@ inscriptions:
       Batchfile {
         inscriptions: vec![
           BatchEntry {
-            file: inscription_path,
+            file: Some(inscription_path),
             metadata: Some(Value::Mapping(metadata)),
             ..Default::default()
           },
           BatchEntry {
-            file: brc20_path,
+            file: Some(brc20_path),
             metaprotocol: Some("brc-20".to_string()),
             ..Default::default()
           }
@@ -1362,6 +2166,26 @@ inscriptions:
     );
   }
 
+  #[test]
+  fn content_type_map_overrides_extension() {
+    let tempdir = TempDir::new().unwrap();
+    let map_path = tempdir.path().join("content-types.yaml");
+    fs::write(&map_path, "'.GLB': model/gltf-binary\nfoo: application/x-foo\n").unwrap();
+
+    let map = load_content_type_map(&map_path).unwrap();
+
+    assert_eq!(
+      mapped_content_type(&map, Path::new("model.glb")),
+      Some("model/gltf-binary".into())
+    );
+    assert_eq!(
+      mapped_content_type(&map, Path::new("thing.FOO")),
+      Some("application/x-foo".into())
+    );
+    assert_eq!(mapped_content_type(&map, Path::new("image.png")), None);
+    assert_eq!(mapped_content_type(&map, Path::new("README")), None);
+  }
+
   #[test]
   fn batch_with_unknown_field_throws_error() {
     let tempdir = TempDir::new().unwrap();
@@ -1378,6 +2202,206 @@ inscriptions:
       .contains("unknown field `unknown`"));
   }
 
+  #[test]
+  fn batch_with_misspelled_field_suggests_closest_field() {
+    let tempdir = TempDir::new().unwrap();
+    let batch_path = tempdir.path().join("batch.yaml");
+    fs::write(
+      &batch_path,
+      "mode: shared-output\ninscriptions:\n- file: meow.wav\n  postages: 1000\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+      Batchfile::load(&batch_path).unwrap_err().to_string(),
+      format!(
+        "unknown field `postages` at line 4 column 3 of batch file {}, did you mean `postage`?",
+        batch_path.display()
+      )
+    );
+  }
+
+  #[test]
+  fn batch_entry_with_delegate_needs_no_file() {
+    let delegate = inscription_id(1);
+
+    let tempdir = TempDir::new().unwrap();
+    let batch_path = tempdir.path().join("batch.yaml");
+    fs::write(
+      &batch_path,
+      format!("mode: separate-outputs\ninscriptions:\n- delegate: {delegate}\n- delegate: {delegate}\n"),
+    )
+    .unwrap();
+
+    let batchfile = Batchfile::load(&batch_path).unwrap();
+
+    assert_eq!(batchfile.inscriptions[0].file, None);
+    assert_eq!(batchfile.inscriptions[1].delegate, Some(delegate));
+  }
+
+  #[test]
+  fn batch_entry_with_delegate_and_non_empty_file_throws_error() {
+    let tempdir = TempDir::new().unwrap();
+    let file_path = tempdir.path().join("tulip.txt");
+    fs::write(&file_path, "tulips are pretty").unwrap();
+
+    let batch_path = tempdir.path().join("batch.yaml");
+    fs::write(
+      &batch_path,
+      format!(
+        "mode: separate-outputs\ninscriptions:\n- file: {}\n  delegate: {}\n",
+        file_path.display(),
+        inscription_id(1)
+      ),
+    )
+    .unwrap();
+
+    assert!(Batchfile::load(&batch_path)
+      .unwrap_err()
+      .to_string()
+      .contains("inscription 0 has both a non-empty `file`"));
+  }
+
+  #[test]
+  fn batch_entry_with_unsupported_content_encoding_throws_error() {
+    let tempdir = TempDir::new().unwrap();
+    let batch_path = tempdir.path().join("batch.yaml");
+    fs::write(
+      &batch_path,
+      "mode: separate-outputs\ninscriptions:\n- file: tulip.svg\n  content_encoding: gzip\n- file: tulip.png\n  content_encoding: zstd\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+      Batchfile::load(&batch_path).unwrap_err().to_string(),
+      "inscription 1 has unsupported `content_encoding` `zstd`; supported encodings are `br`, `gzip`"
+    );
+  }
+
+  #[test]
+  fn batch_metadata_over_max_standard_tx_weight_throws_error() {
+    let oversized = "a".repeat(MAX_STANDARD_TX_WEIGHT as usize);
+
+    let batchfile = Batchfile {
+      inscriptions: vec![
+        BatchEntry {
+          file: Some("inscription.txt".into()),
+          metadata_json: Some(serde_json::Value::String("small".into())),
+          ..Default::default()
+        },
+        BatchEntry {
+          file: Some("inscription.txt".into()),
+          metadata: Some(serde_yaml::Value::String(oversized.clone())),
+          ..Default::default()
+        },
+      ],
+      ..Default::default()
+    };
+
+    assert_eq!(
+      batchfile.check_metadata_size().unwrap_err().to_string(),
+      format!(
+        "inscription 1 has {} bytes of CBOR metadata, bringing the batch's total to {} bytes, which would push the reveal transaction past MAX_STANDARD_TX_WEIGHT of {MAX_STANDARD_TX_WEIGHT} weight units; use --no-limit to inscribe it anyway",
+        MAX_STANDARD_TX_WEIGHT + 5,
+        MAX_STANDARD_TX_WEIGHT + 5 + 6,
+      )
+    );
+
+    let batchfile = Batchfile {
+      inscriptions: vec![BatchEntry {
+        file: Some("inscription.txt".into()),
+        metadata_json: Some(serde_json::Value::String(oversized)),
+        ..Default::default()
+      }],
+      ..Default::default()
+    };
+
+    assert!(batchfile.check_metadata_size().is_err());
+  }
+
+  #[test]
+  fn batch_metadata_adding_up_past_max_standard_tx_weight_throws_error() {
+    let share = MAX_STANDARD_TX_WEIGHT as usize / 10;
+
+    let batchfile = Batchfile {
+      inscriptions: (0..10)
+        .map(|_| BatchEntry {
+          file: Some("inscription.txt".into()),
+          metadata: Some(serde_yaml::Value::String("a".repeat(share))),
+          ..Default::default()
+        })
+        .collect(),
+      ..Default::default()
+    };
+
+    // each entry is a three byte CBOR string header plus its share
+    assert_eq!(
+      batchfile.check_metadata_size().unwrap_err().to_string(),
+      format!(
+        "inscription 9 has {} bytes of CBOR metadata, bringing the batch's total to {} bytes, which would push the reveal transaction past MAX_STANDARD_TX_WEIGHT of {MAX_STANDARD_TX_WEIGHT} weight units; use --no-limit to inscribe it anyway",
+        share + 3,
+        10 * (share + 3),
+      )
+    );
+
+    let batchfile = Batchfile {
+      inscriptions: batchfile.inscriptions[..9].to_vec(),
+      ..Default::default()
+    };
+
+    assert!(batchfile.check_metadata_size().is_ok());
+  }
+
+  #[test]
+  fn batch_parent_destination() {
+    let tempdir = TempDir::new().unwrap();
+    let batch_path = tempdir.path().join("batch.yaml");
+    fs::write(
+      &batch_path,
+      "mode: shared-output\nparent: 8d363b28528b0cb86b5fd48615493fb175bdf132d2a3d20b4251bba3f130a5abi0\nparent_destination: bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\ninscriptions:\n- file: meow.wav\n",
+    )
+    .unwrap();
+
+    let batchfile = Batchfile::load(&batch_path).unwrap();
+
+    assert_eq!(
+      batchfile.parent_destination(None).unwrap(),
+      Some(
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+          .parse::<Address<NetworkUnchecked>>()
+          .unwrap()
+      )
+    );
+
+    assert!(batchfile
+      .parent_destination(Some(
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+          .parse::<Address<NetworkUnchecked>>()
+          .unwrap()
+      ))
+      .unwrap_err()
+      .to_string()
+      .contains("don't use `--parent-destination`"));
+  }
+
+  #[test]
+  fn batch_parent_destination_requires_parent() {
+    let tempdir = TempDir::new().unwrap();
+    let batch_path = tempdir.path().join("batch.yaml");
+    fs::write(
+      &batch_path,
+      "mode: shared-output\nparent_destination: bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\ninscriptions:\n- file: meow.wav\n",
+    )
+    .unwrap();
+
+    assert!(Batchfile::load(&batch_path)
+      .unwrap()
+      .parent_destination(None)
+      .unwrap_err()
+      .to_string()
+      .contains("`parent_destination` can only be set in a batchfile with a `parent`"));
+  }
+
   #[test]
   fn batch_inscribe_with_parent() {
     let utxos = vec![
@@ -1430,7 +2454,7 @@ inscriptions:
 
     let (commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_infos: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: fee_rate,
@@ -1439,9 +2463,9 @@ inscriptions:
       reinscribe: false,
       postage: Amount::from_sat(10_000),
       mode,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       wallet_inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1530,7 +2554,7 @@ inscriptions:
 
     let error = Batch {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_infos: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: 4.0.try_into().unwrap(),
@@ -1539,9 +2563,9 @@ inscriptions:
       reinscribe: false,
       postage: Amount::from_sat(10_000),
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       wallet_inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1608,7 +2632,7 @@ inscriptions:
 
     let _ = Batch {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_infos: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: 4.0.try_into().unwrap(),
@@ -1617,9 +2641,9 @@ inscriptions:
       reinscribe: false,
       postage: Amount::from_sat(10_000),
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       wallet_inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1646,7 +2670,7 @@ inscriptions:
 
     let error = Batch {
       satpoint: None,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: 1.0.try_into().unwrap(),
@@ -1655,9 +2679,9 @@ inscriptions:
       reinscribe: false,
       postage: Amount::from_sat(30_000),
       mode: Mode::SharedOutput,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       wallet_inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1699,7 +2723,7 @@ inscriptions:
 
     let (_commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint: None,
-      parent_info: None,
+      parent_infos: Vec::new(),
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: fee_rate,
@@ -1708,9 +2732,9 @@ inscriptions:
       reinscribe: false,
       postage: Amount::from_sat(10_000),
       mode,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       wallet_inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1779,7 +2803,7 @@ inscriptions:
 
     let (commit_tx, reveal_tx, _private_key, _) = Batch {
       satpoint: None,
-      parent_info: Some(parent_info.clone()),
+      parent_infos: vec![parent_info.clone()],
       inscriptions,
       destinations: reveal_addresses,
       commit_fee_rate: fee_rate,
@@ -1788,9 +2812,9 @@ inscriptions:
       reinscribe: false,
       postage: Amount::from_sat(10_000),
       mode,
-      ..Default::default()
+      ..Batch::fixture()
     }
-    .create_batch_inscription_transactions(
+    .create_fixture_transactions(
       wallet_inscriptions,
       Chain::Signet,
       BTreeSet::new(),
@@ -1842,6 +2866,54 @@ inscriptions:
     );
   }
 
+  #[test]
+  fn calculate_fee_errors_on_unknown_input() {
+    let tx = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+      output: vec![tx_out(1000, address())],
+    };
+
+    assert_eq!(
+      Batch::calculate_fee(&tx, &BTreeMap::from([(outpoint(1), Amount::from_sat(5000))]))
+        .unwrap_err()
+        .to_string(),
+      format!(
+        "transaction {} spends output {}, whose value is unknown",
+        tx.txid(),
+        outpoint(2)
+      ),
+    );
+  }
+
+  #[test]
+  fn calculate_fee_errors_on_overspent_inputs() {
+    let tx = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![tx_in(outpoint(1))],
+      output: vec![tx_out(3000, address()), tx_out(2500, change(0))],
+    };
+
+    let utxos = BTreeMap::from([(outpoint(1), Amount::from_sat(5000))]);
+
+    assert_eq!(
+      Batch::calculate_fee(&tx, &utxos).unwrap_err().to_string(),
+      format!(
+        "transaction {} spends 5000 sats but its outputs hold 5500 sats",
+        tx.txid()
+      ),
+    );
+
+    let tx = Transaction {
+      output: vec![tx_out(3000, address()), tx_out(1500, change(0))],
+      ..tx
+    };
+
+    assert_eq!(Batch::calculate_fee(&tx, &utxos).unwrap(), 500);
+  }
+
   #[test]
   fn example_batchfile_deserializes_successfully() {
     Batchfile::load(Path::new("batch.yaml")).unwrap();
@@ -1,68 +1,178 @@
 use super::*;
 
+// the encodings `Inscription::from_file` can produce, and that `decompressed_body` can undo
+const SUPPORTED_CONTENT_ENCODINGS: &[&str] = &["br", "gzip"];
+
 pub(super) struct Batch {
+  pub(super) allow_dust_reveal_output: bool,
+  pub(super) budget: Option<Amount>,
+  pub(super) change_value: Option<Amount>,
   pub(super) commit_fee_rate: FeeRate,
   pub(super) commit_only: bool,
   pub(super) commit_vsize: Option<u64>,
   pub(super) commitment: Option<OutPoint>,
   pub(super) commitment_output: Option<GetRawTransactionResultVout>,
   pub(super) destinations: Vec<Address>,
+  pub(super) double_check_fees: bool,
   pub(super) dump: bool,
+  pub(super) emit_recovery: bool,
   pub(super) dry_run: bool,
+  pub(super) export_signed: Option<PathBuf>,
+  pub(super) fee_bump_reveal: Option<FeeRate>,
   pub(super) fee_utxos: Vec<OutPoint>,
   pub(super) inscribe_on_specific_utxos: bool,
   pub(super) inscriptions: Vec<Inscription>,
+  pub(super) internal_key: Option<XOnlyPublicKey>,
   pub(super) key: Option<String>,
+  pub(super) max_inscription_postage: Option<Amount>,
   pub(super) mode: Mode,
   pub(super) next_inscriptions: Vec<Inscription>,
   pub(super) no_backup: bool,
   pub(super) no_broadcast: bool,
   pub(super) no_limit: bool,
   pub(super) no_wallet: bool,
-  pub(super) parent_info: Option<ParentInfo>,
+  pub(super) only_common: bool,
+  pub(super) parent_infos: Vec<ParentInfo>,
+  pub(super) parent_value: Option<u64>,
   pub(super) postage: Amount,
+  pub(super) postages: Vec<Option<Amount>>,
+  pub(super) recovery_key_origin: Option<DerivationPath>,
   pub(super) reinscribe: bool,
+  pub(super) resume_from: Option<PathBuf>,
   pub(super) reveal_fee: Option<Amount>,
   pub(super) reveal_fee_rate: FeeRate,
+  pub(super) reveal_change_target: Option<SatPoint>,
   pub(super) reveal_input: Vec<OutPoint>,
+  pub(super) reveal_input_scripts: Vec<ScriptBuf>,
+  pub(super) reveal_input_values: Vec<u64>,
+  pub(super) reveal_input_sighash_type: TapSighashType,
   pub(super) reveal_psbt: Option<Psbt>,
+  pub(super) reveal_psbt_out: Option<PathBuf>,
   pub(super) satpoint: Option<SatPoint>,
+  pub(super) test_accept: bool,
 }
 
 impl Default for Batch {
   fn default() -> Batch {
     Batch {
+      allow_dust_reveal_output: false,
+      budget: None,
+      change_value: None,
       commit_fee_rate: 1.0.try_into().unwrap(),
       commit_only: false,
       commit_vsize: None,
       commitment: None,
       commitment_output: None,
       destinations: Vec::new(),
+      double_check_fees: false,
       dump: false,
       dry_run: false,
+      emit_recovery: false,
+      export_signed: None,
+      fee_bump_reveal: None,
       fee_utxos: Vec::new(),
       inscribe_on_specific_utxos: false,
       inscriptions: Vec::new(),
+      internal_key: None,
       key: None,
+      max_inscription_postage: None,
       mode: Mode::SharedOutput,
       next_inscriptions: Vec::new(),
       no_backup: false,
       no_broadcast: false,
       no_limit: false,
       no_wallet: false,
-      parent_info: None,
+      only_common: false,
+      parent_infos: Vec::new(),
+      parent_value: None,
       postage: Amount::from_sat(10_000),
+      postages: Vec::new(),
+      recovery_key_origin: None,
       reinscribe: false,
+      resume_from: None,
       reveal_fee: None,
       reveal_fee_rate: 1.0.try_into().unwrap(),
+      reveal_change_target: None,
       reveal_input: Vec::new(),
+      reveal_input_scripts: Vec::new(),
+      reveal_input_values: Vec::new(),
+      reveal_input_sighash_type: TapSighashType::Default,
       reveal_psbt: None,
+      reveal_psbt_out: None,
       satpoint: None,
+      test_accept: false,
+    }
+  }
+}
+
+#[cfg(test)]
+impl Batch {
+  // the WIF fixture batches commit with, so their reveal script and commit address, and so their txids,
+  // are the same every run
+  pub(super) const FIXTURE_KEY: &'static str = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+
+  // a batch whose transactions depend only on its fields and the utxos they're built from
+  pub(super) fn fixture() -> Batch {
+    Batch {
+      key: Some(Self::FIXTURE_KEY.into()),
+      ..Default::default()
     }
   }
+
+  // build the commit and reveal against a fresh mock node and index rather than a live bitcoind, so tests
+  // can assert exact txids, vsizes, and fees
+  pub(super) fn create_fixture_transactions(
+    &self,
+    wallet_inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    chain: Chain,
+    locked_utxos: BTreeSet<OutPoint>,
+    runic_utxos: BTreeSet<OutPoint>,
+    utxos: BTreeMap<OutPoint, Amount>,
+    change: [Address; 2],
+  ) -> Result<(Transaction, Transaction, TweakedKeyPair, Fees)> {
+    let context = crate::index::testing::Context::builder().chain(chain).build();
+    let client = context.options.bitcoin_rpc_client(None)?;
+
+    let (commit_tx, reveal_tx, recovery_key_pair, fees, _commit_psbt) = self
+      .create_batch_inscription_transactions(
+        wallet_inscriptions,
+        &context.index,
+        chain,
+        locked_utxos,
+        runic_utxos,
+        utxos,
+        Some(change),
+        Vec::new(),
+        &client,
+      )?;
+
+    Ok((
+      commit_tx.unwrap(),
+      reveal_tx.unwrap(),
+      recovery_key_pair.unwrap(),
+      fees.unwrap(),
+    ))
+  }
 }
 
 impl Batch {
+  // the sighash type the commit input of the reveal is signed with
+  const REVEAL_SIGHASH_TYPE: TapSighashType = TapSighashType::Default;
+
+  // we sign the commit input ourselves and bitcoind signs the rest, the parents and --reveal-inputs,
+  // with --reveal-input-sighash, so each input's dummy signature is sized the way it'll be signed
+  pub(super) fn reveal_sighash_types(&self, input_count: usize, commit_input: usize) -> Vec<TapSighashType> {
+    (0..input_count)
+      .map(|input| {
+        if input == commit_input {
+          Self::REVEAL_SIGHASH_TYPE
+        } else {
+          self.reveal_input_sighash_type
+        }
+      })
+      .collect()
+  }
+
   pub(crate) fn inscribe(
     &self,
     chain: Chain,
@@ -85,9 +195,6 @@ impl Batch {
       if self.commit_fee_rate != FeeRate::try_from(0.0)? {
         return Err(anyhow!("don't use `--commit-fee-rate` when using specific utxos to pay fees; the rate will be calculated from the size of the fee utxo(s)"));
       }
-      if !force_input.is_empty() {
-        return Err(anyhow!("don't use `--commit-input` when using specific utxos to pay fees"));
-      }
 
       for outpoint in &self.fee_utxos {
         if !utxos.contains_key(&outpoint) {
@@ -96,14 +203,31 @@ impl Batch {
       }
     }
 
+    // fee utxos are always spent by the commit, along with any --commit-input that isn't one of them
     let force_input = if self.fee_utxos.is_empty() {
       force_input
     } else {
-      self.fee_utxos.clone()
+      let mut merged = self.fee_utxos.clone();
+
+      for outpoint in force_input {
+        if merged.contains(&outpoint) {
+          continue;
+        }
+
+        if !utxos.contains_key(&outpoint) {
+          utxos.insert(outpoint, Amount::from_sat(client.get_raw_transaction(&outpoint.txid, None)?.output[outpoint.vout as usize].value));
+        }
+
+        merged.push(outpoint);
+      }
+
+      merged
     };
 
+    // without a wallet to ask for change addresses, commit change goes to the caller's --change, and
+    // the transaction builder errors out if there's change and nowhere to send it
     let commit_tx_change = if self.no_wallet {
-      None
+      change.map(|change| [change.clone(), change])
     } else {
       Some([
       get_change_address(client, chain)?,
@@ -113,7 +237,7 @@ impl Batch {
       },
     ])};
 
-    let (commit_tx, reveal_tx, recovery_key_pair, total_fees, dummy_commit_psbt) = self
+    let (commit_tx, reveal_tx, recovery_key_pair, fees, dummy_commit_psbt) = self
       .create_batch_inscription_transactions(
         wallet_inscriptions,
         index,
@@ -137,10 +261,35 @@ impl Batch {
     let commit_tx = commit_tx.unwrap();
     let mut reveal_tx = reveal_tx.unwrap();
     let recovery_key_pair = recovery_key_pair.unwrap();
-    let total_fees = total_fees.unwrap();
+    let fees = fees.unwrap();
+    let total_fees = fees.total;
+
+    if let Some(path) = &self.reveal_psbt_out {
+      if self.commit_only {
+        bail!("--reveal-psbt-out doesn't work with --commit-only");
+      }
+
+      let commit_input = self.parent_infos.len();
+
+      let (commit, commit_output) = if let Some(commitment) = self.commitment {
+        let commitment_output = self.commitment_output.clone().unwrap();
+        (commitment, TxOut {
+          value: commitment_output.value.to_sat(),
+          script_pubkey: commitment_output.script_pub_key.script()?,
+        })
+      } else {
+        let commit = reveal_tx.input[commit_input].previous_output;
+        (commit, commit_tx.output[commit.vout as usize].clone())
+      };
+
+      let reveal_psbt = Self::unsigned_reveal_psbt(&reveal_tx, commit, commit_output)?;
+
+      fs::write(path, format!("{}\n", general_purpose::STANDARD.encode(reveal_psbt.serialize())))
+        .with_context(|| format!("failed to write reveal psbt to {}", path.display()))?;
+    }
 
     if self.dry_run {
-      return Ok(self.output(
+      let mut output = self.output(
         if self.commitment.is_some() {
           None
         } else {
@@ -160,20 +309,26 @@ impl Batch {
         total_fees,
         self.inscriptions.clone(),
         utxos,
-      ));
+      );
+
+      output.commit_fee = Some(fees.commit_fee);
+      output.commit_vsize = Some(fees.commit_vsize);
+      output.reveal_fee = Some(fees.reveal_fee);
+      output.reveal_vsize = Some(fees.reveal_vsize);
+      output.total_postage = Some(fees.total_postage);
+
+      return Ok(output);
     }
 
     let signed_commit_tx = if self.commitment.is_some() || self.no_wallet {
       Vec::new()
     } else {
-      client
-      .sign_raw_transaction_with_wallet(&commit_tx, None, None)?
-      .hex
+      sign_transaction(client, &commit_tx, None)?
     };
 
     let mut reveal_input_info = Vec::new();
 
-    if self.parent_info.is_some() {
+    if !self.parent_infos.is_empty() {
       for (vout, output) in commit_tx.output.iter().enumerate() {
         reveal_input_info.push(SignRawTransactionInput {
           txid: commit_tx.txid(),
@@ -185,8 +340,33 @@ impl Batch {
       }
     }
 
-    for input in &self.reveal_input {
-      let output = index.get_transaction(input.txid)?.unwrap().output[input.vout as usize].clone();
+    // what each reveal input spends, by our own reckoning, to check a supplied reveal_psbt's fee against
+    let mut prevout_values = BTreeMap::new();
+
+    for parent_info in &self.parent_infos {
+      prevout_values.insert(parent_info.location.outpoint, parent_info.tx_out.value);
+    }
+
+    match self.commitment {
+      Some(commitment) => {
+        prevout_values.insert(commitment, self.commitment_output.clone().unwrap().value.to_sat());
+      }
+      None => {
+        for (vout, output) in commit_tx.output.iter().enumerate() {
+          prevout_values.insert(
+            OutPoint {
+              txid: commit_tx.txid(),
+              vout: vout.try_into().unwrap(),
+            },
+            output.value,
+          );
+        }
+      }
+    }
+
+    for (i, input) in self.reveal_input.iter().enumerate() {
+      let output = self.reveal_input_prevout(index, client, i)?;
+      prevout_values.insert(*input, output.value);
       reveal_input_info.push(SignRawTransactionInput {
         txid: input.txid,
         vout: input.vout,
@@ -196,16 +376,10 @@ impl Batch {
       });
     }
 
-    let signed_reveal_tx = if (reveal_input_info.is_empty() && self.parent_info.is_none()) || self.no_wallet {
+    let signed_reveal_tx = if (reveal_input_info.is_empty() && self.parent_infos.is_empty()) || self.no_wallet {
       consensus::encode::serialize(&reveal_tx)
     } else {
-      client
-        .sign_raw_transaction_with_wallet(
-          &reveal_tx,
-          Some(&reveal_input_info),
-          None,
-        )?
-        .hex
+      sign_transaction_with_sighash(client, &reveal_tx, Some(&reveal_input_info), self.reveal_input_sighash_type)?
     };
 
     if self.no_wallet {
@@ -242,6 +416,14 @@ impl Batch {
           return Err(anyhow!("supplied reveal_psbt has {} inputs but should have {}", extracted_tx.input.len(), reveal_tx.input.len()));
         }
 
+        // the signer chose the prevouts it signed over, so check the fee before copying any of its witnesses
+        Self::check_reveal_psbt_fee(
+          &extracted_tx,
+          &prevout_values,
+          fees.reveal_fee,
+          reveal_tx.output[self.parent_infos.len()].script_pubkey.dust_value().to_sat(),
+        )?;
+
         for (i, input) in extracted_tx.input.iter().enumerate() {
           if input.previous_output != reveal_tx.input[i].previous_output {
             return Err(anyhow!("prevout of input {i} of reveal_psbt is incorrect"));
@@ -301,7 +483,7 @@ impl Batch {
 
       return Ok(self.output(None, None, None,
                             Some(commit_tx_hex),
-                            Some(if self.parent_info.is_none() {
+                            Some(if self.parent_infos.is_empty() {
                               "sign commit_psbt, then broadcast the signed result and reveal_hex"
                             } else {
                               "sign commit_psbt and reveal_hex, then broadcast them both. or sign the reveal_psbt, add it to the input json, and run the /inscribe endpoint again"
@@ -311,50 +493,84 @@ impl Batch {
                             None, 0, Vec::new(), &BTreeMap::new()));
     }
 
-    if !self.no_backup && self.key.is_none() {
-      Self::backup_recovery_key(client, recovery_key_pair, chain.network())?;
+    if !self.no_backup && self.key.is_none() && self.internal_key.is_none() {
+      Self::backup_recovery_key(client, recovery_key_pair, chain.network(), self.recovery_key_origin.as_ref())?;
     }
 
-    let (commit, reveal) = if self.no_broadcast {
-      (if self.commitment.is_some() { None }
-      	  else { Some(client.decode_raw_transaction(&signed_commit_tx, None)?.txid) },
-       if self.commit_only { None }
-       	  else { Some(client.decode_raw_transaction(&signed_reveal_tx, None)?.txid) })
-    } else {
     let commit = if self.commitment.is_some() {
       None
     } else {
-      Some(client.send_raw_transaction(&signed_commit_tx)?)
+      Some(client.decode_raw_transaction(&signed_commit_tx, None)?.txid)
     };
 
     let reveal = if self.commit_only {
       None
     } else {
-    match client.send_raw_transaction(&signed_reveal_tx) {
-    Ok(txid) => Some(txid),
-    Err(err) => {
-      return Err(anyhow!(
-        format!("Failed to send reveal transaction: {err}{}", if commit.is_some() { format!("\nCommit tx {:?} will be recovered once mined", commit) } else { "".to_string() })
-      ))
-    }
-    }
+      Some(client.decode_raw_transaction(&signed_reveal_tx, None)?.txid)
     };
 
-    (commit, reveal)
-    };
-
-    Ok(self.output(
+    let output = self.output(
       commit,
       reveal,
       if self.dump && self.commitment.is_none() { Some(signed_commit_tx.raw_hex()) } else { None },
       None, None,
       if self.dump && !self.commit_only { Some(signed_reveal_tx.raw_hex()) } else { None },
       None,
-      if self.dump { Some(Self::get_recovery_key(&client, recovery_key_pair, chain.network())?.to_string()) } else { None },
+      if (self.dump || self.emit_recovery || self.export_signed.is_some()) && self.internal_key.is_none() { Some(Self::get_recovery_key(&client, recovery_key_pair, chain.network(), self.recovery_key_origin.as_ref())?.to_string()) } else { None },
       total_fees,
       self.inscriptions.clone(),
       utxos,
-    ))
+    );
+
+    if self.no_broadcast {
+      return Ok(output);
+    }
+
+    if self.test_accept {
+      Self::test_accept(client, &signed_commit_tx, &signed_reveal_tx, commit.is_some(), reveal.is_some())?;
+    }
+
+    let mut progress = Progress {
+      commit: commit.map(|_| signed_commit_tx.raw_hex()),
+      commit_broadcast: false,
+      reveal: reveal.map(|_| signed_reveal_tx.raw_hex()),
+      reveal_broadcast: false,
+      output,
+    };
+
+    if let Some(path) = &self.export_signed {
+      progress.check_reveal_spends_commit()?;
+      progress.save(path)?;
+      return Ok(progress.output);
+    }
+
+    if let Some(path) = &self.resume_from {
+      progress.save(path)?;
+    }
+
+    progress.broadcast(client, self.resume_from.as_deref())
+  }
+
+  pub(crate) fn resume(path: &Path, client: &Client) -> Result<super::Output> {
+    let progress: Progress = serde_json::from_reader(File::open(path)?)
+      .with_context(|| format!("failed to load resume file {}", path.display()))?;
+
+    if (progress.commit.is_none() || progress.commit_broadcast)
+      && (progress.reveal.is_none() || progress.reveal_broadcast)
+    {
+      eprintln!("everything in {} has already been broadcast", path.display());
+    }
+
+    progress.broadcast(client, Some(path))
+  }
+
+  pub(crate) fn broadcast_exported(path: &Path, client: &Client) -> Result<super::Output> {
+    let progress: Progress = serde_json::from_reader(File::open(path)?)
+      .with_context(|| format!("failed to load exported transactions {}", path.display()))?;
+
+    progress.check_reveal_spends_commit()?;
+
+    progress.broadcast(client, Some(path))
   }
 
   fn output(
@@ -374,16 +590,21 @@ impl Batch {
     if commit_psbt.is_some() {
       return super::Output {
         commit: None,
+        commit_fee: None,
         commit_hex: None,
         commit_psbt,
+        commit_vsize: None,
         inscriptions: Vec::new(),
         message,
         parent: None,
         recovery_descriptor: None,
         reveal: None,
+        reveal_fee: None,
         reveal_hex,
         reveal_psbt,
+        reveal_vsize: None,
         total_fees: 0,
+        total_postage: None,
       };
     }
 
@@ -392,21 +613,12 @@ impl Batch {
     for index in 0..inscriptions.len() {
       let index = u32::try_from(index).unwrap();
 
+      // the parents' outputs come first
+      let parents = u32::try_from(self.parent_infos.len()).unwrap();
+
       let vout = match self.mode {
-        Mode::SharedOutput | Mode::SameSat => {
-          if self.parent_info.is_some() {
-            1
-          } else {
-            0
-          }
-        }
-        Mode::SeparateOutputs => {
-          if self.parent_info.is_some() {
-            index + 1
-          } else {
-            index
-          }
-        }
+        Mode::SharedOutput | Mode::SameSat => parents,
+        Mode::SeparateOutputs => parents + index,
       };
 
       if !self.commit_only {
@@ -433,15 +645,20 @@ impl Batch {
 
     super::Output {
       commit,
+      commit_fee: None,
       commit_hex,
       commit_psbt: None,
+      commit_vsize: None,
       message: None,
       reveal,
+      reveal_fee: None,
       reveal_hex,
       reveal_psbt: None,
+      reveal_vsize: None,
       recovery_descriptor,
       total_fees,
-      parent: self.parent_info.clone().map(|info| info.id),
+      total_postage: None,
+      parent: self.parent_infos.first().map(|info| info.id),
       inscriptions: inscriptions_output,
     }
   }
@@ -457,12 +674,12 @@ impl Batch {
     change: Option<[Address; 2]>,
     force_input: Vec<OutPoint>,
     client: &Client,
-  ) -> Result<(Option<Transaction>, Option<Transaction>, Option<TweakedKeyPair>, Option<u64>, Option<String>)> {
-    if let Some(parent_info) = &self.parent_info {
+  ) -> Result<(Option<Transaction>, Option<Transaction>, Option<TweakedKeyPair>, Option<Fees>, Option<String>)> {
+    for parent_info in &self.parent_infos {
       assert!(self
         .inscriptions
         .iter()
-        .all(|inscription| inscription.parent().unwrap() == parent_info.id))
+        .all(|inscription| inscription.parents().contains(&parent_info.id)))
     }
 
     if !self.fee_utxos.is_empty() && !self.inscribe_on_specific_utxos {
@@ -473,10 +690,38 @@ impl Batch {
       return Err(anyhow!("--next-batch and --next-file don't work without --commitment"));
     }
 
+    if self.change_value.is_some() && !self.fee_utxos.is_empty() {
+      return Err(anyhow!("--change-value doesn't work when specifying fee_utxos, whose change all goes to fees"));
+    }
+
     if !self.fee_utxos.is_empty() && self.reveal_fee.is_some() {
       return Err(anyhow!("--reveal-fee doesn't work when specifying fee_utxos"));
     }
 
+    if self.parent_value.is_some() && self.parent_infos.is_empty() {
+      return Err(anyhow!("--parent-value only works when inscribing with a parent"));
+    }
+
+    if self.parent_value.is_some() && self.parent_infos.len() > 1 {
+      return Err(anyhow!("--parent-value only works when inscribing with a single parent"));
+    }
+
+    if !self.reveal_input_values.is_empty() && self.reveal_input_values.len() != self.reveal_input.len() {
+      return Err(anyhow!(
+        "got {} --reveal-input-value but {} --reveal-input; give a value for every reveal input or none",
+        self.reveal_input_values.len(),
+        self.reveal_input.len(),
+      ));
+    }
+
+    if !self.reveal_input_scripts.is_empty() && self.reveal_input_scripts.len() != self.reveal_input.len() {
+      return Err(anyhow!(
+        "got {} --reveal-input-script but {} --reveal-input; give a script for every reveal input or none",
+        self.reveal_input_scripts.len(),
+        self.reveal_input.len(),
+      ));
+    }
+
     match self.mode {
       Mode::SameSat => assert_eq!(
         self.destinations.len(),
@@ -508,20 +753,41 @@ impl Batch {
         .map(|satpoint| satpoint.outpoint)
         .collect::<BTreeSet<OutPoint>>();
 
-      utxos
+      let excluded_utxos = locked_utxos
         .iter()
-        .find(|(outpoint, amount)| {
-          amount.to_sat() > 0
-            && !inscribed_utxos.contains(outpoint)
-            && !locked_utxos.contains(outpoint)
-            && !runic_utxos.contains(outpoint)
-            && !self.fee_utxos.contains(outpoint)
-        })
-        .map(|(outpoint, _amount)| SatPoint {
-          outpoint: *outpoint,
-          offset: 0,
-        })
-        .ok_or_else(|| anyhow!("wallet contains no cardinal utxos"))?
+        .chain(&self.fee_utxos)
+        .cloned()
+        .collect::<BTreeSet<OutPoint>>();
+
+      // the transaction builder adds whatever else the commit needs, so the inscription goes on the
+      // first cardinal in outpoint order, or with --only-common the first whose first sat is common
+      let cardinals = cardinal_utxos(&utxos, &excluded_utxos, &inscribed_utxos, &runic_utxos)?;
+
+      let outpoint = if self.only_common {
+        let mut common = None;
+
+        for (outpoint, _amount) in cardinals {
+          // the inscription goes on the first sat, so that's the only one whose rarity matters
+          let first_sat = match index.list(outpoint)? {
+            Some(List::Unspent(ranges)) => ranges.first().map(|(start, _end)| Sat(*start)),
+            _ => None,
+          };
+
+          if first_sat.map(|sat| sat.rarity()) == Some(Rarity::Common) {
+            common = Some(outpoint);
+            break;
+          }
+        }
+
+        common.ok_or_else(|| anyhow!("wallet contains no cardinal utxos whose first sat is common"))?
+      } else {
+        cardinals[0].0
+      };
+
+      SatPoint {
+        outpoint,
+        offset: 0,
+      }
     };
     vec![satpoint]
     };
@@ -573,10 +839,13 @@ impl Batch {
         .push_opcode(opcodes::all::OP_CHECKSIG),
     );
 
+    // the leaf is always signed for with the key, but the internal key can be some other, e.g. NUMS, key
+    let internal_key = self.internal_key.unwrap_or(public_key);
+
     let taproot_spend_info = TaprootBuilder::new()
       .add_leaf(0, reveal_script.clone())
       .expect("adding leaf should work")
-      .finalize(&secp256k1, public_key)
+      .finalize(&secp256k1, internal_key)
       .expect("finalizing taproot builder should work");
 
     let control_block = taproot_spend_info
@@ -596,7 +865,7 @@ impl Batch {
       let next_taproot_spend_info = TaprootBuilder::new()
         .add_leaf(0, next_reveal_script.clone())
         .expect("adding leaf should work")
-        .finalize(&secp256k1, public_key)
+        .finalize(&secp256k1, internal_key)
         .expect("finalizing taproot builder should work");
 
       Some(Address::p2tr_tweaked(next_taproot_spend_info.output_key(), chain.network()))
@@ -606,16 +875,7 @@ impl Batch {
       None
     };
 
-    let total_postage = if self.inscribe_on_specific_utxos {
-      self.inscriptions.iter().map(|entry| utxos[&entry.utxo.unwrap()]).sum::<Amount>()
-    } else {
-      match self.mode {
-      Mode::SameSat => self.postage,
-      Mode::SharedOutput | Mode::SeparateOutputs => {
-        self.postage * u64::try_from(self.inscriptions.len()).unwrap()
-      }
-      }
-    };
+    let total_postage = self.total_postage(&utxos);
 
     let mut reveal_inputs = self.reveal_input.clone();
     reveal_inputs.insert(0, OutPoint::null());
@@ -631,7 +891,7 @@ impl Batch {
             Mode::SeparateOutputs => if self.inscribe_on_specific_utxos {
               utxos[&self.inscriptions[count - 1].utxo.unwrap()].to_sat()
             } else {
-              self.postage.to_sat()
+              self.entry_postage(count - 1).to_sat()
             },
             Mode::SharedOutput | Mode::SameSat => total_postage.to_sat(),
           }
@@ -639,24 +899,126 @@ impl Batch {
       })
       .collect::<Vec<TxOut>>();
 
-    if let Some(ParentInfo {
+    // each parent is spent by the input, and returned by the output, with its own index, ahead of the commit
+    // input and the inscription outputs, so insert them last to first
+    for ParentInfo {
       location,
       id: _,
       destination,
       tx_out,
-    }) = self.parent_info.clone()
+    } in self.parent_infos.iter().rev().cloned()
     {
       reveal_inputs.insert(0, location.outpoint);
+
+      let parent_value = self.parent_value.unwrap_or(tx_out.value);
+      let parent_dust_limit = destination.script_pubkey().dust_value().to_sat();
+
+      if parent_value > tx_out.value {
+        bail!("--parent-value {parent_value} sats is bigger than the parent output's {} sats", tx_out.value);
+      }
+
+      if parent_value < parent_dust_limit {
+        bail!("--parent-value {parent_value} sats is less than the dust limit {parent_dust_limit} for address {destination}");
+      }
+
       reveal_outputs.insert(
         0,
         TxOut {
           script_pubkey: destination.script_pubkey(),
-          value: tx_out.value,
+          value: parent_value,
         },
       );
+
+      // whatever we trimmed off the parent goes back to us as a cardinal
+      if parent_value < tx_out.value {
+        let Some(change) = &change else {
+          bail!("--parent-value sends the value trimmed off the parent to change, so without a wallet it needs --change");
+        };
+
+        let parent_change_address = change[1].clone();
+
+        let parent_change = tx_out.value - parent_value;
+        let change_dust_limit = parent_change_address.script_pubkey().dust_value().to_sat();
+
+        if parent_change < change_dust_limit {
+          bail!("--parent-value {parent_value} sats would leave {parent_change} sats of parent change, less than the dust limit {change_dust_limit} for address {parent_change_address}");
+        }
+
+        reveal_outputs.push(TxOut {
+          script_pubkey: parent_change_address.script_pubkey(),
+          value: parent_change,
+        });
+      }
+    }
+
+    // cap the output holding the inscriptions, and send the rest of their postage back to us as a cardinal
+    if let Some(max_inscription_postage) = self.max_inscription_postage {
+      if self.mode == Mode::SeparateOutputs {
+        bail!("--max-inscription-postage only works in shared-output and same-sat modes; use --postage to set each output's postage in separate-outputs mode");
+      }
+
+      let inscription_output = self.parent_infos.len();
+      let dust_limit = reveal_outputs[inscription_output].script_pubkey.dust_value();
+
+      if max_inscription_postage < dust_limit {
+        bail!(
+          "--max-inscription-postage {} sats is less than the dust limit {} sats for the inscription output",
+          max_inscription_postage.to_sat(),
+          dust_limit.to_sat()
+        );
+      }
+
+      // in shared-output mode every inscription sits on its own sat of the output, so the last one must still fit
+      let last_offset = if self.mode == Mode::SharedOutput {
+        total_postage
+          - if self.inscribe_on_specific_utxos {
+            utxos[&self.inscriptions.last().unwrap().utxo.unwrap()]
+          } else {
+            self.postage
+          }
+      } else {
+        Amount::from_sat(0)
+      };
+
+      if max_inscription_postage <= last_offset {
+        bail!(
+          "--max-inscription-postage {} sats is too small to hold the last inscription, at offset {}",
+          max_inscription_postage.to_sat(),
+          last_offset.to_sat()
+        );
+      }
+
+      if max_inscription_postage < total_postage {
+        let Some(change) = &change else {
+          bail!("--max-inscription-postage needs a change address for the excess postage");
+        };
+
+        let excess = total_postage - max_inscription_postage;
+
+        // an excess too small for its own output just stays with the inscriptions
+        if excess >= change[1].script_pubkey().dust_value() {
+          reveal_outputs[inscription_output].value = max_inscription_postage.to_sat();
+
+          reveal_outputs.push(TxOut {
+            script_pubkey: change[1].script_pubkey(),
+            value: excess.to_sat(),
+          });
+        }
+      }
+    }
+
+    // an inscription whose pointer lands past the last output sat isn't bound where we meant it to be
+    let reveal_output_value = reveal_outputs.iter().map(|output| output.value).sum::<u64>();
+
+    for (i, inscription) in self.inscriptions.iter().enumerate() {
+      if let Some(pointer) = inscription.pointer() {
+        if pointer >= reveal_output_value {
+          bail!("inscription {i} has pointer {pointer}, but the reveal outputs only hold {reveal_output_value} sats");
+        }
+      }
     }
 
-    let commit_input = if self.parent_info.is_some() { 1 } else { 0 };
+    let commit_input = self.parent_infos.len();
 
     if self.reveal_fee != Some(Amount::from_sat(0)) {
       if self.commitment.is_some() {
@@ -667,6 +1029,8 @@ impl Batch {
       }
     }
 
+    let reveal_sighash_types = self.reveal_sighash_types(reveal_inputs.len(), commit_input);
+
     let (_, mut reveal_fee, reveal_vsize) = Self::build_reveal_transaction(
       &control_block,
       self.reveal_fee_rate,
@@ -674,6 +1038,7 @@ impl Batch {
       commit_input,
       reveal_outputs.clone(),
       &reveal_script,
+      &reveal_sighash_types,
     );
 
     let commit_vsize = if self.fee_utxos.is_empty() {
@@ -714,11 +1079,27 @@ impl Batch {
     };
 
     if !self.fee_utxos.is_empty() {
-      let fee_utxos_value = self.fee_utxos.iter().map(|outpoint| utxos[&outpoint]).sum::<Amount>();
-      let total_vsize = commit_vsize + reveal_vsize;
-      // eprintln!("total_vsize {} = commit_vsize {} + reveal_vsize {}", total_vsize, commit_vsize, reveal_vsize);
-      reveal_fee = (fee_utxos_value * reveal_vsize + Amount::from_sat(total_vsize - 1)) / total_vsize;
-      // eprintln!("reveal_fee = (fee_utxos {} * reveal_vsize {} + total_vsize {} - 1) / total_vsize {} = reveal_fee {}", fee_utxos_value.to_sat(), reveal_vsize, total_vsize, total_vsize, reveal_fee.to_sat());
+      // the commit's change all goes to fees, so a forced --commit-input that doesn't hold an inscription pays
+      // them alongside the fee utxos, and the dummy commit's vsize already counts it
+      let fee_utxos_value = force_input
+        .iter()
+        .filter(|outpoint| !satpoints.iter().any(|satpoint| satpoint.outpoint == **outpoint))
+        .map(|outpoint| utxos[outpoint])
+        .sum::<Amount>();
+      let inscribed_utxos_value = satpoints
+        .iter()
+        .map(|satpoint| satpoint.outpoint)
+        .collect::<BTreeSet<OutPoint>>()
+        .iter()
+        .map(|outpoint| utxos[outpoint])
+        .sum::<Amount>();
+
+      reveal_fee = Self::allocate_fee_pool(
+        fee_utxos_value + inscribed_utxos_value,
+        total_postage,
+        commit_vsize,
+        &[reveal_vsize],
+      )?[0];
     } else if let Some(r) = self.reveal_fee {
       if r != Amount::from_sat(0) {
         if r < reveal_fee {
@@ -729,6 +1110,27 @@ impl Batch {
       }
     }
 
+    // every same-sat inscription rides on the one sat, so the utxo holding it has to cover the postage and the
+    // reveal fee from that sat on
+    if self.mode == Mode::SameSat && self.commitment.is_none() && !self.inscribe_on_specific_utxos {
+      let satpoint = satpoints[0];
+      let available = utxos
+        .get(&satpoint.outpoint)
+        .map(|value| value.to_sat().saturating_sub(satpoint.offset))
+        .unwrap_or_default();
+      let needed = (total_postage + reveal_fee).to_sat();
+
+      if available < needed {
+        bail!(
+          "same-sat inscriptions need {needed} sats for postage {} plus reveal fee {}, but utxo {} only has {available} sats from sat {satpoint} on, {} sats short",
+          total_postage.to_sat(),
+          reveal_fee.to_sat(),
+          satpoint.outpoint,
+          needed - available,
+        );
+      }
+    }
+
     let unsigned_commit_tx = if self.commitment.is_some() {
       Transaction {
         version: 0,
@@ -748,6 +1150,8 @@ impl Batch {
       self.commit_fee_rate,
       if self.commit_only {
         Target::NoChange(reveal_fee + total_postage)
+      } else if let Some(change_value) = self.change_value {
+        Target::ValueWithChange(reveal_fee + total_postage, change_value)
       } else if !self.fee_utxos.is_empty() {
         Target::ChangeIsFee(reveal_fee + total_postage)
       } else {
@@ -761,19 +1165,32 @@ impl Batch {
 
     let mut reveal_input_value = Amount::from_sat(0);
     let mut reveal_input_prevouts = Vec::new();
-    for i in &self.reveal_input {
-      let output = index.get_transaction(i.txid)?.unwrap().output[i.vout as usize].clone();
+    for (i, input) in self.reveal_input.iter().enumerate() {
+      let output = self.reveal_input_prevout(index, client, i)?;
       reveal_input_value += Amount::from_sat(output.value);
       reveal_input_prevouts.push(output.clone());
-      utxos.insert(*i, Amount::from_sat(output.value));
+      utxos.insert(*input, Amount::from_sat(output.value));
     }
 
     let vout = if self.commitment.is_some() {
       reveal_inputs[commit_input] = self.commitment.unwrap();
 
       if self.reveal_fee != Some(Amount::from_sat(0)) {
+        if let Some(fee_bump_reveal) = self.fee_bump_reveal {
+          let bumped_fee = fee_bump_reveal.fee(reveal_vsize.try_into().unwrap());
+          if bumped_fee < reveal_fee {
+            return Err(anyhow!("--fee-bump-reveal {} sats/vB would pay {} sats, less than the {} sats the reveal pays already", fee_bump_reveal.n(), bumped_fee.to_sat(), reveal_fee.to_sat()));
+          }
+          reveal_fee = bumped_fee;
+        }
+
         if let Some(last) = reveal_outputs.last_mut() {
-          (*last).value = (reveal_input_value + self.commitment_output.clone().unwrap().value - total_postage - reveal_fee).to_sat();
+          let available = reveal_input_value + self.commitment_output.clone().unwrap().value;
+          let dust_limit = last.script_pubkey.dust_value();
+          if available < total_postage + reveal_fee + dust_limit {
+            return Err(anyhow!("commitment and reveal inputs ({} sats) don't cover postage {} plus reveal fee {} plus dust limit {} for the reveal change", available.to_sat(), total_postage.to_sat(), reveal_fee.to_sat(), dust_limit.to_sat()));
+          }
+          (*last).value = (available - total_postage - reveal_fee).to_sat();
         }
       }
 
@@ -801,15 +1218,23 @@ impl Batch {
       commit_input,
       reveal_outputs.clone(),
       &reveal_script,
+      &reveal_sighash_types,
     );
 
-    if reveal_tx.output[commit_input].value
-      < reveal_tx.output[commit_input]
-        .script_pubkey
-        .dust_value()
-        .to_sat()
-    {
-      bail!("commit transaction output would be dust");
+    let dust_value = reveal_tx.output[commit_input]
+      .script_pubkey
+      .dust_value()
+      .to_sat();
+
+    if reveal_tx.output[commit_input].value < dust_value {
+      if !self.allow_dust_reveal_output {
+        bail!("commit transaction output would be dust");
+      }
+
+      eprintln!(
+        "warning: reveal output {commit_input} is {} sats, below its dust value of {dust_value} sats; the reveal tx is nonstandard and most nodes won't relay it",
+        reveal_tx.output[commit_input].value,
+      );
     }
 
     let mut prevouts = vec![
@@ -823,8 +1248,8 @@ impl Batch {
       }
     ];
 
-    if let Some(parent_info) = self.parent_info.clone() {
-      prevouts.insert(0, parent_info.clone().tx_out);
+    for parent_info in self.parent_infos.iter().rev() {
+      prevouts.insert(0, parent_info.tx_out.clone());
       if self.no_wallet {
         utxos.insert(parent_info.location.outpoint, Amount::from_sat(parent_info.tx_out.value));
       }
@@ -832,6 +1257,10 @@ impl Batch {
 
     prevouts.extend(reveal_input_prevouts);
 
+    if let Some(target) = self.reveal_change_target {
+      self.check_reveal_change_target(target, &reveal_tx, &prevouts)?;
+    }
+
     let mut sighash_cache = SighashCache::new(&mut reveal_tx);
 
     let sighash = sighash_cache
@@ -839,7 +1268,7 @@ impl Batch {
         commit_input,
         &Prevouts::All(&prevouts),
         TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
-        TapSighashType::Default,
+        Self::REVEAL_SIGHASH_TYPE,
       )
       .expect("signature hash should compute");
 
@@ -856,7 +1285,7 @@ impl Batch {
     witness.push(
       Signature {
         sig,
-        hash_ty: TapSighashType::Default,
+        hash_ty: Self::REVEAL_SIGHASH_TYPE,
       }
       .to_vec(),
     );
@@ -866,14 +1295,17 @@ impl Batch {
 
     let recovery_key_pair = key_pair.tap_tweak(&secp256k1, taproot_spend_info.merkle_root());
 
-    let (x_only_pub_key, _parity) = recovery_key_pair.to_inner().x_only_public_key();
-    assert_eq!(
-      Address::p2tr_tweaked(
-        TweakedPublicKey::dangerous_assume_tweaked(x_only_pub_key),
-        chain.network(),
-      ),
-      commit_tx_address
-    );
+    // with a separate internal key, the recovery key can't spend the commit output, so it's never used
+    if self.internal_key.is_none() {
+      let (x_only_pub_key, _parity) = recovery_key_pair.to_inner().x_only_public_key();
+      assert_eq!(
+        Address::p2tr_tweaked(
+          TweakedPublicKey::dangerous_assume_tweaked(x_only_pub_key),
+          chain.network(),
+        ),
+        commit_tx_address
+      );
+    }
 
     let reveal_weight = reveal_tx.weight();
 
@@ -895,74 +1327,652 @@ impl Batch {
       },
     );
 
-    let total_fees =
-      if self.commitment.is_some() {
-        0
-      } else {
-        Self::calculate_fee(&unsigned_commit_tx, &utxos)
-      } + if self.commit_only {
-        0
-      } else {
-        Self::calculate_fee(&reveal_tx, &utxos)
-      };
+    let (commit_tx_fee, commit_tx_vsize) = if self.commitment.is_some() {
+      (0, 0)
+    } else if self.dry_run && !self.no_wallet {
+      // a dry run reports the commit's vsize, so measure it from a wallet-signed copy, as the fee utxo path
+      // does with its dummy commit
+      let signed = client.sign_raw_transaction_with_wallet(&unsigned_commit_tx, None, None)?;
+      if !signed.complete {
+        bail!("failed to sign commit tx to measure its vsize");
+      }
 
-    Ok((Some(unsigned_commit_tx), Some(reveal_tx), Some(recovery_key_pair), Some(total_fees), None))
-  }
+      (
+        Self::calculate_fee(&unsigned_commit_tx, &utxos)?,
+        consensus::encode::deserialize::<Transaction>(&signed.hex)?.vsize() as u64,
+      )
+    } else {
+      // the commit is still unsigned, so size it with a schnorr signature on every input, as the wallet's taproot inputs will have
+      let mut commit_tx = unsigned_commit_tx.clone();
+      for input in &mut commit_tx.input {
+        input.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
+      }
 
-  fn get_recovery_key(
-    client: &Client,
-    recovery_key_pair: TweakedKeyPair,
-    network: Network,
-  ) -> Result<String> {
-    let recovery_private_key =
-      PrivateKey::new(recovery_key_pair.to_inner().secret_key(), network).to_wif();
-    Ok(format!(
-      "rawtr({})#{}",
-      recovery_private_key,
-      client
-        .get_descriptor_info(&format!("rawtr({})", recovery_private_key))?
-        .checksum
-    ))
-  }
+      (Self::calculate_fee(&unsigned_commit_tx, &utxos)?, commit_tx.vsize() as u64)
+    };
 
-  fn backup_recovery_key(
-    client: &Client,
-    recovery_key_pair: TweakedKeyPair,
-    network: Network,
-  ) -> Result {
-    let recovery_private_key = PrivateKey::new(recovery_key_pair.to_inner().secret_key(), network);
+    let (reveal_tx_fee, reveal_tx_vsize) = if self.commit_only {
+      (0, 0)
+    } else {
+      (Self::calculate_fee(&reveal_tx, &utxos)?, reveal_vsize)
+    };
 
-    let info = client.get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
+    let total_fees = commit_tx_fee + reveal_tx_fee;
 
-    let response = client.import_descriptors(ImportDescriptors {
-      descriptor: format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum),
-      timestamp: Timestamp::Now,
-      active: Some(false),
-      range: None,
-      next_index: None,
-      internal: Some(false),
-      label: Some("commit tx recovery key".to_string()),
-    })?;
+    if let Some(budget) = self.budget {
+      let total = total_postage + Amount::from_sat(total_fees);
 
-    for result in response {
-      if !result.success {
-        return Err(anyhow!("commit tx recovery key import failed"));
+      if total > budget {
+        bail!(
+          "inscribing would cost {} sats, more than the --budget of {} sats\n       postage {} sats plus fees {total_fees} sats",
+          total.to_sat(),
+          budget.to_sat(),
+          total_postage.to_sat(),
+        );
       }
     }
 
-    Ok(())
+    if self.double_check_fees {
+      self.check_fees(&unsigned_commit_tx, commit_tx_vsize, &reveal_tx, commit_input, &utxos)?;
+    }
+
+    let fees = Fees {
+      commit_fee: commit_tx_fee,
+      commit_vsize: commit_tx_vsize,
+      reveal_fee: reveal_tx_fee,
+      reveal_vsize: reveal_tx_vsize,
+      total: total_fees,
+      total_postage: total_postage.to_sat(),
+    };
+
+    Ok((Some(unsigned_commit_tx), Some(reveal_tx), Some(recovery_key_pair), Some(fees), None))
   }
 
-  fn build_reveal_transaction(
-    control_block: &ControlBlock,
-    fee_rate: FeeRate,
-    inputs: Vec<OutPoint>,
-    commit_input_index: usize,
-    outputs: Vec<TxOut>,
-    script: &Script,
-  ) -> (Transaction, Amount, u64) {
-    let reveal_tx = Transaction {
-      input: inputs
+  // the output spent by the `i`th reveal input, from the index, or from the wallet if the index hasn't got
+  // its tx. a `reveal_input_values` entry overrides the value, and with a `reveal_input_scripts` entry too,
+  // which is all signing needs, the tx isn't looked up at all, e.g. because it's pruned or external
+  fn reveal_input_prevout(&self, index: &Index, client: &Client, i: usize) -> Result<TxOut> {
+    let outpoint = self.reveal_input[i];
+
+    if let (Some(value), Some(script_pubkey)) =
+      (self.reveal_input_values.get(i), self.reveal_input_scripts.get(i))
+    {
+      return Ok(TxOut {
+        value: *value,
+        script_pubkey: script_pubkey.clone(),
+      });
+    }
+
+    let tx = match index.get_transaction(outpoint.txid)? {
+      Some(tx) => tx,
+      None => client
+        .get_transaction(&outpoint.txid, None)
+        .ok()
+        .and_then(|info| info.transaction().ok())
+        .ok_or_else(|| anyhow!("reveal input {outpoint}: tx {} is in neither the index nor the wallet", outpoint.txid))?,
+    };
+
+    let mut output = tx
+      .output
+      .get(outpoint.vout as usize)
+      .cloned()
+      .ok_or_else(|| anyhow!("reveal input {outpoint}: tx {} has no output {}", outpoint.txid, outpoint.vout))?;
+
+    if let Some(value) = self.reveal_input_values.get(i) {
+      output.value = *value;
+    }
+
+    Ok(output)
+  }
+
+  // recompute the fees from the finished transactions' sizes at the requested rates, independently of the
+  // fees they were built to pay, and make sure the two agree to within a sat
+  fn check_fees(
+    &self,
+    commit_tx: &Transaction,
+    commit_vsize: u64,
+    reveal_tx: &Transaction,
+    commit_input: usize,
+    utxos: &BTreeMap<OutPoint, Amount>,
+  ) -> Result {
+    // in fee utxo mode the commit deliberately pays whatever the reveal doesn't, so there's no rate to
+    // check it against, and with --commitment there's no commit at all
+    if self.commitment.is_none() && self.fee_utxos.is_empty() {
+      let projected = self.commit_fee_rate.fee(commit_vsize.try_into().unwrap());
+      let actual = Self::calculate_fee(commit_tx, utxos)?;
+
+      if actual.abs_diff(projected.to_sat()) > 1 {
+        bail!(
+          "commit fee double check failed: commit tx pays {actual} sats, but {commit_vsize} vB at {} sats/vB should pay {} sats",
+          self.commit_fee_rate.n(),
+          projected.to_sat(),
+        );
+      }
+    }
+
+    // a fixed --reveal-fee or a share of the fee utxos isn't paid at any rate
+    if !self.commit_only && self.reveal_fee.is_none() && self.fee_utxos.is_empty() {
+      // we've signed the commit input, and bitcoind signs the rest, so size those with a dummy signature
+      let sighash_types = self.reveal_sighash_types(reveal_tx.input.len(), commit_input);
+
+      let mut signed_reveal_tx = reveal_tx.clone();
+      for (i, txin) in signed_reveal_tx.input.iter_mut().enumerate() {
+        if i != commit_input {
+          txin.witness = Witness::from_slice(&[Signature {
+            sig: secp256k1::schnorr::Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE]).unwrap(),
+            hash_ty: sighash_types[i],
+          }
+          .to_vec()]);
+        }
+      }
+
+      let reveal_fee_rate = self.fee_bump_reveal.unwrap_or(self.reveal_fee_rate);
+      let reveal_vsize = signed_reveal_tx.vsize();
+      let projected = reveal_fee_rate.fee(reveal_vsize);
+      let actual = Self::calculate_fee(reveal_tx, utxos)?;
+
+      if actual.abs_diff(projected.to_sat()) > 1 {
+        bail!(
+          "reveal fee double check failed: reveal tx pays {actual} sats, but {reveal_vsize} vB at {} sats/vB should pay {} sats",
+          reveal_fee_rate.n(),
+          projected.to_sat(),
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  // a batch entry's own `postage` overrides the batch's, in separate-outputs mode
+  fn entry_postage(&self, i: usize) -> Amount {
+    self.postages.get(i).copied().flatten().unwrap_or(self.postage)
+  }
+
+  fn total_postage(&self, utxos: &BTreeMap<OutPoint, Amount>) -> Amount {
+    if self.inscribe_on_specific_utxos {
+      self.inscriptions.iter().map(|entry| utxos[&entry.utxo.unwrap()]).sum::<Amount>()
+    } else {
+      match self.mode {
+      Mode::SameSat => self.postage,
+      Mode::SharedOutput => {
+        self.postage * u64::try_from(self.inscriptions.len()).unwrap()
+      }
+      Mode::SeparateOutputs => (0..self.inscriptions.len()).map(|i| self.entry_postage(i)).sum(),
+      }
+    }
+  }
+
+  // the parent outputs, the inscription outputs, and any trimmed parent value all come before the
+  // change, and the parent inputs come before the commitment, so the sats after the parents have to
+  // fill every inscription's postage before the change starts. Put the --reveal-input holding the
+  // --reveal-change-target after just enough of the other --reveal-inputs to do that, so the target
+  // lands at the start of the change, as far as it can be from the fee that comes off the end.
+  pub(super) fn order_reveal_inputs_for_change_target(
+    &mut self,
+    index: &Index,
+    client: &Client,
+    utxos: &BTreeMap<OutPoint, Amount>,
+  ) -> Result {
+    let Some(target) = self.reveal_change_target else {
+      return Ok(());
+    };
+
+    let Some(target_input) = self.reveal_input.iter().position(|outpoint| *outpoint == target.outpoint) else {
+      return Ok(());
+    };
+
+    let values = (0..self.reveal_input.len())
+      .map(|i| Ok(self.reveal_input_prevout(index, client, i)?.value))
+      .collect::<Result<Vec<u64>>>()?;
+
+    let order = Self::change_target_input_order(
+      &values,
+      target_input,
+      target.offset,
+      self.commitment_output.as_ref().map(|output| output.value.to_sat()).unwrap_or_default(),
+      self.total_postage(utxos).to_sat(),
+    );
+
+    self.reveal_input = order.iter().map(|i| self.reveal_input[*i]).collect();
+
+    if !self.reveal_input_values.is_empty() {
+      self.reveal_input_values = order.iter().map(|i| self.reveal_input_values[*i]).collect();
+    }
+
+    if !self.reveal_input_scripts.is_empty() {
+      self.reveal_input_scripts = order
+        .iter()
+        .map(|i| self.reveal_input_scripts[*i].clone())
+        .collect();
+    }
+
+    Ok(())
+  }
+
+  // everything the commit spends, the fee utxos and the inscribed utxos alike, has to cover the postage
+  // and every transaction's fee at the minimum relay fee rate. What's left after the postage is split
+  // between the reveals in proportion to their vsizes, rounding each share up, and the commit's fee is
+  // whatever remains, since its change all goes to fees.
+  pub(super) fn allocate_fee_pool(
+    pool: Amount,
+    total_postage: Amount,
+    commit_vsize: u64,
+    reveal_vsizes: &[u64],
+  ) -> Result<Vec<Amount>> {
+    let total_vsize = commit_vsize + reveal_vsizes.iter().sum::<u64>();
+    let needed = total_postage + Amount::from_sat(total_vsize);
+
+    if pool < needed {
+      bail!(
+        "fee and inscribed utxos total {} sats, which doesn't cover postage of {} sats plus the minimum relay fee of {total_vsize} sats for the commit ({commit_vsize} vB) and reveals ({} vB)",
+        pool.to_sat(),
+        total_postage.to_sat(),
+        total_vsize - commit_vsize,
+      );
+    }
+
+    let fees = pool - total_postage;
+
+    Ok(
+      reveal_vsizes
+        .iter()
+        .map(|reveal_vsize| (fees * *reveal_vsize + Amount::from_sat(total_vsize - 1)) / total_vsize)
+        .collect(),
+    )
+  }
+
+  // a supplied reveal_psbt's fee, worked out from what we know its prevouts hold rather than trusting the
+  // psbt, has to be within `dust_limit` sats of the fee the reveal should pay
+  pub(super) fn check_reveal_psbt_fee(
+    extracted_tx: &Transaction,
+    prevout_values: &BTreeMap<OutPoint, u64>,
+    reveal_fee: u64,
+    dust_limit: u64,
+  ) -> Result {
+    let mut input_value = 0;
+    for (i, input) in extracted_tx.input.iter().enumerate() {
+      input_value += prevout_values.get(&input.previous_output).ok_or_else(|| {
+        anyhow!("input {i} of reveal_psbt spends unknown output {}", input.previous_output)
+      })?;
+    }
+
+    let output_value = extracted_tx.output.iter().map(|output| output.value).sum::<u64>();
+
+    let actual_fee = input_value
+      .checked_sub(output_value)
+      .ok_or_else(|| anyhow!("reveal_psbt outputs ({output_value} sats) exceed its inputs ({input_value} sats)"))?;
+
+    if actual_fee.abs_diff(reveal_fee) > dust_limit {
+      bail!("reveal_psbt pays a fee of {actual_fee} sats, but the reveal should pay {reveal_fee} sats");
+    }
+
+    Ok(())
+  }
+
+  // the order to spend --reveal-inputs worth `values` in, after `ahead` sats of commitment, so the sat
+  // at `target_offset` in input `target_input` comes after `postage` sats
+  pub(super) fn change_target_input_order(
+    values: &[u64],
+    target_input: usize,
+    target_offset: u64,
+    mut ahead: u64,
+    postage: u64,
+  ) -> Vec<usize> {
+    let mut order = Vec::new();
+
+    for (i, value) in values.iter().enumerate() {
+      if ahead + target_offset >= postage {
+        break;
+      }
+
+      if i != target_input {
+        order.push(i);
+        ahead += value;
+      }
+    }
+
+    order.push(target_input);
+    order.extend((0..values.len()).filter(|i| !order.contains(i)));
+
+    order
+  }
+
+  // sats flow from the reveal inputs to its outputs in order, with the change output last and the fee
+  // coming off the end, so work out which output the sat at `target` lands in
+  fn check_reveal_change_target(
+    &self,
+    target: SatPoint,
+    reveal_tx: &Transaction,
+    prevouts: &[TxOut],
+  ) -> Result {
+    if self.commitment.is_none() || self.reveal_fee == Some(Amount::from_sat(0)) {
+      bail!("--reveal-change-target needs a reveal change output, so it only works with --commitment and a non-zero --reveal-fee");
+    }
+
+    let mut offset = None;
+    let mut input_start = 0;
+    for (input, prevout) in reveal_tx.input.iter().zip(prevouts) {
+      if input.previous_output == target.outpoint {
+        if target.offset >= prevout.value {
+          bail!("--reveal-change-target {target} is past the end of its {} sat output", prevout.value);
+        }
+        offset = Some(input_start + target.offset);
+        break;
+      }
+      input_start += prevout.value;
+    }
+
+    let Some(offset) = offset else {
+      bail!("--reveal-change-target {target} isn't in the commitment or a --reveal-input");
+    };
+
+    let change = reveal_tx.output.len() - 1;
+    let change_start = reveal_tx.output[..change].iter().map(|output| output.value).sum::<u64>();
+
+    if offset < change_start {
+      bail!("--reveal-change-target {target} would land in an inscription output, which all come before the change; the commitment has to cover all of their postage before the target sat");
+    }
+
+    if offset >= change_start + reveal_tx.output[change].value {
+      bail!("--reveal-change-target {target} would be spent on the reveal fee, which comes off the end of the change");
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn inspect_commit(
+    inscriptions: &[Inscription],
+    key: &str,
+    internal_key: Option<XOnlyPublicKey>,
+    commit: Txid,
+    client: &Client,
+    chain: Chain,
+  ) -> Result<super::CommitInspection> {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = secp256k1::KeyPair::from_secret_key(&secp256k1, &PrivateKey::from_wif(key)?.inner);
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let reveal_script = Inscription::append_batch_reveal_script(
+      inscriptions,
+      ScriptBuf::builder()
+        .push_slice(public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    );
+
+    let taproot_spend_info = TaprootBuilder::new()
+      .add_leaf(0, reveal_script.clone())
+      .expect("adding leaf should work")
+      .finalize(&secp256k1, internal_key.unwrap_or(public_key))
+      .expect("finalizing taproot builder should work");
+
+    let control_block = taproot_spend_info
+      .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+      .expect("should compute control block");
+
+    let commit_tx_address = Address::p2tr_tweaked(taproot_spend_info.output_key(), chain.network());
+
+    let commit_tx = client.get_raw_transaction(&commit, None)?;
+
+    let (vout, commit_output) = commit_tx
+      .output
+      .iter()
+      .enumerate()
+      .find(|(_vout, output)| output.script_pubkey == commit_tx_address.script_pubkey())
+      .ok_or_else(|| anyhow!("commit tx {commit} has no output paying to {commit_tx_address}; check --key and the inscription contents"))?;
+
+    // parse the inscriptions back out of the reveal script, the same way the index will see them
+    let mut witness = Witness::new();
+    witness.push(
+      Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
+        .unwrap()
+        .to_vec(),
+    );
+    witness.push(&reveal_script);
+    witness.push(&control_block.serialize());
+
+    let commit = OutPoint {
+      txid: commit,
+      vout: vout.try_into().unwrap(),
+    };
+
+    let reveal_tx = Transaction {
+      input: vec![TxIn {
+        previous_output: commit,
+        script_sig: ScriptBuf::new(),
+        witness,
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      }],
+      output: Vec::new(),
+      lock_time: LockTime::ZERO,
+      version: 2,
+    };
+
+    Ok(super::CommitInspection {
+      address: commit_tx_address,
+      commit,
+      inscriptions: ParsedEnvelope::from_transaction(&reveal_tx, false)
+        .into_iter()
+        .map(|envelope| envelope.payload.try_into())
+        .collect::<Result<Vec<CompactInscription>>>()?,
+      value: commit_output.value,
+    })
+  }
+
+  pub(crate) fn offline_reveal(
+    inscriptions: &[Inscription],
+    key: &str,
+    internal_key: Option<XOnlyPublicKey>,
+    commit: OutPoint,
+    commit_value: Amount,
+    destination: Address,
+    fee_rate: FeeRate,
+    chain: Chain,
+  ) -> Result<super::OfflineReveal> {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = secp256k1::KeyPair::from_secret_key(&secp256k1, &PrivateKey::from_wif(key)?.inner);
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let reveal_script = Inscription::append_batch_reveal_script(
+      inscriptions,
+      ScriptBuf::builder()
+        .push_slice(public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    );
+
+    let taproot_spend_info = TaprootBuilder::new()
+      .add_leaf(0, reveal_script.clone())
+      .expect("adding leaf should work")
+      .finalize(&secp256k1, internal_key.unwrap_or(public_key))
+      .expect("finalizing taproot builder should work");
+
+    let control_block = taproot_spend_info
+      .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+      .expect("should compute control block");
+
+    let commit_tx_address = Address::p2tr_tweaked(taproot_spend_info.output_key(), chain.network());
+
+    let (mut reveal_tx, fee, _vsize) = Self::build_reveal_transaction(
+      &control_block,
+      fee_rate,
+      vec![commit],
+      0,
+      vec![TxOut {
+        script_pubkey: destination.script_pubkey(),
+        value: 0,
+      }],
+      &reveal_script,
+      &[Self::REVEAL_SIGHASH_TYPE],
+    );
+
+    let dust_limit = destination.script_pubkey().dust_value();
+
+    if commit_value < fee + dust_limit {
+      bail!(
+        "--commit-value {} sats is too small\n       we need enough for fee {} plus dust limit {} = {} sats",
+        commit_value.to_sat(),
+        fee.to_sat(),
+        dust_limit.to_sat(),
+        (fee + dust_limit).to_sat()
+      );
+    }
+
+    let postage = commit_value - fee;
+
+    reveal_tx.output[0].value = postage.to_sat();
+
+    // the witness doesn't change the txid, but the psbt keeps the reveal script from it
+    reveal_tx.input[0].witness.push(
+      Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
+        .unwrap()
+        .to_vec(),
+    );
+    reveal_tx.input[0].witness.push(&reveal_script);
+    reveal_tx.input[0].witness.push(&control_block.serialize());
+
+    let reveal_psbt = Self::unsigned_reveal_psbt(
+      &reveal_tx,
+      commit,
+      TxOut {
+        script_pubkey: commit_tx_address.script_pubkey(),
+        value: commit_value.to_sat(),
+      },
+    )?;
+
+    Ok(super::OfflineReveal {
+      address: commit_tx_address,
+      commit,
+      fee: fee.to_sat(),
+      inscription: InscriptionId {
+        txid: reveal_tx.txid(),
+        index: 0,
+      },
+      postage: postage.to_sat(),
+      reveal: reveal_tx.txid(),
+      reveal_psbt: general_purpose::STANDARD.encode(reveal_psbt.serialize()),
+    })
+  }
+
+  fn get_recovery_key(
+    client: &Client,
+    recovery_key_pair: TweakedKeyPair,
+    network: Network,
+    key_origin: Option<&DerivationPath>,
+  ) -> Result<String> {
+    let descriptor = Self::recovery_descriptor(client, recovery_key_pair, network, key_origin)?;
+    Ok(format!(
+      "{descriptor}#{}",
+      client.get_descriptor_info(&descriptor)?.checksum
+    ))
+  }
+
+  // `rawtr(<wif>)`, or with a `key_origin` path, `rawtr([<fingerprint>/<path>]<wif>)`. the recovery key isn't
+  // derived from a master key, so the fingerprint is the one bitcoind gives the key itself, of the public key in
+  // the descriptor `getdescriptorinfo` returns
+  fn recovery_descriptor(
+    client: &Client,
+    recovery_key_pair: TweakedKeyPair,
+    network: Network,
+    key_origin: Option<&DerivationPath>,
+  ) -> Result<String> {
+    let recovery_private_key = PrivateKey::new(recovery_key_pair.to_inner().secret_key(), network);
+
+    let descriptor = format!("rawtr({})", recovery_private_key.to_wif());
+
+    let Some(path) = key_origin else {
+      return Ok(descriptor);
+    };
+
+    let public_descriptor = client.get_descriptor_info(&descriptor)?.descriptor;
+
+    let public_key = public_descriptor
+      .strip_prefix("rawtr(")
+      .and_then(|rest| rest.split(')').next())
+      .and_then(|key| key.parse::<XOnlyPublicKey>().ok())
+      .ok_or_else(|| anyhow!("unexpected public recovery descriptor `{public_descriptor}`"))?;
+
+    let secret_key = DescriptorSecretKey::Single(SinglePriv {
+      origin: Some((recovery_key_fingerprint(public_key), path.clone())),
+      key: recovery_private_key,
+    });
+
+    Ok(format!("rawtr({secret_key})"))
+  }
+
+  fn backup_recovery_key(
+    client: &Client,
+    recovery_key_pair: TweakedKeyPair,
+    network: Network,
+    key_origin: Option<&DerivationPath>,
+  ) -> Result {
+    let descriptor = Self::recovery_descriptor(client, recovery_key_pair, network, key_origin)?;
+
+    let info = client.get_descriptor_info(&descriptor)?;
+
+    let response = client.import_descriptors(ImportDescriptors {
+      descriptor: format!("{descriptor}#{}", info.checksum),
+      timestamp: Timestamp::Now,
+      active: Some(false),
+      range: None,
+      next_index: None,
+      internal: Some(false),
+      label: Some(RECOVERY_KEY_LABEL.to_string()),
+    })?;
+
+    for result in response {
+      if !result.success {
+        return Err(anyhow!("commit tx recovery key import failed"));
+      }
+    }
+
+    Ok(())
+  }
+
+  fn unsigned_reveal_psbt(
+    reveal_tx: &Transaction,
+    commit: OutPoint,
+    commit_output: TxOut,
+  ) -> Result<Psbt> {
+    let mut blank_reveal_tx = reveal_tx.clone();
+    for input in &mut blank_reveal_tx.input {
+      input.witness = Witness::new();
+    }
+
+    let commit_input = blank_reveal_tx
+      .input
+      .iter()
+      .position(|input| input.previous_output == commit)
+      .ok_or_else(|| anyhow!("reveal has no inputs from the commit tx"))?;
+
+    let mut reveal_psbt = Psbt::from_unsigned_tx(blank_reveal_tx)?;
+    reveal_psbt.inputs[commit_input].witness_utxo = Some(commit_output);
+
+    // keep the reveal script so signers can see what they're revealing
+    let witness = &reveal_tx.input[commit_input].witness;
+    if let (Some(script), Some(control_block)) = (witness.tapscript(), witness.last()) {
+      reveal_psbt.inputs[commit_input].tap_scripts.insert(
+        ControlBlock::decode(control_block)?,
+        (script.into(), LeafVersion::TapScript),
+      );
+    }
+
+    Ok(reveal_psbt)
+  }
+
+  // a signature with any sighash type but the default carries it in a 65th byte, so each input's dummy
+  // signature is sized by the sighash type it'll really be signed with
+  pub(super) fn build_reveal_transaction(
+    control_block: &ControlBlock,
+    fee_rate: FeeRate,
+    inputs: Vec<OutPoint>,
+    commit_input_index: usize,
+    outputs: Vec<TxOut>,
+    script: &Script,
+    sighash_types: &[TapSighashType],
+  ) -> (Transaction, Amount, u64) {
+    assert_eq!(inputs.len(), sighash_types.len());
+
+    let reveal_tx = Transaction {
+      input: inputs
         .iter()
         .map(|outpoint| TxIn {
           previous_output: *outpoint,
@@ -980,17 +1990,19 @@ impl Batch {
       let mut reveal_tx = reveal_tx.clone();
 
       for (current_index, txin) in reveal_tx.input.iter_mut().enumerate() {
+        let dummy_signature = Signature {
+          sig: secp256k1::schnorr::Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE]).unwrap(),
+          hash_ty: sighash_types[current_index],
+        }
+        .to_vec();
+
         // add dummy inscription witness for reveal input/commit output
         if current_index == commit_input_index {
-          txin.witness.push(
-            Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
-              .unwrap()
-              .to_vec(),
-          );
+          txin.witness.push(dummy_signature);
           txin.witness.push(script);
           txin.witness.push(&control_block.serialize());
         } else {
-          txin.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
+          txin.witness = Witness::from_slice(&[dummy_signature]);
         }
       }
 
@@ -1001,13 +2013,61 @@ impl Batch {
     (reveal_tx, fee, vsize)
   }
 
-  fn calculate_fee(tx: &Transaction, utxos: &BTreeMap<OutPoint, Amount>) -> u64 {
-    tx.input
-      .iter()
-      .map(|txin| utxos.get(&txin.previous_output).unwrap().to_sat())
-      .sum::<u64>()
-      .checked_sub(tx.output.iter().map(|txout| txout.value).sum::<u64>())
-      .unwrap()
+  // ask bitcoind whether the commit and reveal would be accepted together, before sending either
+  fn test_accept(
+    client: &Client,
+    signed_commit_tx: &[u8],
+    signed_reveal_tx: &[u8],
+    commit: bool,
+    reveal: bool,
+  ) -> Result {
+    let mut package = Vec::new();
+
+    if commit {
+      package.push(signed_commit_tx.raw_hex());
+    }
+
+    if reveal {
+      package.push(signed_reveal_tx.raw_hex());
+    }
+
+    for result in client.test_mempool_accept(&package)? {
+      if !result.allowed {
+        bail!(
+          "bitcoind would reject transaction {}: {}",
+          result.txid,
+          result.reject_reason.as_deref().unwrap_or("no reason given"),
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  pub(super) fn calculate_fee(tx: &Transaction, utxos: &BTreeMap<OutPoint, Amount>) -> Result<u64> {
+    let mut input_value = 0;
+
+    for txin in &tx.input {
+      input_value += utxos
+        .get(&txin.previous_output)
+        .ok_or_else(|| {
+          anyhow!(
+            "transaction {} spends output {}, whose value is unknown",
+            tx.txid(),
+            txin.previous_output
+          )
+        })?
+        .to_sat();
+    }
+
+    let output_value = tx.output.iter().map(|txout| txout.value).sum::<u64>();
+
+    input_value.checked_sub(output_value).ok_or_else(|| {
+      anyhow!(
+        "transaction {} spends {input_value} sats but its outputs hold {output_value} sats",
+        tx.txid()
+      )
+    })
   }
 }
 
@@ -1025,14 +2085,19 @@ pub(crate) enum Mode {
 #[derive(Deserialize, Default, PartialEq, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct BatchEntry {
+  pub(crate) content_encoding: Option<String>,
+  pub(crate) content_type: Option<String>,
   pub(crate) delegate: Option<InscriptionId>,
   pub(crate) destination: Option<Address<NetworkUnchecked>>,
-  pub(crate) file: PathBuf,
+  pub(crate) file: Option<PathBuf>,
   pub(crate) metadata: Option<serde_yaml::Value>,
   pub(crate) metadata_json: Option<serde_json::Value>,
   pub(crate) metaprotocol: Option<String>,
   pub(crate) offset: Option<u64>,
   pub(crate) pointer: Option<u64>,
+  pub(crate) postage: Option<u64>,
+  #[serde(default)]
+  pub(crate) unbound: bool,
   pub(crate) utxo: Option<OutPoint>,
 }
 
@@ -1056,6 +2121,120 @@ impl BatchEntry {
   }
 }
 
+pub(crate) fn load_content_type_map(path: &Path) -> Result<BTreeMap<String, String>> {
+  let map: BTreeMap<String, String> = serde_yaml::from_reader(File::open(path)?)
+    .with_context(|| format!("failed to load content type map {}", path.display()))?;
+
+  Ok(
+    map
+      .into_iter()
+      .map(|(extension, content_type)| {
+        (extension.trim_start_matches('.').to_lowercase(), content_type)
+      })
+      .collect(),
+  )
+}
+
+pub(crate) fn mapped_content_type(
+  content_type_map: &BTreeMap<String, String>,
+  path: &Path,
+) -> Option<String> {
+  content_type_map
+    .get(&path.extension()?.to_str()?.to_lowercase())
+    .cloned()
+}
+
+// what the commit and reveal txs pay, and how big they are, for dry runs to report
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Fees {
+  pub(crate) commit_fee: u64,
+  pub(crate) commit_vsize: u64,
+  pub(crate) reveal_fee: u64,
+  pub(crate) reveal_vsize: u64,
+  pub(crate) total: u64,
+  pub(crate) total_postage: u64,
+}
+
+// what `--resume-from` records: the signed transactions, and which of them have been broadcast
+#[derive(Serialize, Deserialize)]
+struct Progress {
+  commit: Option<String>,
+  commit_broadcast: bool,
+  reveal: Option<String>,
+  reveal_broadcast: bool,
+  output: super::Output,
+}
+
+impl Progress {
+  fn save(&self, path: &Path) -> Result {
+    fs::write(path, serde_json::to_string_pretty(self)?)
+      .with_context(|| format!("failed to write resume file {}", path.display()))
+  }
+
+  fn check_reveal_spends_commit(&self) -> Result {
+    let (Some(commit), Some(reveal)) = (&self.commit, &self.reveal) else {
+      return Ok(());
+    };
+
+    let decode = |hex: &str| -> Result<Transaction> {
+      Ok(consensus::encode::deserialize(&hex::decode(hex)?)?)
+    };
+
+    let commit = decode(commit).context("commit tx is not a valid transaction")?;
+    let reveal = decode(reveal).context("reveal tx is not a valid transaction")?;
+
+    let commit_txid = commit.txid();
+
+    if !reveal.input.iter().any(|input| {
+      input.previous_output.txid == commit_txid
+        && (input.previous_output.vout as usize) < commit.output.len()
+    }) {
+      bail!("reveal tx {} doesn't spend commit tx {commit_txid}", reveal.txid());
+    }
+
+    Ok(())
+  }
+
+  fn broadcast(mut self, client: &Client, path: Option<&Path>) -> Result<super::Output> {
+    if let (Some(commit), false) = (&self.commit, self.commit_broadcast) {
+      client.send_raw_transaction(commit.as_str())?;
+      self.commit_broadcast = true;
+
+      if let Some(path) = path {
+        self.save(path)?;
+      }
+    }
+
+    if let (Some(reveal), false) = (&self.reveal, self.reveal_broadcast) {
+      if let Err(err) = client.send_raw_transaction(reveal.as_str()) {
+        return Err(anyhow!(
+          "Failed to send reveal transaction: {err}{}{}",
+          match self.output.commit {
+            Some(commit) => format!("\nCommit tx {commit} will be recovered once mined"),
+            None => "".to_string(),
+          },
+          match path {
+            Some(path) => format!(
+              "\nRe-run with `--resume-from {}` or `wallet broadcast-file {}` to retry the reveal",
+              path.display(),
+              path.display()
+            ),
+            None => "".to_string(),
+          },
+        ));
+      }
+
+      self.reveal_broadcast = true;
+
+      if let Some(path) = path {
+        self.save(path)?;
+      }
+    }
+
+    Ok(self.output)
+  }
+}
+
 #[derive(Deserialize, PartialEq, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct Batchfile {
@@ -1063,22 +2242,163 @@ pub(crate) struct Batchfile {
   pub(crate) inscriptions: Vec<BatchEntry>,
   pub(crate) mode: Mode,
   pub(crate) parent: Option<InscriptionId>,
+  pub(crate) parent_destination: Option<Address<NetworkUnchecked>>,
   pub(crate) parent_satpoint: Option<SatPoint>,
+  pub(crate) parents: Option<Vec<InscriptionId>>,
   pub(crate) postage: Option<u64>,
   pub(crate) sat: Option<Sat>,
 }
 
 impl Batchfile {
   pub(crate) fn load(path: &Path) -> Result<Batchfile> {
-    let batchfile: Batchfile = serde_yaml::from_reader(File::open(path)?)?;
+    let batchfile: Batchfile = serde_yaml::from_reader(File::open(path)?)
+      .map_err(|err| Self::unknown_field_error(path, err))?;
 
     if batchfile.inscriptions.is_empty() {
       bail!("batchfile must contain at least one inscription");
     }
 
+    for (i, entry) in batchfile.inscriptions.iter().enumerate() {
+      match (&entry.file, entry.delegate) {
+        (None, None) => bail!("inscription {i} needs a `file` or a `delegate`"),
+        (Some(file), Some(delegate))
+          if file != Path::new("none")
+            && fs::metadata(file).map(|metadata| metadata.len() > 0).unwrap_or_default() =>
+        {
+          bail!(
+            "inscription {i} has both a non-empty `file` {} and a `delegate` {delegate}, but a delegate's content is used in place of the file's",
+            file.display()
+          )
+        }
+        _ => {}
+      }
+
+      if let Some(encoding) = &entry.content_encoding {
+        if !SUPPORTED_CONTENT_ENCODINGS.contains(&encoding.as_str()) {
+          bail!(
+            "inscription {i} has unsupported `content_encoding` `{encoding}`; supported encodings are {}",
+            SUPPORTED_CONTENT_ENCODINGS
+              .iter()
+              .map(|encoding| format!("`{encoding}`"))
+              .collect::<Vec<String>>()
+              .join(", ")
+          );
+        }
+      }
+    }
+
+    let parents = batchfile.parents();
+
+    if let Some(parent) = parents
+      .iter()
+      .enumerate()
+      .find(|(i, parent)| parents[..*i].contains(parent))
+      .map(|(_i, parent)| parent)
+    {
+      bail!("parent {parent} is listed more than once");
+    }
+
     Ok(batchfile)
   }
 
+  // serde rejects typos like `postages` with a list of every field it expected, so point at the closest one
+  fn unknown_field_error(path: &Path, err: serde_yaml::Error) -> Error {
+    let message = err.to_string();
+
+    let Some((field, expected)) = message
+      .split_once("unknown field `")
+      .and_then(|(_, rest)| rest.split_once('`'))
+    else {
+      return err.into();
+    };
+
+    let Some((distance, suggestion)) = expected
+      .split('`')
+      .skip(1)
+      .step_by(2)
+      .map(|candidate| (edit_distance(field, candidate), candidate))
+      .min()
+    else {
+      return err.into();
+    };
+
+    if distance > (field.len() / 3).max(1) {
+      return err.into();
+    }
+
+    match err.location() {
+      Some(location) => anyhow!(
+        "unknown field `{field}` at line {} column {} of batch file {}, did you mean `{suggestion}`?",
+        location.line(),
+        location.column(),
+        path.display(),
+      ),
+      None => anyhow!(
+        "unknown field `{field}` in batch file {}, did you mean `{suggestion}`?",
+        path.display(),
+      ),
+    }
+  }
+
+  // every inscription goes in the same reveal, and each witness byte weighs one unit, so metadata
+  // adding up to more than MAX_STANDARD_TX_WEIGHT can never make a standard reveal
+  pub(crate) fn check_metadata_size(&self) -> Result {
+    let mut total = 0;
+
+    for (i, entry) in self.inscriptions.iter().enumerate() {
+      let Some(metadata) = entry.metadata()? else {
+        continue;
+      };
+
+      total += metadata.len();
+
+      if total > MAX_STANDARD_TX_WEIGHT as usize {
+        bail!(
+          "inscription {i} has {} bytes of CBOR metadata, bringing the batch's total to {total} bytes, which would push the reveal transaction past MAX_STANDARD_TX_WEIGHT of {MAX_STANDARD_TX_WEIGHT} weight units; use --no-limit to inscribe it anyway",
+          metadata.len()
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  // `parent`, then any further `parents`, in the order their inputs and outputs appear in the reveal tx
+  pub(crate) fn parents(&self) -> Vec<InscriptionId> {
+    self
+      .parent
+      .into_iter()
+      .chain(self.parents.iter().flatten().copied())
+      .collect()
+  }
+
+  pub(crate) fn parent_destination(
+    &self,
+    parent_destination: Option<Address<NetworkUnchecked>>,
+  ) -> Result<Option<Address<NetworkUnchecked>>> {
+    if self.parent_destination.is_none() {
+      return Ok(parent_destination);
+    }
+
+    if self.parents().is_empty() {
+      return Err(anyhow!("`parent_destination` can only be set in a batchfile with a `parent`"));
+    }
+
+    if parent_destination.is_some() {
+      return Err(anyhow!("don't use `--parent-destination` with a batchfile that sets `parent_destination`"));
+    }
+
+    Ok(self.parent_destination.clone())
+  }
+
+  pub(crate) fn postages(&self) -> Vec<Option<Amount>> {
+    self
+      .inscriptions
+      .iter()
+      .map(|entry| entry.postage.map(Amount::from_sat))
+      .collect()
+  }
+
   pub(crate) fn inscriptions(
     &self,
     client: &Client,
@@ -1089,9 +2409,25 @@ impl Batchfile {
     compress: bool,
     skip_pointer_for_none: bool,
     utxos: &mut BTreeMap<OutPoint, Amount>,
+    content_type_map: &BTreeMap<String, String>,
+    collect_errors: bool,
+    metaprotocols: Option<&[String]>,
   ) -> Result<(Vec<Inscription>, Vec<Address>, bool, Vec<OutPoint>)> {
     assert!(!self.inscriptions.is_empty());
 
+    if let Some(metaprotocols) = metaprotocols {
+      for (i, entry) in self.inscriptions.iter().enumerate() {
+        if let Some(metaprotocol) = &entry.metaprotocol {
+          if !metaprotocols.contains(metaprotocol) {
+            bail!(
+              "inscription {i} has metaprotocol `{metaprotocol}`, which isn't one of --metaprotocols {}",
+              metaprotocols.join(",")
+            );
+          }
+        }
+      }
+    }
+
     if self
       .inscriptions
       .iter()
@@ -1113,11 +2449,25 @@ impl Batchfile {
       false
     };
 
+    if self.inscriptions.iter().any(|entry| entry.postage.is_some()) {
+      if self.mode != Mode::SeparateOutputs {
+        return Err(anyhow!("individual inscription postage can only be set in `separate-outputs` mode"));
+      }
+
+      if inscribe_on_specific_utxos {
+        return Err(anyhow!("individual inscription postage cannot be set when specifying the utxo to inscribe on for each inscription"));
+      }
+    }
+
     if inscribe_on_specific_utxos {
       if self.postage.is_some() {
         return Err(anyhow!("postage size cannot be set when specifying the utxo to inscribe on for each inscription"))
       }
 
+      if self.sat.is_some() {
+        return Err(anyhow!("`sat` cannot be set when specifying the utxo to inscribe on for each inscription"));
+      }
+
       if self.mode == Mode::SameSat {
         return Err(anyhow!("Inscription utxos can't be specified in `same-sat` mode"));
       }
@@ -1129,6 +2479,17 @@ impl Batchfile {
       }
     }
 
+    // an unbound inscription isn't on any sat, so there's no sharing an output or sat with it, or placing it on a utxo
+    if self.inscriptions.iter().any(|entry| entry.unbound) {
+      if self.mode != Mode::SeparateOutputs {
+        return Err(anyhow!("unbound inscriptions can only be created in `separate-outputs` mode"));
+      }
+
+      if inscribe_on_specific_utxos {
+        return Err(anyhow!("unbound inscriptions can't be placed on a specific `utxo`"));
+      }
+    }
+
     if metadata.is_some() {
       assert!(self
         .inscriptions
@@ -1138,16 +2499,27 @@ impl Batchfile {
 
     let mut pointer = parent_value.unwrap_or_default();
 
+    let parents = self.parents();
+
     let mut inscriptions = Vec::new();
+    let mut errors = Vec::new();
     for (i, entry) in self.inscriptions.iter().enumerate() {
       if entry.offset.is_some() && entry.pointer.is_some() {
         return Err(anyhow!("you can't specify `offset` and `pointer` for the same inscription (inscription {i})"));
       }
-      inscriptions.push(Inscription::from_file(
+
+      // an entry with only a `delegate` has no body of its own
+      let file = entry.file.clone().unwrap_or_else(|| PathBuf::from("none"));
+
+      let inscription = entry.metadata().and_then(|entry_metadata| Inscription::from_file(
         chain,
         entry.delegate,
-        &entry.file,
-        self.parent,
+        &file,
+        entry
+          .content_type
+          .clone()
+          .or_else(|| mapped_content_type(content_type_map, &file)),
+        parents.first().copied(),
         match entry.pointer {
           Some(pointer) => Some(pointer),
           None => match entry.offset {
@@ -1158,20 +2530,46 @@ impl Batchfile {
         entry.metaprotocol.clone(),
         match &metadata {
           Some(metadata) => Some(metadata.clone()),
-          None => entry.metadata()?,
+          None => entry_metadata,
         },
-        compress,
+        // an entry's own encoding overrides --compress
+        entry
+          .content_encoding
+          .as_deref()
+          .or(compress.then_some("br")),
         skip_pointer_for_none,
         entry.utxo,
-      )?);
+        entry.unbound,
+      ))
+      .map(|mut inscription| {
+        inscription.additional_parents = parents.iter().skip(1).map(|id| id.value()).collect();
+        inscription
+      });
+
+      match inscription {
+        Ok(inscription) => inscriptions.push(inscription),
+        Err(err) if collect_errors => {
+          errors.push(format!("inscription {i} ({}): {err}", file.display()))
+        }
+        Err(err) => return Err(err),
+      }
 
       if inscribe_on_specific_utxos {
         pointer += utxos[&entry.utxo.unwrap()].to_sat();
       } else {
-        pointer += postage.to_sat();
+        pointer += entry.postage.map(Amount::from_sat).unwrap_or(postage).to_sat();
       }
     }
 
+    if !errors.is_empty() {
+      bail!(
+        "{} of {} batch entries failed:\n{}",
+        errors.len(),
+        self.inscriptions.len(),
+        errors.join("\n"),
+      );
+    }
+
     let destinations = match self.mode {
       Mode::SharedOutput | Mode::SameSat => vec![get_change_address(client, chain)?],
       Mode::SeparateOutputs => self
@@ -1199,3 +2597,26 @@ impl Batchfile {
     Ok((inscriptions, destinations, inscribe_on_specific_utxos, fees))
   }
 }
+
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b = b.chars().collect::<Vec<char>>();
+
+  let mut row = (0..=b.len()).collect::<Vec<usize>>();
+
+  for (i, a) in a.chars().enumerate() {
+    let mut diagonal = row[0];
+    row[0] = i + 1;
+
+    for (j, b) in b.iter().enumerate() {
+      let above = row[j + 1];
+      row[j + 1] = if a == *b {
+        diagonal
+      } else {
+        1 + diagonal.min(above).min(row[j])
+      };
+      diagonal = above;
+    }
+  }
+
+  row[b.len()]
+}
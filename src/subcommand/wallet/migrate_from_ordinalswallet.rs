@@ -0,0 +1,161 @@
+use {super::*, bitcoincore_rpc::RawTx, sendmany::SendMany};
+
+#[derive(Debug, Parser)]
+pub(crate) struct MigrateFromOrdinalswallet {
+  #[arg(long, help = "Create standard wallet <TO> and move everything into it.")]
+  to: String,
+  #[arg(
+    long,
+    default_value = "",
+    help = "Use <PASSPHRASE> to derive the new wallet's seed."
+  )]
+  passphrase: String,
+  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[arg(long, help = "Broadcast the migration transaction; the default is to output the raw transaction hex so you can check it before broadcasting.")]
+  broadcast: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub addresses: Vec<Address<NetworkUnchecked>>,
+  pub inscriptions: usize,
+  pub mnemonic: Mnemonic,
+  pub passphrase: Option<String>,
+  pub tx: String,
+  pub wallet: String,
+}
+
+impl MigrateFromOrdinalswallet {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+    let unspent_outputs = get_unspent_outputs(&client, &index)?;
+
+    if unspent_outputs.is_empty() {
+      bail!("wallet has nothing to migrate");
+    }
+
+    if let Some(outpoint) = index
+      .get_runic_outputs(&unspent_outputs.keys().cloned().collect::<Vec<OutPoint>>())?
+      .first()
+    {
+      bail!("output {outpoint} holds runes, which this would burn; move them out of the wallet first");
+    }
+
+    let inscriptions = index.get_inscriptions_vector(&unspent_outputs)?;
+
+    let inscribed = inscriptions
+      .iter()
+      .map(|(satpoint, _inscription_id)| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let cardinals = unspent_outputs
+      .iter()
+      .filter(|(outpoint, _amount)| !inscribed.contains(outpoint))
+      .map(|(outpoint, amount)| (*outpoint, amount.to_sat()))
+      .collect::<Vec<(OutPoint, u64)>>();
+
+    if cardinals.is_empty() {
+      bail!("wallet has no cardinals to pay the migration fee");
+    }
+
+    // inscribed outputs go first, each to its own output of the same value, so every
+    // inscription keeps its offset; the cardinals are merged into one last output that pays the fee
+    let inputs = inscribed
+      .iter()
+      .cloned()
+      .chain(cardinals.iter().map(|(outpoint, _value)| *outpoint))
+      .collect::<Vec<OutPoint>>();
+
+    // the new wallet only has taproot addresses, so any taproot script stands in for them when
+    // sizing the transaction, before the new wallet exists
+    let taproot_script = get_change_address(&client, options.chain())?.script_pubkey();
+
+    let fee = self
+      .fee_rate
+      .fee(
+        SendMany::build_fake_transaction(
+          &inputs,
+          &vec![
+            TxOut {
+              script_pubkey: taproot_script.clone(),
+              value: 0,
+            };
+            inscribed.len() + 1
+          ],
+        )
+        .vsize(),
+      )
+      .to_sat();
+
+    let cardinal_value = cardinals.iter().map(|(_outpoint, value)| value).sum::<u64>();
+    let dust_limit = taproot_script.dust_value().to_sat();
+
+    if cardinal_value < fee + dust_limit {
+      bail!(
+        "wallet cardinals ({cardinal_value} sats) are too small\n       we need enough for fee {fee} plus dust limit {dust_limit} = {} sats",
+        fee + dust_limit
+      );
+    }
+
+    let mut entropy = [0; 16];
+    rand::thread_rng().fill_bytes(&mut entropy);
+
+    let mnemonic = Mnemonic::from_entropy(&entropy)?;
+
+    wallet::initialize(
+      self.to.clone(),
+      &options,
+      mnemonic.to_seed(self.passphrase.clone()),
+      AddressType::Bech32m,
+      false,
+    )?;
+
+    let new_client = bitcoin_rpc_client_for_wallet_command(self.to.clone(), &options)?;
+
+    let mut addresses = Vec::new();
+    let mut outputs = Vec::new();
+
+    for outpoint in inscribed.iter().map(Some).chain([None]) {
+      let address = new_client.get_new_address(None, Some(bitcoincore_rpc::json::AddressType::Bech32m))?;
+      let script_pubkey = address.clone().require_network(options.chain().network())?.script_pubkey();
+
+      let value = match outpoint {
+        Some(outpoint) => unspent_outputs[outpoint].to_sat(),
+        None => cardinal_value - fee,
+      };
+
+      addresses.push(address);
+      outputs.push(TxOut {
+        script_pubkey,
+        value,
+      });
+    }
+
+    let tx = SendMany::build_transaction(&inputs, &outputs);
+
+    let signed_tx = client.sign_raw_transaction_with_wallet(&tx, None, None)?;
+
+    if !signed_tx.complete {
+      bail!("failed to sign migration transaction");
+    }
+
+    let tx = if self.broadcast {
+      client.send_raw_transaction(&signed_tx.hex)?.to_string()
+    } else {
+      signed_tx.hex.raw_hex()
+    };
+
+    Ok(Box::new(Output {
+      addresses,
+      inscriptions: inscriptions.len(),
+      mnemonic,
+      passphrase: Some(self.passphrase),
+      tx,
+      wallet: self.to,
+    }))
+  }
+}
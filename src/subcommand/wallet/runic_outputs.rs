@@ -0,0 +1,45 @@
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub output: OutPoint,
+  pub amount: u64,
+  pub runes: BTreeMap<Rune, u128>,
+}
+
+pub(crate) fn run(wallet: String, options: Options) -> SubcommandResult {
+  let index = Index::open(&options)?;
+
+  if !index.has_rune_index() {
+    bail!("`wallet runic-outputs` requires index created with `--index-runes` flag");
+  }
+
+  index.update()?;
+
+  let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+  let unspent_outputs = get_unspent_outputs(&client, &index)?;
+
+  let runic_outputs =
+    index.get_runic_outputs(&unspent_outputs.keys().cloned().collect::<Vec<OutPoint>>())?;
+
+  let mut outputs = Vec::new();
+  for (output, amount) in unspent_outputs {
+    if !runic_outputs.contains(&output) {
+      continue;
+    }
+
+    let mut runes = BTreeMap::new();
+    for (spaced_rune, pile) in index.get_rune_balances_for_outpoint(output)? {
+      *runes.entry(spaced_rune.rune).or_default() += pile.amount;
+    }
+
+    outputs.push(Output {
+      output,
+      amount: amount.to_sat(),
+      runes,
+    });
+  }
+
+  Ok(Box::new(outputs))
+}
@@ -150,9 +150,7 @@ impl Send {
     )
     .build_transaction()?;
 
-    let signed_tx = client
-      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
-      .hex;
+    let signed_tx = sign_transaction(&client, &unsigned_transaction, None)?;
 
     let txid = client.send_raw_transaction(&signed_tx)?;
 
@@ -307,9 +305,7 @@ impl Send {
 
     let unsigned_transaction = fund_raw_transaction(client, fee_rate, &unfunded_transaction)?;
 
-    let signed_transaction = client
-      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
-      .hex;
+    let signed_transaction = sign_transaction(client, &unsigned_transaction, None)?;
 
     Ok(client.send_raw_transaction(&signed_transaction)?)
   }
@@ -1,8 +1,10 @@
 use {
   super::*,
+  base64::{Engine as _, engine::general_purpose},
   bitcoin::{
     locktime::absolute::LockTime,
     policy::MAX_STANDARD_TX_WEIGHT,
+    psbt::Psbt,
     Witness,
   },
   bitcoincore_rpc::RawTx,
@@ -15,10 +17,16 @@ use {
 
 #[derive(Debug, Parser, Clone)]
 pub(crate) struct SendMany {
-  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
-  fee_rate: FeeRate,
-  #[arg(long, help = "Location of a CSV file containing `inscriptionid`,`destination` pairs.")]
-  pub(crate) csv: PathBuf,
+  #[arg(long, required_unless_present = "fee", help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: Option<FeeRate>,
+  #[arg(long, conflicts_with = "fee_rate", help = "Pay a fee of exactly <FEE>, e.g. `5000sat`, whatever the transaction's size.")]
+  fee: Option<Amount>,
+  #[arg(long, required_unless_present = "json", conflicts_with = "json", help = "Location of a CSV file containing `inscriptionid`,`destination` pairs.")]
+  pub(crate) csv: Option<PathBuf>,
+  #[arg(long, requires = "csv", help = "Skip the first line of the --csv file if it doesn't start with an inscription ID, as a header row would.")]
+  pub(crate) header: bool,
+  #[arg(long, help = "Location of a JSON file containing an array of `{\"inscription\": <INSCRIPTION_ID>, \"destination\": <ADDRESS>}` objects, as an alternative to --csv.")]
+  pub(crate) json: Option<PathBuf>,
   #[arg(long, help = "Broadcast the transaction; the default is to output the raw tranasction hex so you can check it before broadcasting.")]
   pub(crate) broadcast: bool,
   #[arg(long, help = "Do not check that the transaction is equal to or below the MAX_STANDARD_TX_WEIGHT of 400,000 weight units. Transactions over this limit are currently nonstandard and will not be relayed by bitcoind in its default configuration. Do not use this flag unless you understand the implications."
@@ -34,21 +42,49 @@ pub(crate) struct SendMany {
   pub(crate) change: Option<Address<NetworkUnchecked>>,
   #[arg(long, help = "Which cardinal to use to pay the fees.")]
   pub(crate) cardinal: Option<OutPoint>,
+  #[arg(long, conflicts_with = "broadcast", help = "Don't sign the transaction; output it as a base64 PSBT for signing elsewhere.")]
+  pub(crate) psbt: bool,
+  #[arg(long, help = "Require every destination to be an address controlled by this wallet, as a safety rail when reshuffling inscriptions between your own addresses.")]
+  pub(crate) own_destinations_only: bool,
+  #[arg(long, requires = "consolidate_threshold", help = "Also spend every unlocked cardinal worth less than --consolidate-threshold, folding it into the change output.")]
+  pub(crate) consolidate: bool,
+  #[arg(long, requires = "consolidate", help = "Sweep cardinals worth less than <CONSOLIDATE_THRESHOLD>, e.g. `10000sat`, when using --consolidate.")]
+  pub(crate) consolidate_threshold: Option<Amount>,
+  #[arg(long, conflicts_with = "psbt", help = "Lock the transaction's inputs in the wallet once it's signed, so other wallet commands don't spend them. This is the default unless --broadcast is set.")]
+  pub(crate) lock_inputs: bool,
+  #[arg(long, conflicts_with = "fee", help = "Drop the change output if it would be worth less than <DUST_TO_FEE>, e.g. `2000sat`, adding its value to the fee instead.")]
+  pub(crate) dust_to_fee: Option<Amount>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Transfer {
+  inscription: InscriptionId,
+  destination: Address<NetworkUnchecked>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Output {
+  pub change: u64,
+  pub fee: u64,
+  pub input_count: usize,
+  pub locked: Vec<OutPoint>,
   pub tx: String,
 }
 
 impl SendMany {
-  const SCHNORR_SIGNATURE_SIZE: usize = 64;
+  pub(crate) const SCHNORR_SIGNATURE_SIZE: usize = 64;
+
+  fn fee(&self, vsize: usize) -> u64 {
+    match self.fee {
+      Some(fee) => fee.to_sat(),
+      None => self.fee_rate.unwrap().fee(vsize).to_sat(),
+    }
+  }
 
   pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
-    let file = File::open(&self.csv)?;
-    let reader = BufReader::new(file);
-    let mut line_number = 1;
     let mut requested = BTreeMap::new();
+    let mut lines = BTreeMap::new();
 
     let chain = options.chain();
 
@@ -56,43 +92,51 @@ impl SendMany {
         bail!("--min-postage {} sats is bigger than --max-postage {} sats", self.min_postage.unwrap().to_sat(), self.max_postage.unwrap().to_sat());
     }
 
-    for line in reader.lines() {
-      let line = line?;
-      let mut line = line.trim_start_matches('\u{feff}').split(',');
-
-      let inscriptionid = line.next().ok_or_else(|| {
-        anyhow!("CSV file '{}' is not formatted correctly - no inscriptionid on line {line_number}", self.csv.display())
-      })?;
-
-      let inscriptionid = match InscriptionId::from_str(inscriptionid) {
-        Err(e) => bail!("bad inscriptionid on line {line_number}: {}", e),
-        Ok(ok) => ok,
-      };
-
-      let destination = line.next().ok_or_else(|| {
-        anyhow!("CSV file '{}' is not formatted correctly - no comma on line {line_number}", self.csv.display())
-      })?;
+    // CSV files are numbered by line, and JSON files by entry
+    let (entries, item) = match (&self.csv, &self.json) {
+      (Some(csv), None) => (Self::read_csv(csv, self.header)?, "line"),
+      (None, Some(json)) => (Self::read_json(json)?, "entry"),
+      _ => bail!("exactly one of --csv and --json is required"),
+    };
 
-      let destination = match match Address::from_str(destination) {
-        Err(e) => bail!("bad address on line {line_number}: {}", e),
-        Ok(ok) => ok,
-      }.require_network(chain.network()) {
-        Err(e) => bail!("bad network for address on line {line_number}: {}", e),
+    for (line_number, inscriptionid, destination) in entries {
+      let destination = match destination.require_network(chain.network()) {
+        Err(e) => bail!("bad network for address on {item} {line_number}: {}", e),
         Ok(ok) => ok,
       };
 
       if requested.contains_key(&inscriptionid) {
-        bail!("duplicate entry for {} on line {}", inscriptionid.to_string(), line_number);
+        bail!("duplicate entry for {} on {item} {}", inscriptionid, line_number);
       }
 
       requested.insert(inscriptionid, destination);
-      line_number += 1;
+      lines.insert(inscriptionid, line_number);
+    }
+
+    // no output can be both under --max-postage and over its destination's dust limit, so don't bother building anything
+    if let Some(max_postage) = self.max_postage {
+      for (inscriptionid, destination) in &requested {
+        let dust_limit = destination.script_pubkey().dust_value();
+        if dust_limit > max_postage {
+          bail!("--max-postage {} sats is less than dust limit {} for address {} (inscription {})",
+                max_postage.to_sat(), dust_limit.to_sat(), destination, inscriptionid);
+        }
+      }
     }
 
     let index = Index::open(&options)?;
     index.update()?;
 
     let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+    if self.own_destinations_only {
+      for (inscriptionid, destination) in &requested {
+        if client.get_address_info(destination)?.is_mine != Some(true) {
+          bail!("destination {} on {item} {} (inscription {}) is not controlled by this wallet", destination, lines[inscriptionid], inscriptionid);
+        }
+      }
+    }
+
     let unspent_outputs = get_unspent_outputs(&client, &index)?;
     let locked_outputs = get_locked_outputs(&client)?;
 
@@ -120,6 +164,15 @@ impl SendMany {
       requested_satpoints.insert(satpoint, (inscriptionid.clone(), address.clone()));
     }
 
+    // the per-output lookups are independent, read-only, and the slow part of a big send, so do them all up front
+    let inscriptions_on_outpoints = Self::get_inscriptions_on_outputs(
+      &index,
+      requested
+        .keys()
+        .map(|inscriptionid| inscriptions[inscriptionid].outpoint)
+        .collect(),
+    )?;
+
     let change_dust_limit = Self::get_change_pubkey(&client, chain, self.change.clone())?.dust_value().to_sat();
 
     let mut cardinal_value = 0;
@@ -132,7 +185,7 @@ impl SendMany {
         // look up which utxo it's in
         let outpoint = inscriptions[inscriptionid].outpoint;
         // get a list of the inscriptions in that utxo
-        inscriptions_on_outpoint = index.get_inscriptions_on_output_with_satpoints(outpoint)?;
+        inscriptions_on_outpoint = inscriptions_on_outpoints.get(&outpoint).cloned().unwrap_or_default();
         // sort it by offset
         inscriptions_on_outpoint.sort_by_key(|(s, _)| s.offset);
         // make sure that they are all in the csv file, unless --ignore-unlisted is in effect
@@ -237,31 +290,42 @@ impl SendMany {
         "transaction weight greater than {MAX_STANDARD_TX_WEIGHT} (MAX_STANDARD_TX_WEIGHT): {weight}"
       );
     }
-    let fee = self.fee_rate.fee(fake_tx.vsize()).to_sat();
+    let mut fee = self.fee(fake_tx.vsize());
     let needed = fee + change_dust_limit;
-    let value;
+
+    let inscribed_outputs = inscriptions
+      .values()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let runic_outputs =
+      index.get_runic_outputs(&unspent_outputs.keys().cloned().collect::<Vec<OutPoint>>())?;
+
+    let mut value;
     if cardinal_value < needed {
       // eprintln!("left over amount ({} sats) is too small\n       we need enough for fee {} plus dust limit {} = {} sats", cardinal_value, fee, change_dust_limit, needed);
 
-      let (cardinal_outpoint, new_cardinal_value) = match self.cardinal {
-        Some(cardinal) => (cardinal, unspent_outputs[&cardinal].to_sat()),
+      let cardinals = match self.cardinal {
+        Some(cardinal) => vec![(cardinal, unspent_outputs[&cardinal].to_sat())],
         None => {
-          // select the biggest cardinal - this could be improved by figuring out what size we need, and picking the next biggest for example
-          // get a list of available unlocked cardinals
-          let cardinals = Self::get_cardinals(unspent_outputs.clone(), locked_outputs, inscriptions);
-
-          if cardinals.is_empty() {
-            bail!("wallet has no cardinals");
-          }
-
-          cardinals[0]
+          Self::select_cardinals(
+            &unspent_outputs,
+            &locked_outputs,
+            &inscribed_outputs,
+            &runic_outputs,
+            |count| {
+              let mut inputs = inputs.clone();
+              inputs.extend(std::iter::repeat(OutPoint::null()).take(count));
+              let needed = self.fee(Self::build_fake_transaction(&inputs, &outputs).vsize()) + change_dust_limit;
+              Ok(needed.saturating_sub(cardinal_value))
+            },
+          )?
         }
       };
 
-      // eprintln!("we have {} left over, and {} in the biggest cardinal", cardinal_value, new_cardinal_value);
-
-      // use the biggest cardinal as the last input
-      inputs.push(cardinal_outpoint);
+      // the chosen cardinals are the last inputs
+      inputs.extend(cardinals.iter().map(|(outpoint, _value)| *outpoint));
+      let new_cardinal_value = cardinals.iter().map(|(_outpoint, value)| value).sum::<u64>();
 
       // calculate the size of the tx once it is signed
       let fake_tx = Self::build_fake_transaction(&inputs, &outputs);
@@ -271,34 +335,237 @@ impl SendMany {
           "transaction weight greater than {MAX_STANDARD_TX_WEIGHT} (MAX_STANDARD_TX_WEIGHT): {weight}"
         );
       }
-      let fee = self.fee_rate.fee(fake_tx.vsize()).to_sat();
+      fee = self.fee(fake_tx.vsize());
       let needed = fee + change_dust_limit;
       if cardinal_value + new_cardinal_value < needed {
-        bail!("cardinal {} ({} sats) is too small\n       we need enough for fee {} plus dust limit {} = {} sats",
-              cardinal_outpoint.to_string(), new_cardinal_value, fee, change_dust_limit, needed - cardinal_value);
+        match self.cardinal {
+          Some(cardinal) => bail!("cardinal {cardinal} ({} sats) is too small\n       we need enough for fee {} plus dust limit {} = {} sats",
+                                  new_cardinal_value, fee, change_dust_limit, needed - cardinal_value),
+          None => bail!("wallet cardinals ({} sats) are too small\n       we need enough for fee {} plus dust limit {} = {} sats",
+                        new_cardinal_value, fee, change_dust_limit, needed - cardinal_value),
+        }
       }
       value = cardinal_value + new_cardinal_value - fee;
     } else {
       value = cardinal_value - fee;
     }
 
-    let last = outputs.len() - 1;
-    outputs[last] = TxOut{script_pubkey, value};
+    if let Some(threshold) = self.consolidate_threshold {
+      let swept = unspent_outputs
+        .iter()
+        .filter(|(outpoint, amount)| {
+          **amount < threshold
+            && !inputs.contains(outpoint)
+            && !locked_outputs.contains(outpoint)
+            && !inscribed_outputs.contains(outpoint)
+            && !runic_outputs.contains(outpoint)
+        })
+        .map(|(outpoint, amount)| (*outpoint, amount.to_sat()))
+        .collect::<Vec<(OutPoint, u64)>>();
+
+      if !swept.is_empty() {
+        inputs.extend(swept.iter().map(|(outpoint, _value)| *outpoint));
+        let swept_value = swept.iter().map(|(_outpoint, value)| value).sum::<u64>();
+
+        let fake_tx = Self::build_fake_transaction(&inputs, &outputs);
+        let weight = fake_tx.weight();
+        if !self.no_limit && weight > bitcoin::Weight::from_wu(MAX_STANDARD_TX_WEIGHT.into()) {
+          bail!(
+            "transaction weight greater than {MAX_STANDARD_TX_WEIGHT} (MAX_STANDARD_TX_WEIGHT) after consolidating {} cardinals: {weight}",
+            swept.len()
+          );
+        }
+
+        // the swept cardinals can be worth less than the fee for spending them
+        let swept_fee = self.fee(fake_tx.vsize());
+        if value + fee + swept_value < swept_fee + change_dust_limit {
+          bail!("consolidating {} cardinals ({swept_value} sats) would leave change below the dust limit {change_dust_limit}; lower --consolidate-threshold", swept.len());
+        }
+
+        value = value + fee + swept_value - swept_fee;
+        fee = swept_fee;
+      }
+    }
+
+    // change too small to be worth spending later goes to the miners instead, as long as something else is being sent
+    if outputs.len() > 1 && self.dust_to_fee.map_or(false, |threshold| value < threshold.to_sat()) {
+      outputs.pop();
+      fee += value;
+      value = 0;
+    } else {
+      let last = outputs.len() - 1;
+      outputs[last] = TxOut{script_pubkey, value};
+    }
 
     let tx = Self::build_transaction(&inputs, &outputs);
 
-    let signed_tx = client.sign_raw_transaction_with_wallet(&tx, None, None)?;
-    let signed_tx = signed_tx.hex;
+    let mut locked = Vec::new();
 
-    if self.broadcast {
-      let txid = client.send_raw_transaction(&signed_tx)?.to_string();
-      Ok(Box::new(Output { tx: txid }))
+    let tx = if self.psbt {
+      Self::unsigned_psbt(&client, tx)?
     } else {
-      Ok(Box::new(Output { tx: signed_tx.raw_hex() }))
+      let signed_tx = sign_transaction(&client, &tx, None)?;
+
+      let tx = if self.broadcast {
+        client.send_raw_transaction(&signed_tx)?.to_string()
+      } else {
+        signed_tx.raw_hex()
+      };
+
+      // an unbroadcast transaction's inputs look unspent to the wallet, so keep them from being picked again.
+      // this happens after broadcasting, so that a rejected transaction doesn't leave its inputs locked.
+      if self.lock_inputs || !self.broadcast {
+        locked = inputs
+          .iter()
+          .filter(|outpoint| !locked_outputs.contains(outpoint))
+          .cloned()
+          .collect();
+
+        if !client.lock_unspent(&locked)? {
+          if self.broadcast {
+            bail!("failed to lock UTXOs spent by broadcast transaction {tx}");
+          }
+          bail!("failed to lock UTXOs");
+        }
+      }
+
+      tx
+    };
+
+    Ok(Box::new(Output {
+      change: value,
+      fee,
+      input_count: inputs.len(),
+      locked,
+      tx,
+    }))
+  }
+
+  // look up the inscriptions on each of `outpoints`, spreading the lookups over a thread per core
+  fn get_inscriptions_on_outputs(
+    index: &Index,
+    outpoints: BTreeSet<OutPoint>,
+  ) -> Result<BTreeMap<OutPoint, Vec<(SatPoint, InscriptionId)>>> {
+    let outpoints = outpoints.into_iter().collect::<Vec<OutPoint>>();
+
+    let threads = std::thread::available_parallelism()
+      .map(usize::from)
+      .unwrap_or(1);
+
+    let chunk_size = ((outpoints.len() + threads - 1) / threads).max(1);
+
+    std::thread::scope(|scope| {
+      let handles = outpoints
+        .chunks(chunk_size)
+        .map(|chunk| {
+          scope.spawn(move || {
+            chunk
+              .iter()
+              .map(|outpoint| Ok((*outpoint, index.get_inscriptions_on_output_with_satpoints(*outpoint)?)))
+              .collect::<Result<Vec<(OutPoint, Vec<(SatPoint, InscriptionId)>)>>>()
+          })
+        })
+        .collect::<Vec<_>>();
+
+      let mut inscriptions_on_outputs = BTreeMap::new();
+
+      for handle in handles {
+        inscriptions_on_outputs.extend(handle.join().expect("inscription lookup thread panicked")?);
+      }
+
+      Ok(inscriptions_on_outputs)
+    })
+  }
+
+  // the comma-separated fields of each line that isn't blank or a `#` comment, trimmed and paired
+  // with the line they're on, so errors still point at the right line after skipped lines; shared
+  // with `wallet send-many-sats` so both CSV formats follow the same rules
+  pub(crate) fn read_csv_records(path: &Path) -> Result<Vec<(usize, Vec<String>)>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+
+    for (line_number, line) in (1..).zip(reader.lines()) {
+      let line = line?;
+      let line = line.trim_start_matches('\u{feff}').trim();
+
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      records.push((line_number, line.split(',').map(|field| field.trim().to_string()).collect()));
     }
+
+    Ok(records)
   }
 
-  fn get_change_pubkey(
+  fn read_csv(path: &Path, header: bool) -> Result<Vec<(usize, InscriptionId, Address<NetworkUnchecked>)>> {
+    let mut entries = Vec::new();
+    let mut first = true;
+
+    for (line_number, fields) in Self::read_csv_records(path)? {
+      let mut line = fields.iter().map(String::as_str);
+
+      let inscriptionid = line.next().ok_or_else(|| {
+        anyhow!("CSV file '{}' is not formatted correctly - no inscriptionid on line {line_number}", path.display())
+      })?;
+
+      let inscriptionid = match InscriptionId::from_str(inscriptionid) {
+        Err(_) if header && first => {
+          first = false;
+          continue;
+        }
+        Err(e) => bail!("bad inscriptionid on line {line_number}: {}", e),
+        Ok(ok) => ok,
+      };
+
+      first = false;
+
+      let destination = line.next().ok_or_else(|| {
+        anyhow!("CSV file '{}' is not formatted correctly - no comma on line {line_number}", path.display())
+      })?;
+
+      let destination = match Address::from_str(destination) {
+        Err(e) => bail!("bad address on line {line_number}: {}", e),
+        Ok(ok) => ok,
+      };
+
+      entries.push((line_number, inscriptionid, destination));
+    }
+
+    Ok(entries)
+  }
+
+  fn read_json(path: &Path) -> Result<Vec<(usize, InscriptionId, Address<NetworkUnchecked>)>> {
+    let transfers: Vec<Transfer> = serde_json::from_reader(File::open(path)?)
+      .with_context(|| format!("JSON file '{}' is not formatted correctly", path.display()))?;
+
+    Ok(
+      (1..)
+        .zip(transfers)
+        .map(|(entry, transfer)| (entry, transfer.inscription, transfer.destination))
+        .collect(),
+    )
+  }
+
+  pub(crate) fn unsigned_psbt(client: &Client, tx: Transaction) -> Result<String> {
+    Ok(general_purpose::STANDARD.encode(Self::witness_utxo_psbt(client, tx)?.serialize()))
+  }
+
+  pub(crate) fn witness_utxo_psbt(client: &Client, tx: Transaction) -> Result<Psbt> {
+    let mut psbt = Psbt::from_unsigned_tx(tx)?;
+
+    // external signers need the spent outputs to compute taproot and segwit sighashes
+    for (input, txin) in psbt.inputs.iter_mut().zip(&psbt.unsigned_tx.input) {
+      let outpoint = txin.previous_output;
+      input.witness_utxo = Some(
+        client.get_raw_transaction(&outpoint.txid, None)?.output[outpoint.vout as usize].clone(),
+      );
+    }
+
+    Ok(psbt)
+  }
+
+  pub(crate) fn get_change_pubkey(
     client: &Client,
     chain: Chain,
     change: Option<Address<NetworkUnchecked>>,
@@ -309,37 +576,40 @@ impl SendMany {
     }.script_pubkey())
   }
 
-  fn get_cardinals(
-    unspent_outputs: BTreeMap<OutPoint, Amount>,
-    locked_outputs: BTreeSet<OutPoint>,
-    inscriptions: BTreeMap<InscriptionId, SatPoint>,
-  ) -> Vec<(OutPoint, u64)> {
-    let inscribed_utxos =
-      inscriptions				// get a tree <InscriptionId, SatPoint> of the inscriptions we own
-      .values()					// just the SatPoints
-      .map(|satpoint| satpoint.outpoint)	// just the OutPoints of those SatPoints
-      .collect::<BTreeSet<OutPoint>>();		// as a set of OutPoints
-
-    let mut cardinal_utxos = unspent_outputs
-      .iter()
-      .filter_map(|(output, amount)| {
-        if inscribed_utxos.contains(output) || locked_outputs.contains(output) {
-          None
-        } else {
-          Some((
-            *output,
-            amount.to_sat(),
-          ))
-        }
-      })
-      .collect::<Vec<(OutPoint, u64)>>();
+  // every cardinal spent adds to the fee, so `needed` is asked what the cardinals have to cover given
+  // how many of them are spent, and they're picked again until that stops growing
+  pub(crate) fn select_cardinals(
+    unspent_outputs: &BTreeMap<OutPoint, Amount>,
+    locked_outputs: &BTreeSet<OutPoint>,
+    inscribed_outputs: &BTreeSet<OutPoint>,
+    runic_outputs: &BTreeSet<OutPoint>,
+    needed: impl Fn(usize) -> Result<u64>,
+  ) -> Result<Vec<(OutPoint, u64)>> {
+    let mut count = 1;
+
+    loop {
+      let selected = select_cardinal_utxos(
+        unspent_outputs,
+        locked_outputs,
+        inscribed_outputs,
+        runic_outputs,
+        Amount::from_sat(needed(count)?),
+      )?;
+
+      if selected.len() <= count {
+        return Ok(
+          selected
+            .into_iter()
+            .map(|(outpoint, amount)| (outpoint, amount.to_sat()))
+            .collect(),
+        );
+      }
 
-    cardinal_utxos.sort_by_key(|x| x.1);
-    cardinal_utxos.reverse();
-    cardinal_utxos
+      count = selected.len();
+    }
   }
 
-  fn build_transaction(
+  pub(crate) fn build_transaction(
     inputs: &Vec<OutPoint>,
     outputs: &Vec<TxOut>,
   ) -> Transaction {
@@ -359,7 +629,7 @@ impl SendMany {
     }
   }
 
-  fn build_fake_transaction(
+  pub(crate) fn build_fake_transaction(
     inputs: &Vec<OutPoint>,
     outputs: &Vec<TxOut>,
   ) -> Transaction {
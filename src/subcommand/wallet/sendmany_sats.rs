@@ -0,0 +1,141 @@
+use {
+  super::*,
+  bitcoin::policy::MAX_STANDARD_TX_WEIGHT,
+  bitcoincore_rpc::RawTx,
+  sendmany::{Output, SendMany},
+};
+
+#[derive(Debug, Parser, Clone)]
+pub(crate) struct SendManySats {
+  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[arg(long, help = "Location of a CSV file containing `destination`,`amount` pairs, with amounts in sats.")]
+  pub(crate) csv: PathBuf,
+  #[arg(long, help = "Broadcast the transaction; the default is to output the raw tranasction hex so you can check it before broadcasting.")]
+  pub(crate) broadcast: bool,
+  #[arg(long, help = "Do not check that the transaction is equal to or below the MAX_STANDARD_TX_WEIGHT of 400,000 weight units. Transactions over this limit are currently nonstandard and will not be relayed by bitcoind in its default configuration. Do not use this flag unless you understand the implications."
+  )]
+  pub(crate) no_limit: bool,
+  #[arg(long, help = "The address to send the change output to.")]
+  pub(crate) change: Option<Address<NetworkUnchecked>>,
+}
+
+impl SendManySats {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let mut outputs = Vec::new();
+
+    let chain = options.chain();
+
+    for (line_number, fields) in SendMany::read_csv_records(&self.csv)? {
+      let mut line = fields.iter().map(String::as_str);
+
+      let destination = line.next().ok_or_else(|| {
+        anyhow!("CSV file '{}' is not formatted correctly - no destination on line {line_number}", self.csv.display())
+      })?;
+
+      let destination = match match Address::from_str(destination) {
+        Err(e) => bail!("bad address on line {line_number}: {}", e),
+        Ok(ok) => ok,
+      }.require_network(chain.network()) {
+        Err(e) => bail!("bad network for address on line {line_number}: {}", e),
+        Ok(ok) => ok,
+      };
+
+      let amount = line.next().ok_or_else(|| {
+        anyhow!("CSV file '{}' is not formatted correctly - no comma on line {line_number}", self.csv.display())
+      })?;
+
+      let amount = match amount.parse::<u64>() {
+        Err(e) => bail!("bad amount on line {line_number}: {}", e),
+        Ok(ok) => ok,
+      };
+
+      let script_pubkey = destination.script_pubkey();
+      let dust_limit = script_pubkey.dust_value().to_sat();
+
+      if amount < dust_limit {
+        bail!("amount {} sats on line {line_number} is less than dust limit {} for address {}", amount, dust_limit, destination);
+      }
+
+      outputs.push(TxOut{script_pubkey, value: amount});
+    }
+
+    if outputs.is_empty() {
+      bail!("CSV file '{}' doesn't list any destinations", self.csv.display());
+    }
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+    let unspent_outputs = get_unspent_outputs(&client, &index)?;
+    let locked_outputs = get_locked_outputs(&client)?;
+    let runic_outputs = index.get_runic_outputs(&unspent_outputs.keys().cloned().collect::<Vec<OutPoint>>())?;
+
+    let inscribed_outputs = index
+      .get_inscriptions_vector(&unspent_outputs)?
+      .into_iter()
+      .map(|(satpoint, _inscriptionid)| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let total_sent = outputs.iter().map(|output| output.value).sum::<u64>();
+
+    let script_pubkey = SendMany::get_change_pubkey(&client, chain, self.change.clone())?;
+    let change_dust_limit = script_pubkey.dust_value().to_sat();
+    outputs.push(TxOut{script_pubkey: script_pubkey.clone(), value: 0}); // the change value is filled in once we know the fee
+
+    let fake_tx = |count| {
+      SendMany::build_fake_transaction(&vec![OutPoint::null(); count], &outputs)
+    };
+
+    // enough cardinals to cover the payments, the fee, and a change output, never touching anything
+    // inscribed, locked, or runic
+    let cardinals = SendMany::select_cardinals(
+      &unspent_outputs,
+      &locked_outputs,
+      &inscribed_outputs,
+      &runic_outputs,
+      |count| Ok(total_sent + self.fee_rate.fee(fake_tx(count).vsize()).to_sat() + change_dust_limit),
+    )?;
+
+    let inputs = cardinals.iter().map(|(outpoint, _value)| *outpoint).collect::<Vec<OutPoint>>();
+    let input_value = cardinals.iter().map(|(_outpoint, value)| value).sum::<u64>();
+
+    let weight = fake_tx(inputs.len()).weight();
+    if !self.no_limit && weight > bitcoin::Weight::from_wu(MAX_STANDARD_TX_WEIGHT.into()) {
+      bail!(
+        "transaction weight greater than {MAX_STANDARD_TX_WEIGHT} (MAX_STANDARD_TX_WEIGHT): {weight}"
+      );
+    }
+    let fee = self.fee_rate.fee(fake_tx(inputs.len()).vsize()).to_sat();
+
+    let needed = total_sent + fee + change_dust_limit;
+    if input_value < needed {
+      bail!("wallet cardinals ({} sats) are too small\n       we need enough for payments {} plus fee {} plus dust limit {} = {} sats",
+            input_value, total_sent, fee, change_dust_limit, needed);
+    }
+
+    let change = input_value - total_sent - fee;
+
+    let last = outputs.len() - 1;
+    outputs[last] = TxOut{script_pubkey, value: change};
+
+    let tx = SendMany::build_transaction(&inputs, &outputs);
+
+    let signed_tx = sign_transaction(&client, &tx, None)?;
+
+    let tx = if self.broadcast {
+      client.send_raw_transaction(&signed_tx)?.to_string()
+    } else {
+      signed_tx.raw_hex()
+    };
+
+    Ok(Box::new(Output {
+      change,
+      fee,
+      input_count: inputs.len(),
+      locked: Vec::new(),
+      tx,
+    }))
+  }
+}
@@ -0,0 +1,248 @@
+use {
+  super::*,
+  base64::{Engine as _, engine::general_purpose},
+  bitcoin::{bip32::KeySource, key::TweakedPublicKey, psbt::Psbt, secp256k1::XOnlyPublicKey},
+  bitcoincore_rpc::RawTx,
+  inscribe::{recovery_key_fingerprint, RECOVERY_KEY_LABEL},
+  miniscript::descriptor::{DescriptorPublicKey, SinglePub, SinglePubKey},
+  sendmany::SendMany,
+};
+
+#[derive(Debug, Parser, Clone)]
+pub(crate) struct SweepCommits {
+  #[arg(long, help = "Send the swept commit outputs to <DESTINATION>.")]
+  destination: Address<NetworkUnchecked>,
+  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[arg(long, help = "Broadcast the sweep transaction; the default is to output the raw transaction hex so you can check it before broadcasting.")]
+  broadcast: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub commits: Vec<OutPoint>,
+  pub fee: u64,
+  pub psbt: Option<String>,
+  pub tx: Option<String>,
+  pub value: u64,
+}
+
+impl SweepCommits {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let destination = self.destination.require_network(options.chain().network())?;
+
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+    let commits = client
+      .list_unspent(None, None, None, None, None)?
+      .into_iter()
+      .filter(|entry| entry.label.as_deref() == Some(RECOVERY_KEY_LABEL))
+      .map(|entry| {
+        (
+          OutPoint {
+            txid: entry.txid,
+            vout: entry.vout,
+          },
+          entry.amount.to_sat(),
+        )
+      })
+      .collect::<Vec<(OutPoint, u64)>>();
+
+    if commits.is_empty() {
+      bail!("wallet has no unspent commit outputs to sweep");
+    }
+
+    let inputs = commits.iter().map(|(outpoint, _value)| *outpoint).collect::<Vec<OutPoint>>();
+    let value = commits.iter().map(|(_outpoint, value)| value).sum::<u64>();
+
+    let script_pubkey = destination.script_pubkey();
+    let dust_limit = script_pubkey.dust_value().to_sat();
+
+    // commit outputs are spent through the key path, so every input has a single schnorr signature
+    let fee = self
+      .fee_rate
+      .fee(SendMany::build_fake_transaction(&inputs, &vec![TxOut { script_pubkey: script_pubkey.clone(), value: 0 }]).vsize())
+      .to_sat();
+
+    if value < fee + dust_limit {
+      bail!(
+        "commit outputs ({value} sats) are too small\n       we need enough for fee {fee} plus dust limit {dust_limit} = {} sats",
+        fee + dust_limit
+      );
+    }
+
+    let tx = SendMany::build_transaction(&inputs, &vec![TxOut { script_pubkey, value: value - fee }]);
+
+    let signed_tx = client.sign_raw_transaction_with_wallet(&tx, None, None)?;
+
+    // if the wallet can't sign for the commits, e.g. because it's watch-only, hand over a PSBT instead
+    let (psbt, tx) = if !signed_tx.complete {
+      let mut psbt = SendMany::witness_utxo_psbt(&client, tx)?;
+
+      let descriptors = client
+        .list_descriptors(None)?
+        .descriptors
+        .into_iter()
+        .map(|descriptor| descriptor.desc)
+        .collect::<Vec<String>>();
+
+      Self::add_recovery_keys(&mut psbt, &descriptors)?;
+
+      (Some(general_purpose::STANDARD.encode(psbt.serialize())), None)
+    } else if self.broadcast {
+      (None, Some(client.send_raw_transaction(&signed_tx.hex)?.to_string()))
+    } else {
+      (None, Some(signed_tx.hex.raw_hex()))
+    };
+
+    Ok(Box::new(Output {
+      commits: inputs,
+      fee,
+      psbt,
+      tx,
+      value: value - fee,
+    }))
+  }
+
+  // an external signer needs to know which key a commit output pays to, and where it came from, to sign for it.
+  // the recovery key is the output key itself, so it's matched against the wallet's public
+  // `rawtr(<key>)` and `rawtr([<fingerprint>/<path>]<key>)` descriptors
+  fn add_recovery_keys(psbt: &mut Psbt, descriptors: &[String]) -> Result {
+    let mut recovery_keys = BTreeMap::<ScriptBuf, (XOnlyPublicKey, KeySource)>::new();
+
+    for descriptor in descriptors {
+      let Some(key) = descriptor
+        .strip_prefix("rawtr(")
+        .and_then(|rest| rest.split(')').next())
+      else {
+        continue;
+      };
+
+      let DescriptorPublicKey::Single(SinglePub {
+        key: SinglePubKey::XOnly(public_key),
+        origin,
+      }) = DescriptorPublicKey::from_str(key)?
+      else {
+        continue;
+      };
+
+      let origin = origin.unwrap_or_else(|| {
+        (
+          recovery_key_fingerprint(public_key),
+          DerivationPath::master(),
+        )
+      });
+
+      recovery_keys.insert(
+        ScriptBuf::new_v1_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(public_key)),
+        (public_key, origin),
+      );
+    }
+
+    for input in &mut psbt.inputs {
+      let Some((public_key, origin)) = input
+        .witness_utxo
+        .as_ref()
+        .and_then(|output| recovery_keys.get(&output.script_pubkey))
+      else {
+        continue;
+      };
+
+      input.tap_internal_key = Some(*public_key);
+      input
+        .tap_key_origins
+        .insert(*public_key, (Vec::new(), origin.clone()));
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::secp256k1::{KeyPair, Secp256k1},
+  };
+
+  fn public_key(byte: u8) -> XOnlyPublicKey {
+    KeyPair::from_seckey_slice(&Secp256k1::new(), &[byte; 32])
+      .unwrap()
+      .x_only_public_key()
+      .0
+  }
+
+  fn psbt_spending(script_pubkey: ScriptBuf) -> Psbt {
+    let mut psbt = Psbt::from_unsigned_tx(Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: Vec::new(),
+    })
+    .unwrap();
+
+    psbt.inputs[0].witness_utxo = Some(TxOut {
+      script_pubkey,
+      value: 10_000,
+    });
+
+    psbt
+  }
+
+  #[test]
+  fn recovery_key_and_origin_are_added_to_matching_inputs() {
+    let public_key = public_key(1);
+
+    let script_pubkey =
+      ScriptBuf::new_v1_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(public_key));
+
+    let mut psbt = psbt_spending(script_pubkey.clone());
+
+    SweepCommits::add_recovery_keys(
+      &mut psbt,
+      &[
+        "tr(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/0/*)".into(),
+        format!("rawtr([deadbeef/86'/0'/0']{public_key})#00000000"),
+      ],
+    )
+    .unwrap();
+
+    assert_eq!(psbt.inputs[0].tap_internal_key, Some(public_key));
+    assert_eq!(
+      psbt.inputs[0].tap_key_origins[&public_key],
+      (
+        Vec::new(),
+        ("deadbeef".parse().unwrap(), "m/86'/0'/0'".parse().unwrap())
+      ),
+    );
+
+    let mut psbt = psbt_spending(script_pubkey);
+
+    SweepCommits::add_recovery_keys(&mut psbt, &[format!("rawtr({public_key})")]).unwrap();
+
+    assert_eq!(
+      psbt.inputs[0].tap_key_origins[&public_key].1,
+      (
+        recovery_key_fingerprint(public_key),
+        DerivationPath::master()
+      ),
+    );
+  }
+
+  #[test]
+  fn inputs_paying_to_other_keys_are_left_alone() {
+    let mut psbt = psbt_spending(ScriptBuf::new_v1_p2tr_tweaked(
+      TweakedPublicKey::dangerous_assume_tweaked(public_key(2)),
+    ));
+
+    SweepCommits::add_recovery_keys(&mut psbt, &[format!("rawtr({})", public_key(1))]).unwrap();
+
+    assert_eq!(psbt.inputs[0].tap_internal_key, None);
+    assert!(psbt.inputs[0].tap_key_origins.is_empty());
+  }
+}
@@ -20,6 +20,10 @@
 //! `Target::Value(Amount)` ensures that the outgoing value is exactly the
 //! requested amount,
 //!
+//! `Target::ValueWithChange(Amount, Amount)` ensures that the outgoing value
+//! and a single change output are exactly the requested amounts, with anything
+//! left over going to fees.
+//!
 //! Internally, `TransactionBuilder` calls multiple methods that implement
 //! transformations responsible for individual concerns, such as ensuring that
 //! the transaction fee is paid, and that outgoing outputs aren't too large.
@@ -43,6 +47,7 @@ pub enum Error {
     output_value: Amount,
     dust_value: Amount,
   },
+  NoChangeAddress(Amount),
   NotEnoughCardinalUtxos,
   NotInWallet(SatPoint),
   OutOfRange(SatPoint, u64),
@@ -62,6 +67,7 @@ pub enum Target {
   ExactPostage(Amount),
   NoChange(Amount),
   ChangeIsFee(Amount),
+  ValueWithChange(Amount, Amount),
 }
 
 impl fmt::Display for Error {
@@ -71,6 +77,7 @@ impl fmt::Display for Error {
         output_value,
         dust_value,
       } => write!(f, "output value is below dust value: {output_value} < {dust_value}"),
+      Error::NoChangeAddress(change) => write!(f, "transaction would have {change} of change, but there is no change address to send it to"),
       Error::NotInWallet(outgoing_satpoint) => write!(f, "outgoing satpoint {outgoing_satpoint} not in wallet"),
       Error::OutOfRange(outgoing_satpoint, maximum) => write!(f, "outgoing satpoint {outgoing_satpoint} offset higher than maximum {maximum}"),
       Error::OutputNotInWallet(outpoint) => write!(f, "outpoint {outpoint} not in wallet"),
@@ -181,6 +188,25 @@ impl TransactionBuilder {
           });
         }
       }
+      Target::ValueWithChange(output_value, change_value) => {
+        let dust_value = self.recipient.script_pubkey().dust_value();
+
+        if output_value < dust_value {
+          return Err(Error::Dust {
+            output_value,
+            dust_value,
+          });
+        }
+
+        let dust_value = self.unused_change_addresses[1].script_pubkey().dust_value();
+
+        if change_value < dust_value {
+          return Err(Error::Dust {
+            output_value: change_value,
+            dust_value,
+          });
+        }
+      }
       _ => (),
     }
 
@@ -189,7 +215,7 @@ impl TransactionBuilder {
       .align_outgoing()
       .pad_alignment_output()?
       .add_value()?
-      .strip_value()
+      .strip_value()?
       .deduct_fee()
       .build()
   }
@@ -322,6 +348,10 @@ impl TransactionBuilder {
     let min_value = match self.target {
       Target::Postage => self.outputs.last().unwrap().0.script_pubkey().dust_value(),
       Target::Value(value) | Target::ExactPostage(value) | Target::NoChange(value) | Target::ChangeIsFee(value) => value,
+      Target::ValueWithChange(value, change) => value
+        .checked_add(change)
+        .and_then(|value| value.checked_add(self.fee_rate.fee(Self::ADDITIONAL_OUTPUT_VBYTES)))
+        .ok_or(Error::ValueOverflow)?,
     };
 
     let total = min_value
@@ -359,11 +389,18 @@ impl TransactionBuilder {
     Ok(self)
   }
 
-  fn strip_value(mut self) -> Self {
+  fn strip_value(mut self) -> Result<Self> {
     if let Target::ChangeIsFee(value) = self.target {
       // for ChangeIsFee, set the output value to be the target value, and don't assign the extra sats to any output, so they end up as fee
       self.outputs.last_mut().expect("no outputs found").1 = value;
-      return self;
+      return Ok(self);
+    }
+
+    if let Target::ValueWithChange(value, change) = self.target {
+      // set both outputs to exactly their targets, leaving everything else as fee
+      self.outputs.last_mut().expect("no outputs found").1 = value;
+      self.outputs.push((self.unused_change_addresses[1].clone(), change));
+      return Ok(self);
     }
 
     let sat_offset = self.calculate_sat_offset();
@@ -388,34 +425,42 @@ impl TransactionBuilder {
         Target::Postage => (Self::MAX_POSTAGE, TARGET_POSTAGE),
         Target::Value(value) => (value, value),
         Target::NoChange(_) => (excess, excess),
-        Target::ChangeIsFee(value) => (value, value),
+        Target::ChangeIsFee(value) | Target::ValueWithChange(value, _) => (value, value),
       };
 
+      // a --no-wallet builder may have no change addresses, so size the dust limit off the recipient
+      let change_address = self.unused_change_addresses.last().cloned();
+
       if excess > max
         && value.checked_sub(target).unwrap()
-          > self
-            .unused_change_addresses
-            .last()
-            .unwrap()
+          > change_address
+            .as_ref()
+            .unwrap_or(&self.recipient)
             .script_pubkey()
             .dust_value()
             + self
               .fee_rate
               .fee(self.estimate_vbytes() + Self::ADDITIONAL_OUTPUT_VBYTES)
       {
+        let Some(change_address) = change_address else {
+          return Err(Error::NoChangeAddress(value - target));
+        };
+
         tprintln!("stripped {} sats", (value - target).to_sat());
         self.outputs.last_mut().expect("no outputs found").1 = target;
-        self.outputs.push((
-          self.unused_change_addresses[1].clone(),
-          value - target,
-        ));
+        self.outputs.push((change_address, value - target));
       }
     }
 
-    self
+    Ok(self)
   }
 
   fn deduct_fee(mut self) -> Self {
+    if let Target::ValueWithChange(..) = self.target {
+      // the fee is whatever strip_value left over
+      return self;
+    }
+
     let sat_offset = self.calculate_sat_offset();
 
     let fee = self.estimate_fee();
@@ -634,6 +679,13 @@ impl TransactionBuilder {
               "invariant: output is at least the target amount"
             );
           }
+          Target::ValueWithChange(value, _) => {
+            assert_eq!(
+              Amount::from_sat(output.value),
+              value,
+              "invariant: output equals target value",
+            );
+          }
         }
         assert_eq!(
           offset, first_sat_offset,
@@ -920,6 +972,7 @@ mod tests {
     .unwrap()
     .align_outgoing()
     .strip_value()
+    .unwrap()
     .deduct_fee();
   }
 
@@ -1269,6 +1322,7 @@ mod tests {
     .add_value()
     .unwrap()
     .strip_value()
+    .unwrap()
     .deduct_fee();
 
     builder.change_addresses = BTreeSet::new();
@@ -1298,6 +1352,7 @@ mod tests {
     .add_value()
     .unwrap()
     .strip_value()
+    .unwrap()
     .deduct_fee()
     .build()
     .unwrap();
@@ -1322,6 +1377,7 @@ mod tests {
     .select_outgoing()
     .unwrap()
     .strip_value()
+    .unwrap()
     .deduct_fee()
     .build()
     .unwrap();
@@ -1346,6 +1402,7 @@ mod tests {
     .select_outgoing()
     .unwrap()
     .strip_value()
+    .unwrap()
     .build()
     .unwrap();
   }
@@ -1545,6 +1602,83 @@ mod tests {
     )
   }
 
+  #[test]
+  fn value_with_change_sends_exact_change_and_leaves_remainder_as_fee() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        vec![satpoint(1, 0)],
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        Some([change(0), change(1)]),
+        FeeRate::try_from(1.0).unwrap(),
+        Target::ValueWithChange(Amount::from_sat(1000), Amount::from_sat(2000)),
+        Vec::new(),
+        false,
+      )
+      .build_transaction(),
+      Ok(Transaction {
+        version: 2,
+        lock_time: LockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(1000, recipient()), tx_out(2000, change(1))],
+      })
+    )
+  }
+
+  #[test]
+  fn excess_value_without_change_address_is_an_error() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        vec![satpoint(1, 0)],
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        None,
+        FeeRate::try_from(1.0).unwrap(),
+        Target::Value(Amount::from_sat(1000)),
+        Vec::new(),
+        true,
+      )
+      .build_transaction(),
+      Err(Error::NoChangeAddress(Amount::from_sat(4000)))
+    )
+  }
+
+  #[test]
+  fn value_with_change_refuses_dust_change() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::new(
+        vec![satpoint(1, 0)],
+        BTreeMap::new(),
+        utxos.into_iter().collect(),
+        BTreeSet::new(),
+        BTreeSet::new(),
+        recipient(),
+        Some([change(0), change(1)]),
+        FeeRate::try_from(1.0).unwrap(),
+        Target::ValueWithChange(Amount::from_sat(1000), Amount::from_sat(1)),
+        Vec::new(),
+        false,
+      )
+      .build_transaction(),
+      Err(Error::Dust {
+        output_value: Amount::from_sat(1),
+        dust_value: change(1).script_pubkey().dust_value(),
+      })
+    )
+  }
+
   #[test]
   fn exact_transaction_adds_output_to_cover_value() {
     let utxos = vec![
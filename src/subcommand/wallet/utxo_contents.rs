@@ -0,0 +1,69 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct UtxoContents {
+  #[arg(help = "Show what's in <OUTPOINT>.")]
+  outpoint: OutPoint,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct InscribedSat {
+  pub id: InscriptionId,
+  pub offset: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub outpoint: OutPoint,
+  pub value: u64,
+  pub inscriptions: Vec<InscribedSat>,
+  pub runes: Option<BTreeMap<Rune, u128>>,
+  pub is_cardinal: bool,
+}
+
+impl UtxoContents {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = bitcoin_rpc_client_for_wallet_command(wallet, &options)?;
+
+    let value = client
+      .get_raw_transaction(&self.outpoint.txid, None)?
+      .output
+      .get(usize::try_from(self.outpoint.vout).unwrap())
+      .ok_or_else(|| anyhow!("output {} doesn't exist", self.outpoint))?
+      .value;
+
+    let inscriptions = index
+      .get_inscriptions_on_output_with_satpoints(self.outpoint)?
+      .into_iter()
+      .map(|(satpoint, id)| InscribedSat {
+        id,
+        offset: satpoint.offset,
+      })
+      .collect::<Vec<InscribedSat>>();
+
+    // without a rune index we can't tell whether the output holds runes, so it's never reported as cardinal
+    let runes = if index.has_rune_index() {
+      let mut runes = BTreeMap::new();
+      for (spaced_rune, pile) in index.get_rune_balances_for_outpoint(self.outpoint)? {
+        *runes.entry(spaced_rune.rune).or_default() += pile.amount;
+      }
+      Some(runes)
+    } else {
+      None
+    };
+
+    let is_cardinal =
+      inscriptions.is_empty() && runes.as_ref().map(BTreeMap::is_empty).unwrap_or_default();
+
+    Ok(Box::new(Output {
+      outpoint: self.outpoint,
+      value,
+      inscriptions,
+      runes,
+      is_cardinal,
+    }))
+  }
+}
@@ -0,0 +1,86 @@
+use {
+  super::*,
+  bitcoin::bip32::ExtendedPubKey,
+  miniscript::descriptor::DescriptorPublicKey,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Watch {
+  #[arg(
+    long,
+    required_unless_present = "descriptor",
+    conflicts_with_all = &["descriptor", "change_descriptor"],
+    help = "Watch the taproot addresses of account extended public key <XPUB>, e.g. the xpub at m/86'/0'/0'."
+  )]
+  xpub: Option<ExtendedPubKey>,
+  #[arg(
+    long,
+    requires = "change_descriptor",
+    help = "Watch receive addresses of output descriptor <DESCRIPTOR>."
+  )]
+  descriptor: Option<Descriptor<DescriptorPublicKey>>,
+  #[arg(
+    long,
+    requires = "descriptor",
+    help = "Watch change addresses of output descriptor <CHANGE_DESCRIPTOR>."
+  )]
+  change_descriptor: Option<Descriptor<DescriptorPublicKey>>,
+}
+
+impl Watch {
+  pub(crate) fn run(self, wallet: String, options: Options) -> SubcommandResult {
+    let network = options.chain().network();
+
+    let descriptors = match self.xpub {
+      Some(xpub) => {
+        if (xpub.network == Network::Bitcoin) != (network == Network::Bitcoin) {
+          bail!("xpub is for {}, but ord is running on {network}", xpub.network);
+        }
+
+        [false, true]
+          .into_iter()
+          .map(|change| {
+            Descriptor::new_tr(
+              DescriptorPublicKey::XPub(DescriptorXKey {
+                origin: None,
+                xkey: xpub,
+                derivation_path: DerivationPath::master().child(ChildNumber::Normal {
+                  index: change.into(),
+                }),
+                wildcard: Wildcard::Unhardened,
+              }),
+              None,
+            )
+          })
+          .collect::<Result<Vec<Descriptor<DescriptorPublicKey>>, miniscript::Error>>()?
+      }
+      None => vec![self.descriptor.unwrap(), self.change_descriptor.unwrap()],
+    };
+
+    // no private keys means no seed, so unlike `wallet create` there's no mnemonic to hand back
+    check_version(options.bitcoin_rpc_client(None)?)?.create_wallet(
+      &wallet,
+      Some(true),
+      Some(true),
+      None,
+      None,
+    )?;
+
+    let client = options.bitcoin_rpc_client(Some(wallet))?;
+
+    // the keys are usually already in use, so rescan from the start of the chain to find their history
+    for (descriptor, change) in descriptors.into_iter().zip([false, true]) {
+      client.import_descriptors(ImportDescriptors {
+        descriptor: descriptor.to_string(),
+        timestamp: Timestamp::Time(0),
+        active: Some(true),
+        range: None,
+        next_index: None,
+        internal: Some(change),
+        label: None,
+      })?;
+    }
+
+    Ok(Box::new(Empty {}))
+  }
+}
@@ -26,6 +26,7 @@ pub(crate) use {
   server_config::ServerConfig,
   status::StatusHtml,
   transaction::TransactionHtml,
+  transfers::{BlockTransfersJson, TransferJson, TransfersHtml, TransfersJson},
 };
 
 pub mod block;
@@ -49,6 +50,7 @@ pub mod runes;
 pub mod sat;
 pub mod status;
 mod transaction;
+pub mod transfers;
 
 #[derive(Boilerplate)]
 pub(crate) struct PageHtml<T: PageContent> {
@@ -0,0 +1,211 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct TransfersHtml {
+  pub(crate) height: u32,
+  pub(crate) address: Option<String>,
+  pub(crate) transfers: Vec<TransferJson>,
+  pub(crate) prev_page: Option<u32>,
+  pub(crate) next_page: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransfersJson {
+  pub height: u32,
+  pub transfers: Vec<TransferJson>,
+  pub total: usize,
+  pub more: bool,
+  pub page_index: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockTransfersJson {
+  pub height: u32,
+  pub transfers: Vec<TransferJson>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransferJson {
+  pub inscription_id: InscriptionId,
+  pub address: String,
+  pub satpoint: SatPoint,
+}
+
+impl TransfersJson {
+  /// One `inscription_id,address,satpoint` row per transfer, after a header row. `address` and `satpoint` are
+  /// where the inscription is now. The index doesn't record the outputs transfers spent, so there are no
+  /// `from_address` or `from_satpoint` columns.
+  pub fn to_csv(&self) -> String {
+    let mut csv = String::from("inscription_id,address,satpoint\n");
+
+    for transfer in &self.transfers {
+      csv.push_str(&format!(
+        "{},{},{}\n",
+        transfer.inscription_id,
+        csv_field(&transfer.address),
+        transfer.satpoint,
+      ));
+    }
+
+    csv
+  }
+}
+
+/// Quote `field` if it contains anything that would break a row apart.
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\r', '\n']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.into()
+  }
+}
+
+impl TransfersHtml {
+  pub(crate) fn new(
+    height: u32,
+    address: Option<String>,
+    transfers: Vec<TransferJson>,
+    more: bool,
+    page_index: u32,
+  ) -> Self {
+    Self {
+      height,
+      address,
+      transfers,
+      prev_page: page_index.checked_sub(1),
+      next_page: more.then_some(page_index + 1),
+    }
+  }
+
+  fn page_query(&self) -> String {
+    self
+      .address
+      .as_ref()
+      .map(|address| format!("?address={address}"))
+      .unwrap_or_default()
+  }
+}
+
+impl PageContent for TransfersHtml {
+  fn title(&self) -> String {
+    match &self.address {
+      Some(address) => format!("Transfers in Block {} for {address}", self.height),
+      None => format!("Transfers in Block {}", self.height),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_prev_and_next() {
+    assert_regex_match!(
+      TransfersHtml::new(
+        21,
+        None,
+        vec![TransferJson {
+          inscription_id: inscription_id(1),
+          address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+          satpoint: satpoint(1, 0),
+        }],
+        false,
+        0,
+      ),
+      "
+        <h1>Transfers in <a href=/block/21>Block 21</a></h1>
+        <ul>
+          <li><a href=/inscription/1{64}i1>1{64}i1</a> bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</li>
+        </ul>
+        <div class=center>
+        prev
+        next
+        </div>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      TransfersHtml::new(21, None, Vec::new(), true, 2),
+      "
+        <h1>Transfers in <a href=/block/21>Block 21</a></h1>
+        <ul>
+        </ul>
+        <div class=center>
+          <a class=prev href=/transfers/block/21/1>prev</a>
+          <a class=next href=/transfers/block/21/3>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_address_filter() {
+    let html = TransfersHtml::new(
+      21,
+      Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into()),
+      Vec::new(),
+      true,
+      1,
+    );
+
+    assert_eq!(
+      html.title(),
+      "Transfers in Block 21 for bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    );
+
+    assert_regex_match!(
+      html,
+      "
+        <h1>Transfers in <a href=/block/21>Block 21</a> for bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</h1>
+        <p>Showing inscriptions moved in this block that bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 holds now. Senders aren't indexed, so transfers away from bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 aren't shown.</p>
+        <ul>
+        </ul>
+        <div class=center>
+          <a class=prev href=/transfers/block/21/0\\?address=bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>prev</a>
+          <a class=next href=/transfers/block/21/2\\?address=bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn csv() {
+    assert_eq!(
+      TransfersJson {
+        height: 21,
+        transfers: vec![
+          TransferJson {
+            inscription_id: inscription_id(1),
+            address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+            satpoint: satpoint(1, 0),
+          },
+          TransferJson {
+            inscription_id: inscription_id(2),
+            address: "odd,\"address\"".into(),
+            satpoint: satpoint(2, 7),
+          },
+        ],
+        total: 2,
+        more: false,
+        page_index: 0,
+      }
+      .to_csv(),
+      format!(
+        "inscription_id,address,satpoint\n\
+        {},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,{}\n\
+        {},\"odd,\"\"address\"\"\",{}\n",
+        inscription_id(1),
+        satpoint(1, 0),
+        inscription_id(2),
+        satpoint(2, 7),
+      )
+    );
+  }
+}
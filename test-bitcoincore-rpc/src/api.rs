@@ -63,12 +63,15 @@ pub trait Api {
     &self,
     tx: String,
     utxos: Option<Vec<SignRawTransactionInput>>,
-    sighash_type: Option<()>,
+    sighash_type: Option<String>,
   ) -> Result<Value, jsonrpc_core::Error>;
 
   #[rpc(name = "sendrawtransaction")]
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error>;
 
+  #[rpc(name = "testmempoolaccept")]
+  fn test_mempool_accept(&self, rawtxs: Vec<String>) -> Result<Value, jsonrpc_core::Error>;
+
   #[rpc(name = "sendtoaddress")]
   fn send_to_address(
     &self,
@@ -157,6 +160,13 @@ pub trait Api {
   #[rpc(name = "listdescriptors")]
   fn list_descriptors(&self) -> Result<ListDescriptorsResult, jsonrpc_core::Error>;
 
+  #[rpc(name = "getreceivedbyaddress")]
+  fn get_received_by_address(
+    &self,
+    address: Address<NetworkUnchecked>,
+    minconf: Option<u32>,
+  ) -> Result<f64, jsonrpc_core::Error>;
+
   #[rpc(name = "loadwallet")]
   fn load_wallet(&self, wallet: String) -> Result<LoadWalletResult, jsonrpc_core::Error>;
 
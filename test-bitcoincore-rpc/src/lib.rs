@@ -19,7 +19,8 @@ use {
   bitcoincore_rpc::json::{
     Bip125Replaceable, CreateRawTransactionInput, Descriptor, EstimateMode, GetBalancesResult,
     GetBalancesResultEntry, GetBlockHeaderResult, GetBlockchainInfoResult, GetDescriptorInfoResult,
-    GetNetworkInfoResult, GetRawTransactionResult, GetTransactionResult,
+    GetNetworkInfoResult, GetRawTransactionResult, GetRawTransactionResultVout,
+    GetRawTransactionResultVoutScriptPubKey, GetTransactionResult,
     GetTransactionResultDetail, GetTransactionResultDetailCategory, GetWalletInfoResult,
     ImportDescriptors, ImportMultiResult, ListDescriptorsResult, ListTransactionResult,
     ListUnspentResultEntry, LoadWalletResult, SignRawTransactionInput, SignRawTransactionResult,
@@ -276,6 +277,10 @@ impl Handle {
     }
   }
 
+  pub fn test_mempool_accept_calls(&self) -> Vec<usize> {
+    self.state().test_mempool_accept_calls.clone()
+  }
+
   pub fn loaded_wallets(&self) -> BTreeSet<String> {
     self.state().loaded_wallets.clone()
   }
@@ -1,8 +1,10 @@
 use {
   super::*,
   bitcoin::{
+    bip32::{ChildNumber, ExtendedPrivKey, ExtendedPubKey},
     consensus::Decodable,
     secp256k1::{rand, KeyPair, Secp256k1, XOnlyPublicKey},
+    sighash::EcdsaSighashType,
     Witness,
   },
   std::io::Cursor,
@@ -19,10 +21,63 @@ impl Server {
     Self { network, state }
   }
 
+  // the extended key of a `tr([origin]key/path/*)` descriptor as it's written, in public form, and
+  // the unhardened path after it
+  fn tr_descriptor_key(descriptor: &str) -> Option<(&str, ExtendedPubKey, Vec<ChildNumber>)> {
+    let inner = descriptor.strip_prefix("tr(")?.split(')').next()?;
+    let inner = inner.split(']').last()?;
+    let mut parts = inner.split('/');
+    let key = parts.next()?;
+
+    let xpub = match key.parse::<ExtendedPrivKey>() {
+      Ok(xprv) => ExtendedPubKey::from_priv(&Secp256k1::new(), &xprv),
+      Err(_) => key.parse::<ExtendedPubKey>().ok()?,
+    };
+
+    let path = parts
+      .filter(|part| *part != "*")
+      .map(|part| ChildNumber::from_normal_idx(part.parse().ok()?).ok())
+      .collect::<Option<Vec<ChildNumber>>>()?;
+
+    Some((key, xpub, path))
+  }
+
+  // like bitcoind, list descriptors without their private keys; the checksum would no longer match,
+  // so it's dropped
+  fn public_descriptor(descriptor: &str) -> String {
+    match Self::tr_descriptor_key(descriptor) {
+      Some((key, xpub, _path)) if key != xpub.to_string() => descriptor
+        .split('#')
+        .next()
+        .unwrap()
+        .replace(key, &xpub.to_string()),
+      _ => descriptor.into(),
+    }
+  }
+
   fn state(&self) -> MutexGuard<State> {
     self.state.lock().unwrap()
   }
 
+  // the key of a `rawtr(<wif>)` descriptor, with or without a key origin
+  fn rawtr_public_key(descriptor: &str) -> Option<XOnlyPublicKey> {
+    let key = descriptor
+      .strip_prefix("rawtr(")?
+      .split(')')
+      .next()?
+      .rsplit(']')
+      .next()?;
+
+    let private_key = bitcoin::PrivateKey::from_wif(key).ok()?;
+
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&KeyPair::from_secret_key(
+      &Secp256k1::new(),
+      &private_key.inner,
+    ));
+
+    Some(public_key)
+  }
+
   fn not_found() -> jsonrpc_core::Error {
     jsonrpc_core::Error::new(jsonrpc_core::types::error::ErrorCode::ServerError(-8))
   }
@@ -167,7 +222,10 @@ impl Api for Server {
   }
 
   fn get_wallet_info(&self) -> Result<GetWalletInfoResult, jsonrpc_core::Error> {
-    if let Some(wallet_name) = self.state().loaded_wallets.first().cloned() {
+    let wallet_name = self.state().loaded_wallets.first().cloned();
+
+    if let Some(wallet_name) = wallet_name {
+      let private_keys_enabled = !self.state().watch_only_wallets.contains(&wallet_name);
       Ok(GetWalletInfoResult {
         avoid_reuse: None,
         balance: Amount::from_sat(0),
@@ -177,7 +235,7 @@ impl Api for Server {
         keypool_size: 0,
         keypool_size_hd_internal: 0,
         pay_tx_fee: Amount::from_sat(0),
-        private_keys_enabled: false,
+        private_keys_enabled,
         scanning: None,
         tx_count: 0,
         unconfirmed_balance: Amount::from_sat(0),
@@ -227,12 +285,15 @@ impl Api for Server {
   fn create_wallet(
     &self,
     name: String,
-    _disable_private_keys: Option<bool>,
+    disable_private_keys: Option<bool>,
     _blank: Option<bool>,
     _passphrase: Option<String>,
     _avoid_reuse: Option<bool>,
   ) -> Result<LoadWalletResult, jsonrpc_core::Error> {
     self.state().wallets.insert(name.clone());
+    if disable_private_keys == Some(true) {
+      self.state().watch_only_wallets.insert(name.clone());
+    }
     Ok(LoadWalletResult {
       name,
       warning: None,
@@ -337,14 +398,46 @@ impl Api for Server {
     &self,
     tx: String,
     _utxos: Option<Vec<SignRawTransactionInput>>,
-    sighash_type: Option<()>,
+    sighash_type: Option<String>,
   ) -> Result<Value, jsonrpc_core::Error> {
-    assert_eq!(sighash_type, None, "sighash_type param not supported");
+    // a schnorr signature is 64 bytes, plus a sighash byte unless it's signed with the default type
+    let signature = match sighash_type {
+      None => vec![0; 64],
+      Some(sighash_type) => {
+        let sighash_type = format!("SIGHASH_{}", sighash_type.replace('|', "|SIGHASH_"))
+          .parse::<EcdsaSighashType>()
+          .unwrap();
+        let mut signature = vec![0; 64];
+        signature.push(sighash_type.to_u32().try_into().unwrap());
+        signature
+      }
+    };
 
     let mut transaction: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
+
+    let watch_only = {
+      let state = self.state();
+      state
+        .loaded_wallets
+        .first()
+        .map(|wallet| state.watch_only_wallets.contains(wallet))
+        .unwrap_or_default()
+    };
+
+    if watch_only {
+      return Ok(
+        serde_json::to_value(SignRawTransactionResult {
+          hex: serialize(&transaction),
+          complete: false,
+          errors: None,
+        })
+        .unwrap(),
+      );
+    }
+
     for input in &mut transaction.input {
       if input.witness.is_empty() {
-        input.witness = Witness::from_slice(&[&[0; 64]]);
+        input.witness = Witness::from_slice(&[&signature]);
       }
     }
 
@@ -360,11 +453,30 @@ impl Api for Server {
 
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error> {
     let tx: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
-    self.state.lock().unwrap().mempool.push(tx.clone());
+    self.state.lock().unwrap().replace(tx.clone());
 
     Ok(tx.txid().to_string())
   }
 
+  fn test_mempool_accept(&self, rawtxs: Vec<String>) -> Result<Value, jsonrpc_core::Error> {
+    let mut state = self.state();
+
+    state.test_mempool_accept_calls.push(rawtxs.len());
+
+    Ok(Value::Array(
+      rawtxs
+        .into_iter()
+        .map(|tx| {
+          let tx: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
+          serde_json::json!({
+            "txid": tx.txid(),
+            "allowed": true,
+          })
+        })
+        .collect(),
+    ))
+  }
+
   fn send_to_address(
     &self,
     address: Address<NetworkUnchecked>,
@@ -483,30 +595,58 @@ impl Api for Server {
   ) -> Result<Value, jsonrpc_core::Error> {
     assert_eq!(blockhash, None, "Blockhash param is unsupported");
     if verbose.unwrap_or(false) {
-      match self.state().transactions.get(&txid) {
-        Some(_) => Ok(
-          serde_json::to_value(GetRawTransactionResult {
-            in_active_chain: Some(true),
-            hex: Vec::new(),
-            txid: Txid::all_zeros(),
-            hash: Wtxid::all_zeros(),
-            size: 0,
-            vsize: 0,
-            version: 2,
-            locktime: 0,
-            vin: Vec::new(),
-            vout: Vec::new(),
-            blockhash: None,
-            confirmations: Some(1),
-            time: None,
-            blocktime: None,
-          })
-          .unwrap(),
-        ),
-        None => Err(Self::not_found()),
-      }
+      let state = self.state();
+
+      let (tx, in_active_chain, confirmations) = match state.transactions.get(&txid) {
+        Some(tx) => (tx, Some(true), Some(1)),
+        None => match state.mempool().iter().find(|tx| tx.txid() == txid) {
+          Some(tx) => (tx, None, None),
+          None => return Err(Self::not_found()),
+        },
+      };
+
+      Ok(
+        serde_json::to_value(GetRawTransactionResult {
+          in_active_chain,
+          hex: Vec::new(),
+          txid,
+          hash: Wtxid::all_zeros(),
+          size: 0,
+          vsize: 0,
+          version: 2,
+          locktime: 0,
+          vin: Vec::new(),
+          vout: tx
+            .output
+            .iter()
+            .enumerate()
+            .map(|(n, output)| GetRawTransactionResultVout {
+              value: Amount::from_sat(output.value),
+              n: n.try_into().unwrap(),
+              script_pub_key: GetRawTransactionResultVoutScriptPubKey {
+                asm: String::new(),
+                hex: output.script_pubkey.to_bytes(),
+                req_sigs: None,
+                type_: None,
+                addresses: Vec::new(),
+                address: None,
+              },
+            })
+            .collect(),
+          blockhash: None,
+          confirmations,
+          time: None,
+          blocktime: None,
+        })
+        .unwrap(),
+      )
     } else {
-      match self.state().transactions.get(&txid) {
+      let state = self.state();
+      match state
+        .transactions
+        .get(&txid)
+        .or_else(|| state.mempool().iter().find(|tx| tx.txid() == txid))
+      {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
@@ -534,11 +674,20 @@ impl Api for Server {
         .utxos
         .iter()
         .filter(|(outpoint, _amount)| !state.locked.contains(outpoint))
+        .filter(|(outpoint, _amount)| !state.unscanned.contains(outpoint))
         .map(|(outpoint, &amount)| ListUnspentResultEntry {
           txid: outpoint.txid,
           vout: outpoint.vout,
           address: None,
-          label: None,
+          label: state
+            .transactions
+            .get(&outpoint.txid)
+            .and_then(|tx| {
+              state
+                .labels
+                .get(&tx.output[outpoint.vout as usize].script_pubkey)
+            })
+            .cloned(),
           redeem_script: None,
           witness_script: None,
           script_pub_key: ScriptBuf::new(),
@@ -569,10 +718,38 @@ impl Api for Server {
     _address_type: Option<bitcoincore_rpc::json::AddressType>,
   ) -> Result<Address, jsonrpc_core::Error> {
     let secp256k1 = Secp256k1::new();
-    let key_pair = KeyPair::new(&secp256k1, &mut rand::thread_rng());
-    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+    let mut state = self.state();
+
+    // like bitcoind, hand out the internal descriptor's addresses in order, if the wallet has one
+    let public_key = match state
+      .internal_descriptor
+      .as_deref()
+      .and_then(Self::tr_descriptor_key)
+    {
+      Some((_key, xpub, path)) => {
+        let index =
+          ChildNumber::from_normal_idx(state.change_addresses.len().try_into().unwrap()).unwrap();
+        xpub
+          .derive_pub(
+            &secp256k1,
+            &path
+              .into_iter()
+              .chain([index])
+              .collect::<Vec<ChildNumber>>(),
+          )
+          .unwrap()
+          .public_key
+          .x_only_public_key()
+          .0
+      }
+      None => {
+        let key_pair = KeyPair::new(&secp256k1, &mut rand::thread_rng());
+        XOnlyPublicKey::from_keypair(&key_pair).0
+      }
+    };
+
     let address = Address::p2tr(&secp256k1, public_key, None, self.network);
-    self.state().change_addresses.push(address.clone());
+    state.change_addresses.push(address.clone());
 
     Ok(address)
   }
@@ -581,8 +758,14 @@ impl Api for Server {
     &self,
     desc: String,
   ) -> Result<GetDescriptorInfoResult, jsonrpc_core::Error> {
+    // like bitcoind, give the public form of private key descriptors, which here are only ever `rawtr`
+    let descriptor = match Self::rawtr_public_key(&desc) {
+      Some(public_key) => format!("rawtr({public_key})"),
+      None => desc,
+    };
+
     Ok(GetDescriptorInfoResult {
-      descriptor: desc,
+      descriptor,
       checksum: "".into(),
       is_range: false,
       is_solvable: false,
@@ -594,10 +777,37 @@ impl Api for Server {
     &self,
     req: Vec<ImportDescriptors>,
   ) -> Result<Vec<ImportMultiResult>, jsonrpc_core::Error> {
-    self
-      .state()
-      .descriptors
-      .extend(req.into_iter().map(|params| params.descriptor));
+    let mut state = self.state();
+
+    for params in req {
+      // so that outputs paying to the descriptor are listed with the label it was imported with
+      if let (Some(label), Some(public_key)) =
+        (&params.label, Self::rawtr_public_key(&params.descriptor))
+      {
+        state.labels.insert(
+          ScriptBuf::new_v1_p2tr_tweaked(bitcoin::key::TweakedPublicKey::dangerous_assume_tweaked(
+            public_key,
+          )),
+          label.clone(),
+        );
+      }
+
+      if params.internal == Some(true) {
+        state.internal_descriptor = Some(params.descriptor.clone());
+      }
+
+      // like bitcoind, a descriptor imported without a rescan doesn't see outputs that already exist; only
+      // watched public descriptors get this treatment, since tests routinely mine before creating a wallet
+      if matches!(params.timestamp, Timestamp::Now)
+        && !params.descriptor.contains("prv")
+        && Self::rawtr_public_key(&params.descriptor).is_none()
+      {
+        let utxos = state.utxos.keys().copied().collect::<Vec<OutPoint>>();
+        state.unscanned.extend(utxos);
+      }
+
+      state.descriptors.push(params.descriptor);
+    }
 
     Ok(vec![ImportMultiResult {
       success: true,
@@ -689,17 +899,18 @@ impl Api for Server {
   }
 
   fn list_descriptors(&self) -> Result<ListDescriptorsResult, jsonrpc_core::Error> {
+    let state = self.state();
+
     Ok(ListDescriptorsResult {
       wallet_name: "ord".into(),
-      descriptors: self
-        .state()
+      descriptors: state
         .descriptors
         .iter()
         .map(|desc| Descriptor {
-          desc: desc.to_string(),
+          desc: Self::public_descriptor(desc),
           timestamp: Timestamp::Now,
           active: true,
-          internal: None,
+          internal: Some(state.internal_descriptor.as_ref() == Some(desc)),
           range: None,
           next: None,
         })
@@ -707,6 +918,35 @@ impl Api for Server {
     })
   }
 
+  fn get_received_by_address(
+    &self,
+    address: Address<NetworkUnchecked>,
+    minconf: Option<u32>,
+  ) -> Result<f64, jsonrpc_core::Error> {
+    let state = self.state();
+    let script_pubkey = address.assume_checked().script_pubkey();
+
+    let mempool = if minconf == Some(0) {
+      state.mempool()
+    } else {
+      &[]
+    };
+
+    Ok(
+      Amount::from_sat(
+        state
+          .transactions
+          .values()
+          .chain(mempool)
+          .flat_map(|tx| &tx.output)
+          .filter(|output| output.script_pubkey == script_pubkey)
+          .map(|output| output.value)
+          .sum(),
+      )
+      .to_btc(),
+    )
+  }
+
   fn load_wallet(&self, wallet: String) -> Result<LoadWalletResult, jsonrpc_core::Error> {
     if self.state().wallets.contains(&wallet) {
       self.state().loaded_wallets.insert(wallet.clone());
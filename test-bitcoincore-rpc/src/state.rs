@@ -6,16 +6,21 @@ pub(crate) struct State {
   pub(crate) descriptors: Vec<String>,
   pub(crate) fail_lock_unspent: bool,
   pub(crate) hashes: Vec<BlockHash>,
+  pub(crate) internal_descriptor: Option<String>,
+  pub(crate) labels: BTreeMap<ScriptBuf, String>,
   pub(crate) loaded_wallets: BTreeSet<String>,
   pub(crate) locked: BTreeSet<OutPoint>,
   pub(crate) mempool: Vec<Transaction>,
   pub(crate) network: Network,
   pub(crate) nonce: u32,
   pub(crate) sent: Vec<Sent>,
+  pub(crate) test_mempool_accept_calls: Vec<usize>,
   pub(crate) transactions: BTreeMap<Txid, Transaction>,
+  pub(crate) unscanned: BTreeSet<OutPoint>,
   pub(crate) utxos: BTreeMap<OutPoint, Amount>,
   pub(crate) version: usize,
   pub(crate) wallets: BTreeSet<String>,
+  pub(crate) watch_only_wallets: BTreeSet<String>,
 }
 
 impl State {
@@ -34,16 +39,21 @@ impl State {
       descriptors: Vec::new(),
       fail_lock_unspent,
       hashes,
+      internal_descriptor: None,
+      labels: BTreeMap::new(),
       locked: BTreeSet::new(),
       mempool: Vec::new(),
       network,
       nonce: 0,
       sent: Vec::new(),
+      test_mempool_accept_calls: Vec::new(),
       transactions: BTreeMap::new(),
+      unscanned: BTreeSet::new(),
       utxos: BTreeMap::new(),
       version,
       wallets: BTreeSet::new(),
       loaded_wallets: BTreeSet::new(),
+      watch_only_wallets: BTreeSet::new(),
     }
   }
 
@@ -194,6 +204,48 @@ impl State {
     tx.txid()
   }
 
+  // add `tx` to the mempool, evicting any transactions it conflicts with, along with their descendants
+  pub(crate) fn replace(&mut self, tx: Transaction) {
+    let mut evicted = self
+      .mempool
+      .iter()
+      .filter(|mempool_tx| {
+        mempool_tx
+          .input
+          .iter()
+          .any(|input| tx.input.iter().any(|txin| txin.previous_output == input.previous_output))
+      })
+      .map(Transaction::txid)
+      .collect::<BTreeSet<Txid>>();
+
+    loop {
+      let descendants = self
+        .mempool
+        .iter()
+        .filter(|mempool_tx| {
+          !evicted.contains(&mempool_tx.txid())
+            && mempool_tx
+              .input
+              .iter()
+              .any(|input| evicted.contains(&input.previous_output.txid))
+        })
+        .map(Transaction::txid)
+        .collect::<Vec<Txid>>();
+
+      if descendants.is_empty() {
+        break;
+      }
+
+      evicted.extend(descendants);
+    }
+
+    self
+      .mempool
+      .retain(|mempool_tx| !evicted.contains(&mempool_tx.txid()));
+
+    self.mempool.push(tx);
+  }
+
   pub(crate) fn mempool(&self) -> &[Transaction] {
     &self.mempool
   }
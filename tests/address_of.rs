@@ -0,0 +1,32 @@
+use {super::*, ord::subcommand::address_of::Output};
+
+#[test]
+fn address_of_inscription() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, reveal) = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new(format!("address-of {inscription}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.satpoint, SatPoint::from_str(&format!("{reveal}:0:0")).unwrap());
+  assert!(output.address.is_some());
+  assert_eq!(output.note, None);
+}
+
+#[test]
+fn address_of_unknown_inscription() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new(
+    "address-of 0000000000000000000000000000000000000000000000000000000000000000i0",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stderr(
+    "error: inscription 0000000000000000000000000000000000000000000000000000000000000000i0 not found\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
@@ -0,0 +1,114 @@
+use {super::*, ord::subcommand::children::Output};
+
+#[test]
+fn children() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (parent, _reveal) = inscribe(&rpc_server);
+
+  let child = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --parent {parent} --file child.txt"
+  ))
+  .write("child.txt", "child")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!("children {parent}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].inscription, child.inscriptions[0].id);
+  assert_eq!(output[0].satpoint.unwrap().outpoint.txid, child.reveal.unwrap());
+}
+
+#[test]
+fn recursive_children() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (parent, _reveal) = inscribe(&rpc_server);
+
+  let child = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --parent {parent} --file child.txt"
+  ))
+  .write("child.txt", "child")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .inscriptions[0]
+    .id;
+
+  rpc_server.mine_blocks(1);
+
+  let grandchild = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --parent {child} --file grandchild.txt"
+  ))
+  .write("grandchild.txt", "grandchild")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .inscriptions[0]
+    .id;
+
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!("children {parent}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].inscription, child);
+  assert_eq!(output[0].children, None);
+
+  let output = CommandBuilder::new(format!("children --recursive {parent}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output[0].inscription, child);
+
+  let grandchildren = output[0].children.as_ref().unwrap();
+
+  assert_eq!(grandchildren.len(), 1);
+  assert_eq!(grandchildren[0].inscription, grandchild);
+  assert_eq!(grandchildren[0].children, Some(Vec::new()));
+
+  let output = CommandBuilder::new(format!("children --recursive --depth 1 {parent}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output[0].inscription, child);
+  assert_eq!(output[0].children, Some(Vec::new()));
+}
+
+#[test]
+fn childless_inscription_has_empty_children() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  assert_eq!(
+    CommandBuilder::new(format!("children {inscription}"))
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Vec<Output>>(),
+    Vec::new()
+  );
+}
+
+#[test]
+fn unknown_parent() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "children 8d363b28528b0cb86b5fd48615493fb175bdf132d2a3d20b4251bba3f130a5abi0",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stderr(
+    "error: inscription 8d363b28528b0cb86b5fd48615493fb175bdf132d2a3d20b4251bba3f130a5abi0 not found\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
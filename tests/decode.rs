@@ -22,6 +22,33 @@ fn transaction() -> Vec<u8> {
     .push_opcode(opcodes::all::OP_ENDIF)
     .into_script();
 
+  transaction_with_script(script)
+}
+
+fn encoded_transaction(content_encoding: &[u8], body: &[u8]) -> Vec<u8> {
+  let mut content_encoding_buf = script::PushBytesBuf::new();
+  content_encoding_buf.extend_from_slice(content_encoding).unwrap();
+
+  let mut body_buf = script::PushBytesBuf::new();
+  body_buf.extend_from_slice(body).unwrap();
+
+  let script = script::Builder::new()
+    .push_opcode(opcodes::OP_FALSE)
+    .push_opcode(opcodes::all::OP_IF)
+    .push_slice(b"ord")
+    .push_slice([1])
+    .push_slice(b"text/plain;charset=utf-8")
+    .push_slice([9])
+    .push_slice(content_encoding_buf)
+    .push_slice([])
+    .push_slice(body_buf)
+    .push_opcode(opcodes::all::OP_ENDIF)
+    .into_script();
+
+  transaction_with_script(script)
+}
+
+fn transaction_with_script(script: ScriptBuf) -> Vec<u8> {
   let mut witness = Witness::new();
 
   witness.push(script);
@@ -64,6 +91,7 @@ fn from_file() {
         pushnum: false,
         stutter: false,
       }],
+      unrecognized_even_fields: BTreeMap::new(),
     },
   );
 }
@@ -86,6 +114,7 @@ fn from_stdin() {
         pushnum: false,
         stutter: false,
       }],
+      unrecognized_even_fields: BTreeMap::new(),
     },
   );
 }
@@ -114,6 +143,7 @@ fn from_core() {
         pushnum: false,
         stutter: false,
       }],
+      unrecognized_even_fields: BTreeMap::new(),
     },
   );
 }
@@ -127,16 +157,148 @@ fn compact() {
     CompactOutput {
       inscriptions: vec![CompactInscription {
         body: Some("00010203".into()),
+        collection: None,
         content_encoding: None,
         content_type: Some("text/plain;charset=utf-8".into()),
+        decoded_size: None,
         duplicate_field: false,
+        encoded_size: None,
         incomplete_field: false,
         metadata: None,
         metaprotocol: None,
         parent: None,
         pointer: None,
         unrecognized_even_field: false,
+        unrecognized_even_fields: Vec::new(),
       }],
     },
   );
 }
+
+#[test]
+fn decompress_brotli() {
+  let body = b"FOO".repeat(100);
+
+  let mut compressed = Vec::new();
+  brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22)
+    .write_all(&body)
+    .unwrap();
+
+  assert_eq!(
+    CommandBuilder::new("decode --compact --decompress --file transaction.bin")
+      .write("transaction.bin", encoded_transaction(b"br", &compressed))
+      .run_and_deserialize_output::<CompactOutput>(),
+    CompactOutput {
+      inscriptions: vec![CompactInscription {
+        body: Some(hex::encode(&body)),
+        collection: None,
+        content_encoding: Some("br".into()),
+        content_type: Some("text/plain;charset=utf-8".into()),
+        decoded_size: Some(body.len()),
+        duplicate_field: false,
+        encoded_size: Some(compressed.len()),
+        incomplete_field: false,
+        metadata: None,
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
+        unrecognized_even_field: false,
+        unrecognized_even_fields: Vec::new(),
+      }],
+    },
+  );
+}
+
+#[test]
+fn decompress_unknown_encoding() {
+  CommandBuilder::new("decode --compact --decompress --file transaction.bin")
+    .write("transaction.bin", encoded_transaction(b"foo", b"bar"))
+    .expected_stderr("error: failed to decompress inscription 0\nbecause: unknown content encoding `foo`\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn decompress_corrupt_stream() {
+  CommandBuilder::new("decode --compact --decompress --file transaction.bin")
+    .write("transaction.bin", encoded_transaction(b"gzip", b"bar"))
+    .stderr_regex("error: failed to decompress inscription 0\nbecause: corrupt gzip stream: .*\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn unrecognized_even_fields() {
+  let script = script::Builder::new()
+    .push_opcode(opcodes::OP_FALSE)
+    .push_opcode(opcodes::all::OP_IF)
+    .push_slice(b"ord")
+    .push_slice([1])
+    .push_slice(b"text/plain;charset=utf-8")
+    .push_slice([22])
+    .push_slice([0])
+    .push_slice([23])
+    .push_slice([0])
+    .push_slice([])
+    .push_slice([0, 1, 2, 3])
+    .push_opcode(opcodes::all::OP_ENDIF)
+    .into_script();
+
+  assert_eq!(
+    CommandBuilder::new("decode --compact --file transaction.bin")
+      .write("transaction.bin", transaction_with_script(script.clone()))
+      .run_and_deserialize_output::<CompactOutput>()
+      .inscriptions[0]
+      .unrecognized_even_fields,
+    vec!["16".to_string()],
+  );
+
+  assert_eq!(
+    CommandBuilder::new("decode --file transaction.bin")
+      .write("transaction.bin", transaction_with_script(script))
+      .run_and_deserialize_output::<RawOutput>()
+      .unrecognized_even_fields,
+    BTreeMap::from([(0, vec!["16".to_string()])]),
+  );
+}
+
+#[test]
+fn strict() {
+  assert_eq!(
+    CommandBuilder::new("decode --strict --file transaction.bin")
+      .write("transaction.bin", transaction())
+      .run_and_deserialize_output::<RawOutput>(),
+    CommandBuilder::new("decode --file transaction.bin")
+      .write("transaction.bin", transaction())
+      .run_and_deserialize_output::<RawOutput>(),
+  );
+
+  let script = script::Builder::new()
+    .push_opcode(opcodes::OP_FALSE)
+    .push_opcode(opcodes::all::OP_IF)
+    .push_slice(b"ord")
+    .push_slice([1])
+    .push_slice(b"text/plain;charset=utf-8")
+    .push_slice([1])
+    .push_slice(b"text/html;charset=utf-8")
+    .push_slice([])
+    .push_slice([0, 1, 2, 3])
+    .push_opcode(opcodes::all::OP_ENDIF)
+    .into_script();
+
+  assert!(
+    CommandBuilder::new("decode --compact --file transaction.bin")
+      .write("transaction.bin", transaction_with_script(script.clone()))
+      .run_and_deserialize_output::<CompactOutput>()
+      .inscriptions[0]
+      .duplicate_field
+  );
+
+  CommandBuilder::new("decode --strict --file transaction.bin")
+    .write("transaction.bin", transaction_with_script(script))
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: failed to decode inscription 0\nbecause: tag 1 appears 2 times, but may only appear once\n",
+    )
+    .run_and_extract_stdout();
+}
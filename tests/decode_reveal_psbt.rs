@@ -0,0 +1,81 @@
+use {
+  super::*,
+  bitcoin::{absolute::LockTime, psbt::Psbt, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness},
+  ord::subcommand::decode_reveal_psbt::{Output, RevealInscription, RevealOutput},
+};
+
+fn reveal_psbt(witness: Option<Witness>) -> String {
+  let mut psbt = Psbt::from_unsigned_tx(Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: OutPoint::null(),
+      script_sig: ScriptBuf::new(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness: Witness::new(),
+    }],
+    output: vec![TxOut {
+      script_pubkey: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+        .assume_checked()
+        .script_pubkey(),
+      value: 10_000,
+    }],
+  })
+  .unwrap();
+
+  psbt.inputs[0].final_script_witness = witness;
+
+  psbt.to_string()
+}
+
+#[test]
+fn signed_reveal() {
+  assert_eq!(
+    CommandBuilder::new(format!(
+      "decode-reveal-psbt {}",
+      reveal_psbt(Some(envelope(&[
+        b"ord",
+        &[1],
+        b"text/plain;charset=utf-8",
+        &[],
+        b"FOO"
+      ])))
+    ))
+    .run_and_deserialize_output::<Output>(),
+    Output {
+      inscriptions: vec![RevealInscription {
+        input: 0,
+        body_size: Some(3),
+        content_encoding: None,
+        content_type: Some("text/plain;charset=utf-8".into()),
+        delegate: None,
+        metaprotocol: None,
+        parent: None,
+        pointer: None,
+      }],
+      outputs: vec![RevealOutput {
+        address: Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into()),
+        script_pubkey: "0014751e76e8199196d454941c45d1b3a323f1433bd6".into(),
+        value: 10_000,
+      }],
+    }
+  );
+}
+
+#[test]
+fn no_inscriptions() {
+  CommandBuilder::new(format!("decode-reveal-psbt {}", reveal_psbt(None)))
+    .expected_stderr("error: reveal PSBT doesn't reveal any inscriptions\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn bad_psbt() {
+  CommandBuilder::new("decode-reveal-psbt foo")
+    .stderr_regex("error: bad reveal PSBT: .*\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
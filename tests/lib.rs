@@ -124,13 +124,62 @@ fn inscribe(rpc_server: &test_bitcoincore_rpc::Handle) -> (InscriptionId, Txid)
   (output.inscriptions[0].id, output.reveal)
 }
 
+// a fixed recovery key, so the commit and reveal built for a batch depend only on the mock node's utxos
+const FIXTURE_KEY: &str = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+
+// the BIP 39 test vector mnemonic, so a wallet restored from it hands out the same change addresses every run
+const FIXTURE_MNEMONIC: &str =
+  "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+// inscribe `batch` with `FIXTURE_KEY` from a fresh mock node's `FIXTURE_MNEMONIC` wallet, funded by one
+// block, so the commit and reveal, and their txids, vsizes, and fees, are the same every run
+fn batch_fixture(
+  batch: &str,
+  files: &[(&str, &str)],
+  dry_run: bool,
+) -> (test_bitcoincore_rpc::Handle, Inscribe) {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new([
+    "--chain",
+    rpc_server.network().as_str(),
+    "wallet",
+    "restore",
+    FIXTURE_MNEMONIC,
+  ])
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<ord::subcommand::Empty>();
+
+  rpc_server.mine_blocks(1);
+
+  let mut builder = CommandBuilder::new(format!(
+    "--chain {} wallet inscribe{} --fee-rate 1 --key {FIXTURE_KEY} --batch batch.yaml",
+    rpc_server.network(),
+    if dry_run { " --dry-run" } else { "" },
+  ))
+  .write("batch.yaml", batch);
+
+  for (path, contents) in files {
+    builder = builder.write(path, contents);
+  }
+
+  let output = builder
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  (rpc_server, output)
+}
+
 mod command_builder;
 mod expected;
 mod test_server;
 
+mod address_of;
 mod balances;
+mod children;
 mod core;
 mod decode;
+mod decode_reveal_psbt;
 mod epochs;
 mod etch;
 mod find;
@@ -143,6 +192,9 @@ mod runes;
 mod server;
 mod subsidy;
 mod supply;
+mod teleburn;
 mod traits;
+mod verify_content;
+mod verify_delegate;
 mod version;
 mod wallet;
@@ -0,0 +1,82 @@
+use {
+  super::*,
+  ord::subcommand::teleburn::Output,
+};
+
+#[test]
+fn teleburn_block() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new("teleburn --block 2")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].inscription, Some(inscription));
+
+  let single = CommandBuilder::new(format!("teleburn {inscription}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(single.inscription, None);
+  assert_eq!(output[0].ethereum, single.ethereum);
+  assert_eq!(output[0].solana, single.solana);
+}
+
+#[test]
+fn teleburn_file() {
+  let first = "1111111111111111111111111111111111111111111111111111111111111111i0"
+    .parse::<InscriptionId>()
+    .unwrap();
+  let second = "2222222222222222222222222222222222222222222222222222222222222222i1"
+    .parse::<InscriptionId>()
+    .unwrap();
+
+  let output = CommandBuilder::new("teleburn --file ids.txt")
+    .write("ids.txt", format!("{first}\n\n{second}\n"))
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 2);
+
+  for (entry, inscription) in output.iter().zip([first, second]) {
+    let single = CommandBuilder::new(format!("teleburn {inscription}"))
+      .run_and_deserialize_output::<Output>();
+
+    assert_eq!(entry.inscription, Some(inscription));
+    assert_eq!(entry.ethereum, single.ethereum);
+    assert_eq!(entry.solana, single.solana);
+  }
+}
+
+#[test]
+fn teleburn_file_rejects_invalid_inscription_id() {
+  CommandBuilder::new("teleburn --file ids.txt")
+    .write("ids.txt", "foo\n")
+    .stderr_regex("error: invalid inscription ID on line 1: `foo`.*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn teleburn_tron_and_dogecoin_share_ethereum_digest() {
+  let output = CommandBuilder::new(
+    "teleburn 6fb976ab49dcec017f1e201e84395983204ae1a7c2abf7ced0a85d692e442799i0",
+  )
+  .run_and_deserialize_output::<Output>();
+
+  let ethereum = output.ethereum.to_string().to_lowercase();
+
+  for (address, version, prefix) in [
+    (output.tron.to_string(), 0x41, 'T'),
+    (output.dogecoin.to_string(), 0x1e, 'D'),
+  ] {
+    assert!(address.starts_with(prefix), "{address}");
+
+    let bytes = bitcoin::base58::decode_check(&address).unwrap();
+    assert_eq!(bytes[0], version);
+    assert_eq!(format!("0x{}", hex::encode(&bytes[1..])), ethereum);
+  }
+}
@@ -0,0 +1,67 @@
+use {super::*, ord::subcommand::verify_content::Output};
+
+#[test]
+fn matching_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify-content --inscription {inscription} --file foo.txt"))
+      .write("foo.txt", "FOO")
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      matches: true,
+      content_size: 3,
+      file_size: 3,
+      first_difference: None,
+    }
+  );
+}
+
+#[test]
+fn mismatched_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify-content --inscription {inscription} --file foo.txt"))
+      .write("foo.txt", "FOB")
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      matches: false,
+      content_size: 3,
+      file_size: 3,
+      first_difference: Some(2),
+    }
+  );
+}
+
+#[test]
+fn truncated_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify-content --inscription {inscription} --file foo.txt"))
+      .write("foo.txt", "FO")
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      matches: false,
+      content_size: 3,
+      file_size: 2,
+      first_difference: Some(2),
+    }
+  );
+}
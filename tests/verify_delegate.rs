@@ -0,0 +1,78 @@
+use {super::*, ord::subcommand::verify_delegate::Output};
+
+fn inscribe_delegate(rpc_server: &test_bitcoincore_rpc::Handle, delegate: InscriptionId) -> InscriptionId {
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml")
+    .write(
+      "batch.yaml",
+      format!("mode: shared-output\ninscriptions:\n- delegate: {delegate}\n"),
+    )
+    .rpc_server(rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  rpc_server.mine_blocks(1);
+
+  output.inscriptions[0].id
+}
+
+#[test]
+fn delegate_exists() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (delegate, _reveal) = inscribe(&rpc_server);
+
+  let inscription = inscribe_delegate(&rpc_server, delegate);
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify-delegate {inscription}"))
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      delegate,
+      exists: true,
+      content_type: Some("text/plain;charset=utf-8".into()),
+      content_length: Some(3),
+    }
+  );
+}
+
+#[test]
+fn dangling_delegate() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let delegate = "8d363b28528b0cb86b5fd48615493fb175bdf132d2a3d20b4251bba3f130a5abi0"
+    .parse::<InscriptionId>()
+    .unwrap();
+
+  let inscription = inscribe_delegate(&rpc_server, delegate);
+
+  assert_eq!(
+    CommandBuilder::new(format!("verify-delegate {inscription}"))
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Output>(),
+    Output {
+      inscription,
+      delegate,
+      exists: false,
+      content_type: None,
+      content_length: None,
+    }
+  );
+}
+
+#[test]
+fn inscription_without_delegate() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  CommandBuilder::new(format!("verify-delegate {inscription}"))
+    .rpc_server(&rpc_server)
+    .expected_stderr(format!("error: inscription {inscription} has no delegate\n"))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
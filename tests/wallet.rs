@@ -1,13 +1,24 @@
 use super::*;
 
 mod balance;
+mod bump_commit;
 mod cardinals;
+mod change_addresses;
+mod consolidate_plan;
+mod content_type_histogram;
 mod create;
 mod inscribe;
 mod inscriptions;
+mod migrate_from_ordinalswallet;
 mod outputs;
 mod receive;
 mod restore;
+mod runic_outputs;
 mod sats;
 mod send;
+mod sendmany;
+mod sendmany_sats;
+mod sweep_commits;
 mod transactions;
+mod utxo_contents;
+mod watch;
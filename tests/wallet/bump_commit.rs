@@ -0,0 +1,73 @@
+use {super::*, ord::subcommand::wallet::bump_commit::Output};
+
+fn inscribe_with_key(rpc_server: &test_bitcoincore_rpc::Handle) -> Inscribe {
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!("wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --file foo.txt"))
+    .write("foo.txt", "FOO")
+    .rpc_server(rpc_server)
+    .run_and_deserialize_output::<Inscribe>()
+}
+
+#[test]
+fn bump_commit_replaces_commit_and_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let inscribe = inscribe_with_key(&rpc_server);
+
+  let output = CommandBuilder::new(format!(
+    "wallet bump-commit --commit {} --reveal {} --key {FIXTURE_KEY} --fee-rate 10",
+    inscribe.commit.unwrap(),
+    inscribe.reveal.unwrap(),
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Output>();
+
+  assert_ne!(output.commit, inscribe.commit.unwrap());
+  assert_ne!(output.reveal, inscribe.reveal.unwrap());
+  assert!(output.fee > output.replaced_fee);
+  assert_eq!(
+    output.inscriptions,
+    vec![InscriptionId {
+      txid: output.reveal,
+      index: 0,
+    }]
+  );
+
+  let mempool = rpc_server.mempool();
+
+  assert_eq!(
+    mempool.iter().map(|tx| tx.txid()).collect::<Vec<Txid>>(),
+    vec![output.commit, output.reveal]
+  );
+  assert_eq!(mempool[1].input[0].previous_output.txid, output.commit);
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!("wallet bump-commit --commit {} --reveal {} --key {FIXTURE_KEY} --fee-rate 20", output.commit, output.reveal))
+    .rpc_server(&rpc_server)
+    .expected_stderr(format!("error: commit tx {} is already confirmed\n", output.commit))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn bump_commit_must_pay_more_than_commit_and_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let inscribe = inscribe_with_key(&rpc_server);
+
+  CommandBuilder::new(format!(
+    "wallet bump-commit --commit {} --reveal {} --key {FIXTURE_KEY} --fee-rate 1",
+    inscribe.commit.unwrap(),
+    inscribe.reveal.unwrap(),
+  ))
+  .rpc_server(&rpc_server)
+  .stderr_regex("error: --fee-rate 1 sats/vB would pay [0-9]+ sats, but the replacement must pay more than the [0-9]+ sats paid by the commit and reveal it replaces\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+}
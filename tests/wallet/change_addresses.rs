@@ -0,0 +1,57 @@
+use {super::*, ord::subcommand::wallet::change_addresses::Output};
+
+#[test]
+fn change_addresses_are_marked_used_after_a_send() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet send-many-sats --fee-rate 1 --csv payments.csv --broadcast")
+    .write(
+      "payments.csv",
+      "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,1000\n",
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<ord::subcommand::wallet::sendmany::Output>();
+
+  let change_addresses = rpc_server.change_addresses();
+
+  assert_eq!(change_addresses.len(), 1);
+  assert!(rpc_server.mempool()[0]
+    .output
+    .iter()
+    .any(|output| output.script_pubkey == change_addresses[0].script_pubkey()));
+
+  let output = CommandBuilder::new("wallet change-addresses --count 3")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(
+    output
+      .iter()
+      .map(|output| (output.index, output.used))
+      .collect::<Vec<(u32, bool)>>(),
+    [(0, true), (1, false), (2, false)],
+  );
+  assert_eq!(
+    output[0].address.clone().assume_checked(),
+    change_addresses[0]
+  );
+  assert!(output[1..]
+    .iter()
+    .all(|later| later.address != output[0].address));
+}
+
+#[test]
+fn change_addresses_are_unused_in_a_new_wallet() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let output = CommandBuilder::new("wallet change-addresses --count 2")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 2);
+  assert!(output.iter().all(|output| !output.used));
+  assert_ne!(output[0].address, output[1].address);
+}
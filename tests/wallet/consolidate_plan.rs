@@ -0,0 +1,120 @@
+use {
+  super::*,
+  ord::subcommand::wallet::{consolidate_plan, sendmany},
+};
+
+#[test]
+fn consolidate_plan_moves_every_inscription_and_pays_fee_from_cardinals() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let inscriptions = (0..3)
+    .map(|_| inscribe(&rpc_server))
+    .collect::<Vec<(InscriptionId, Txid)>>();
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let plan = CommandBuilder::new("wallet consolidate-plan --addresses 2 --csv plan.csv")
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<consolidate_plan::Output>();
+
+  assert_eq!(
+    plan
+      .destinations
+      .iter()
+      .map(|destination| destination.inscriptions)
+      .collect::<Vec<usize>>(),
+    [2, 1],
+  );
+
+  let csv = fs::read_to_string(tempdir.path().join("plan.csv")).unwrap();
+
+  assert_eq!(csv.lines().count(), 3);
+
+  for (inscription, _reveal) in &inscriptions {
+    assert!(csv.contains(&inscription.to_string()));
+  }
+
+  let output = CommandBuilder::new("wallet send-many --fee-rate 1 --csv plan.csv")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<sendmany::Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  let inputs = tx
+    .input
+    .iter()
+    .map(|input| input.previous_output)
+    .collect::<Vec<OutPoint>>();
+
+  // each inscription is spent once, from its reveal's first output
+  for (_inscription, reveal) in &inscriptions {
+    assert_eq!(
+      inputs
+        .iter()
+        .filter(|outpoint| **outpoint
+          == OutPoint {
+            txid: *reveal,
+            vout: 0
+          })
+        .count(),
+      1,
+    );
+  }
+
+  // the fee comes from cardinals, never from another inscribed output
+  let cardinals = inputs
+    .iter()
+    .filter(|outpoint| {
+      !inscriptions
+        .iter()
+        .any(|(_inscription, reveal)| outpoint.txid == *reveal)
+    })
+    .collect::<Vec<&OutPoint>>();
+
+  assert!(!cardinals.is_empty());
+  assert_eq!(inputs.len(), inscriptions.len() + cardinals.len());
+
+  let input_value = inputs
+    .iter()
+    .map(|outpoint| rpc_server.get_utxo_amount(outpoint).unwrap().to_sat())
+    .sum::<u64>();
+
+  let output_value = tx.output.iter().map(|output| output.value).sum::<u64>();
+
+  assert_eq!(input_value - output_value, output.fee);
+}
+
+#[test]
+fn consolidate_plan_requires_inscriptions() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet consolidate-plan --addresses 2 --csv plan.csv")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: wallet contains no inscriptions\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn consolidate_plan_respects_max_per_address() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  for _ in 0..3 {
+    inscribe(&rpc_server);
+  }
+
+  CommandBuilder::new("wallet consolidate-plan --addresses 1 --max-per-address 2 --csv plan.csv")
+    .rpc_server(&rpc_server)
+    .expected_stderr(
+      "error: wallet has 3 inscriptions, which won't fit in 1 addresses of at most 2 inscriptions each\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
@@ -0,0 +1,28 @@
+use {super::*, ord::subcommand::wallet::content_type_histogram::Output};
+
+#[test]
+fn content_type_histogram() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  assert_eq!(
+    CommandBuilder::new("wallet content-type-histogram")
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Vec<Output>>(),
+    Vec::new()
+  );
+
+  inscribe(&rpc_server);
+  inscribe(&rpc_server);
+
+  assert_eq!(
+    CommandBuilder::new("wallet content-type-histogram")
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Vec<Output>>(),
+    vec![Output {
+      content_type: Some("text/plain;charset=utf-8".into()),
+      count: 2,
+    }]
+  );
+}
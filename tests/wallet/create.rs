@@ -22,6 +22,24 @@ fn seed_phrases_are_twelve_words_long() {
   assert_eq!(mnemonic.word_count(), 12);
 }
 
+#[test]
+fn seed_phrases_can_be_twenty_four_words_long() {
+  let Output { mnemonic, .. } = CommandBuilder::new("wallet create --words 24")
+    .rpc_server(&test_bitcoincore_rpc::spawn())
+    .run_and_deserialize_output();
+
+  assert_eq!(mnemonic.word_count(), 24);
+}
+
+#[test]
+fn seed_phrase_word_count_must_be_twelve_or_twenty_four() {
+  CommandBuilder::new("wallet create --words 18")
+    .rpc_server(&test_bitcoincore_rpc::spawn())
+    .stderr_regex(".*invalid value '18' for '--words <WORDS>'.*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
+}
+
 #[test]
 fn wallet_creates_correct_mainnet_taproot_descriptor() {
   let rpc_server = test_bitcoincore_rpc::spawn();
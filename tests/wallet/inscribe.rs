@@ -89,6 +89,98 @@ fn inscribe_no_backup() {
   assert_eq!(rpc_server.descriptors().len(), 2);
 }
 
+#[test]
+fn inscribe_test_accept_checks_commit_and_reveal_together() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribe --file hello.txt --test-accept --fee-rate 1")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  assert_eq!(rpc_server.test_mempool_accept_calls(), vec![2]);
+  assert_eq!(rpc_server.mempool().len(), 2);
+}
+
+#[test]
+fn inscribe_test_accept_conflicts_with_dry_run() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribe --file hello.txt --test-accept --dry-run --fee-rate 1")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: the argument '--(test-accept|dry-run)' cannot be used with '--(test-accept|dry-run)'.*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_emit_recovery_outputs_the_backed_up_descriptor() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  create_wallet(&rpc_server);
+
+  let output = CommandBuilder::new("wallet inscribe --file hello.txt --emit-recovery --fee-rate 1")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  let recovery_descriptor = output.recovery_descriptor.unwrap();
+
+  assert!(recovery_descriptor.starts_with("rawtr("));
+  assert_eq!(rpc_server.descriptors().len(), 3);
+  assert_eq!(rpc_server.descriptors()[2], recovery_descriptor);
+  assert_eq!(output.commit_hex, None);
+  assert_eq!(output.reveal_hex, None);
+}
+
+#[test]
+fn inscribe_recovery_key_origin() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  create_wallet(&rpc_server);
+
+  let output = CommandBuilder::new(
+    "wallet inscribe --file hello.txt --emit-recovery --recovery-key-origin m/86h/0h/0h --fee-rate 1",
+  )
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  let recovery_descriptor = output.recovery_descriptor.unwrap();
+
+  assert_regex_match!(
+    recovery_descriptor,
+    r"rawtr\(\[[0-9a-f]{8}/86'/0'/0'\][0-9A-Za-z]+\)#.*"
+  );
+
+  // the fingerprint is bitcoind's for a bare x-only key: the hash160 of its even-parity compressed form
+  let wif = recovery_descriptor.split(']').nth(1).unwrap().split(')').next().unwrap();
+
+  let (public_key, _parity) = bitcoin::PrivateKey::from_wif(wif)
+    .unwrap()
+    .inner
+    .x_only_public_key(&bitcoin::secp256k1::Secp256k1::new());
+
+  let mut compressed = vec![0x02];
+  compressed.extend_from_slice(&public_key.serialize());
+
+  assert_eq!(
+    recovery_descriptor[7..15],
+    hex::encode(&<bitcoin::hashes::hash160::Hash as bitcoin::hashes::Hash>::hash(&compressed)[..4])
+  );
+
+  assert_eq!(rpc_server.descriptors()[2], recovery_descriptor);
+}
+
 #[test]
 fn inscribe_unknown_file_extension() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -238,6 +330,28 @@ fn inscribe_with_optional_satpoint_arg() {
     .assert_response_regex(format!("/content/{inscription}",), "FOO");
 }
 
+#[test]
+fn inscribe_only_common_skips_utxos_starting_with_rarer_sats() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  // a fresh coinbase output starts with the first sat of its block, which is uncommon
+  CommandBuilder::new("--index-sats wallet inscribe --file foo.txt --only-common --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: wallet contains no cardinal utxos whose first sat is common\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --only-common --fee-rate 1")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: index must be built with `--index-sats` to use `--only-common`\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
 #[test]
 fn inscribe_with_fee_rate() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -287,6 +401,120 @@ fn inscribe_with_fee_rate() {
   );
 }
 
+#[test]
+fn inscribe_with_double_check_fees() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file degenerate.png --fee-rate 2.0 --double-check-fees")
+    .write("degenerate.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+}
+
+#[test]
+fn inscribe_with_change_value() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file degenerate.png --fee-rate 1 --change-value 100000sat")
+    .write("degenerate.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  let commit = &rpc_server.mempool()[0];
+
+  assert_eq!(commit.output.len(), 2);
+  assert_eq!(commit.output[1].value, 100_000);
+}
+
+// the BIP 341 NUMS point, which nobody knows the private key for
+const NUMS_KEY: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+#[test]
+fn inscribe_with_internal_key() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "wallet inscribe --file foo.txt --fee-rate 1 --no-broadcast --key {FIXTURE_KEY} --internal-key {NUMS_KEY}"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  assert!(output.reveal_hex.is_some());
+  assert_eq!(output.recovery_descriptor, None);
+}
+
+#[test]
+fn inscribe_with_internal_key_requires_key() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --file foo.txt --fee-rate 1 --internal-key {NUMS_KEY}"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .stderr_regex("(?s)error: the following required arguments were not provided:.*--key <KEY>.*")
+  .expected_exit_code(2)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_over_budget_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file foo.txt --fee-rate 1 --budget 10000sat")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .stderr_regex(
+      "error: inscribing would cost [0-9]+ sats, more than the --budget of 10000 sats\n       postage 10000 sats plus fees [0-9]+ sats\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_with_dust_postage_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --file degenerate.png --fee-rate 1 --postage 100sat")
+    .write("degenerate.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: commit transaction output would be dust\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn inscribe_with_dust_postage_and_allow_dust_reveal_output_warns() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet inscribe --file degenerate.png --fee-rate 1 --postage 100sat --allow-dust-reveal-output",
+  )
+  .write("degenerate.png", [1; 520])
+  .rpc_server(&rpc_server)
+  .stderr_regex("warning: reveal output 0 is 100 sats, below its dust value of .*\n")
+  .run_and_deserialize_output::<Inscribe>();
+}
+
 #[test]
 fn inscribe_with_commit_fee_rate() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -367,6 +595,47 @@ fn inscribe_with_dry_run_flag() {
   assert_eq!(rpc_server.mempool().len(), 2);
 }
 
+#[test]
+fn inscribe_with_dry_run_flag_breaks_down_fees() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1")
+    .write("degenerate.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  assert_eq!(
+    output.commit_fee.unwrap() + output.reveal_fee.unwrap(),
+    output.total_fees
+  );
+  assert!(output.commit_vsize.unwrap() > 0);
+  assert!(output.reveal_vsize.unwrap() > 0);
+  assert_eq!(output.total_postage, Some(10_000));
+}
+
+#[test]
+fn inscribe_with_dry_run_flag_reports_signed_commit_vsize() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --dry-run --file degenerate.png --fee-rate 1")
+    .write("degenerate.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  // at 1 sat/vB the commit pays about a sat per vbyte of the signed commit
+  assert!(
+    output
+      .commit_fee
+      .unwrap()
+      .abs_diff(output.commit_vsize.unwrap())
+      <= 1
+  );
+}
+
 #[test]
 fn inscribe_with_dry_run_flag_fees_increase() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -555,75 +824,177 @@ fn inscribe_with_parent_inscription_and_fee_rate() {
 }
 
 #[test]
-fn reinscribe_with_flag() {
+fn inscribe_with_parent_and_reveal_input_sighash_estimates_signed_reveal_vsize() {
   let rpc_server = test_bitcoincore_rpc::spawn();
-  rpc_server.mine_blocks(1);
-
-  assert_eq!(rpc_server.descriptors().len(), 0);
-
   create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
 
-  let inscribe = CommandBuilder::new("wallet inscribe --file tulip.png --fee-rate 5.0 ")
-    .write("tulip.png", [1; 520])
+  let parent_id = CommandBuilder::new("wallet inscribe --fee-rate 5.0 --file parent.png")
+    .write("parent.png", [1; 520])
     .rpc_server(&rpc_server)
-    .run_and_deserialize_output::<Inscribe>();
-
-  assert_eq!(rpc_server.descriptors().len(), 3);
-
-  let txid = rpc_server.mine_blocks(1)[0].txdata[2].txid();
-
-  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
-  let request = ord_server.request(format!("/content/{}", inscribe.inscriptions[0].id));
+    .run_and_deserialize_output::<Inscribe>()
+    .inscriptions[0]
+    .id;
 
-  assert_eq!(request.status(), 200);
+  rpc_server.mine_blocks(1);
 
-  let reinscribe = CommandBuilder::new(format!(
-    "wallet inscribe --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {txid}:0:0"
+  let child_output = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 7.3 --parent {parent_id} --reveal-input-sighash SIGHASH_ALL --file child.png"
   ))
-  .write("orchid.png", [1; 520])
+  .write("child.png", [1; 520])
   .rpc_server(&rpc_server)
   .run_and_deserialize_output::<Inscribe>();
 
-  rpc_server.mine_blocks(1);
+  let commit_tx = &rpc_server.mempool()[0];
+  let reveal_tx = &rpc_server.mempool()[1];
 
-  let ord_server = TestServer::spawn_with_args(&rpc_server, &["--index-sats"]);
-  let request = ord_server.request(format!("/content/{}", reinscribe.inscriptions[0].id));
+  // bitcoind signed the parent input with an explicit sighash type, so its signature is 65 bytes,
+  // while the commit input is still signed with the default type
+  assert_eq!(reveal_tx.input[0].witness.len(), 1);
+  assert_eq!(reveal_tx.input[0].witness.nth(0).unwrap().len(), 65);
+  assert_eq!(reveal_tx.input[1].witness.nth(0).unwrap().len(), 64);
 
-  assert_eq!(request.status(), 200);
-  ord_server.assert_response_regex(
-    format!("/sat/{}", 50 * COIN_VALUE),
-    format!(
-      ".*<dt>inscriptions</dt>.*<a href=/inscription/{}>.*<a href=/inscription/{}>.*",
-      inscribe.inscriptions[0].id, reinscribe.inscriptions[0].id
-    ),
+  assert_eq!(
+    ord::FeeRate::try_from(7.3)
+      .unwrap()
+      .fee(commit_tx.vsize() + reveal_tx.vsize())
+      .to_sat(),
+    child_output.total_fees
   );
 }
 
 #[test]
-fn with_reinscribe_flag_but_not_actually_a_reinscription() {
+fn inscribe_with_parent_value_sends_trimmed_parent_value_to_change() {
   let rpc_server = test_bitcoincore_rpc::spawn();
-  rpc_server.mine_blocks(1);
-
-  assert_eq!(rpc_server.descriptors().len(), 0);
-
   create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
 
-  CommandBuilder::new("wallet inscribe --file tulip.png --fee-rate 5.0 ")
-    .write("tulip.png", [1; 520])
+  let parent_id = CommandBuilder::new("wallet inscribe --fee-rate 1 --file parent.png")
+    .write("parent.png", [1; 520])
     .rpc_server(&rpc_server)
-    .run_and_deserialize_output::<Inscribe>();
+    .run_and_deserialize_output::<Inscribe>()
+    .inscriptions[0]
+    .id;
 
-  let coinbase = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+  rpc_server.mine_blocks(1);
 
-  CommandBuilder::new(format!(
-    "wallet inscribe --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {coinbase}:0:0"
+  let child_output = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --parent {parent_id} --parent-value 1000 --file child.png"
   ))
-  .write("orchid.png", [1; 520])
+  .write("child.png", [1; 520])
   .rpc_server(&rpc_server)
-  .expected_exit_code(1)
-  .stderr_regex("error: reinscribe flag set but this would not be a reinscription.*")
-  .run_and_extract_stdout();
-}
+  .run_and_deserialize_output::<Inscribe>();
+
+  let commit_tx = &rpc_server.mempool()[0];
+  let reveal_tx = &rpc_server.mempool()[1];
+
+  assert_eq!(
+    reveal_tx
+      .output
+      .iter()
+      .map(|output| output.value)
+      .collect::<Vec<u64>>(),
+    [1000, 10_000, 9000],
+  );
+
+  assert!(rpc_server
+    .change_addresses()
+    .iter()
+    .any(|address| address.script_pubkey() == reveal_tx.output[2].script_pubkey));
+
+  assert_eq!(
+    ord::FeeRate::try_from(1.0)
+      .unwrap()
+      .fee(commit_tx.vsize() + reveal_tx.vsize())
+      .to_sat(),
+    child_output.total_fees
+  );
+}
+
+#[test]
+fn inscribe_with_parent_value_and_no_wallet_requires_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --no-wallet --parent 0000000000000000000000000000000000000000000000000000000000000000i0 --parent-value 1000 --file child.png",
+  )
+  .write("child.png", [1; 520])
+  .rpc_server(&rpc_server)
+  .expected_stderr("error: --parent-value sends the value trimmed off the parent to change, so without a wallet it needs --change\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn reinscribe_with_flag() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  assert_eq!(rpc_server.descriptors().len(), 0);
+
+  create_wallet(&rpc_server);
+
+  let inscribe = CommandBuilder::new("wallet inscribe --file tulip.png --fee-rate 5.0 ")
+    .write("tulip.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  assert_eq!(rpc_server.descriptors().len(), 3);
+
+  let txid = rpc_server.mine_blocks(1)[0].txdata[2].txid();
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
+  let request = ord_server.request(format!("/content/{}", inscribe.inscriptions[0].id));
+
+  assert_eq!(request.status(), 200);
+
+  let reinscribe = CommandBuilder::new(format!(
+    "wallet inscribe --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {txid}:0:0"
+  ))
+  .write("orchid.png", [1; 520])
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  rpc_server.mine_blocks(1);
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &["--index-sats"]);
+  let request = ord_server.request(format!("/content/{}", reinscribe.inscriptions[0].id));
+
+  assert_eq!(request.status(), 200);
+  ord_server.assert_response_regex(
+    format!("/sat/{}", 50 * COIN_VALUE),
+    format!(
+      ".*<dt>inscriptions</dt>.*<a href=/inscription/{}>.*<a href=/inscription/{}>.*",
+      inscribe.inscriptions[0].id, reinscribe.inscriptions[0].id
+    ),
+  );
+}
+
+#[test]
+fn with_reinscribe_flag_but_not_actually_a_reinscription() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  assert_eq!(rpc_server.descriptors().len(), 0);
+
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribe --file tulip.png --fee-rate 5.0 ")
+    .write("tulip.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  let coinbase = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --file orchid.png --fee-rate 1.1 --reinscribe --satpoint {coinbase}:0:0"
+  ))
+  .write("orchid.png", [1; 520])
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex("error: reinscribe flag set but this would not be a reinscription.*")
+  .run_and_extract_stdout();
+}
 
 #[test]
 fn try_reinscribe_without_flag() {
@@ -819,6 +1190,56 @@ fn batch_inscribe_can_create_one_inscription() {
   );
 }
 
+#[test]
+fn batch_inscribe_max_inscription_postage_returns_excess_as_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --batch batch.yaml --max-inscription-postage 15000sat",
+  )
+  .write("inscription.txt", "Hello World")
+  .write("tulip.png", [0; 555])
+  .write(
+    "batch.yaml",
+    "mode: shared-output\ninscriptions:\n- file: inscription.txt\n- file: tulip.png\n",
+  )
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  let reveal = &rpc_server.mempool()[1];
+
+  assert_eq!(reveal.output.len(), 2);
+  assert_eq!(reveal.output[0].value, 15_000);
+  assert_eq!(reveal.output[1].value, 5_000);
+}
+
+#[test]
+fn batch_inscribe_max_inscription_postage_must_hold_every_inscription() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --batch batch.yaml --max-inscription-postage 10000sat",
+  )
+  .write("inscription.txt", "Hello World")
+  .write("tulip.png", [0; 555])
+  .write(
+    "batch.yaml",
+    "mode: shared-output\ninscriptions:\n- file: inscription.txt\n- file: tulip.png\n",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stderr(
+    "error: --max-inscription-postage 10000 sats is too small to hold the last inscription, at offset 10000\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
 #[test]
 fn batch_inscribe_with_multiple_inscriptions() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -914,6 +1335,47 @@ fn batch_inscribe_with_multiple_inscriptions_with_parent() {
   assert_eq!(request.headers().get("content-type").unwrap(), "audio/wav");
 }
 
+#[test]
+fn batch_inscribe_with_parent_satpoint() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let parent_output = CommandBuilder::new("wallet inscribe --fee-rate 5.0 --file parent.png")
+    .write("parent.png", [1; 520])
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  rpc_server.mine_blocks(1);
+
+  let parent_id = parent_output.inscriptions[0].id;
+  let reveal = parent_output.reveal.unwrap();
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write(
+      "batch.yaml",
+      format!("parent: {parent_id}\nparent_satpoint: {reveal}:0:1\nmode: shared-output\ninscriptions:\n- file: inscription.txt\n"),
+    )
+    .rpc_server(&rpc_server)
+    .expected_stderr(format!(
+      "error: parent satpoint {reveal}:0:1 does not hold parent {parent_id}\n"
+    ))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write(
+      "batch.yaml",
+      format!("parent: {parent_id}\nparent_satpoint: {reveal}:0:0\nmode: shared-output\ninscriptions:\n- file: inscription.txt\n"),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  assert_eq!(output.parent, Some(parent_id));
+}
+
 #[test]
 fn batch_inscribe_respects_dry_run_flag() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -1384,6 +1846,79 @@ fn batch_inscribe_fails_with_shared_output_and_destination_set() {
     .run_and_extract_stdout();
 }
 
+#[test]
+fn batch_inscribe_rejects_metaprotocols_not_in_allowlist() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml --metaprotocols brc-20,sns")
+    .write("inscription.txt", "Hello World")
+    .write(
+      "batch.yaml",
+      "mode: separate-outputs\ninscriptions:\n- file: inscription.txt\n  metaprotocol: brc-20\n- file: inscription.txt\n  metaprotocol: brc20\n",
+    )
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: inscription 1 has metaprotocol `brc20`, which isn't one of --metaprotocols brc-20,sns\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn batch_sat_cannot_be_set_with_inscription_utxos() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let utxo = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  CommandBuilder::new("--index-sats wallet inscribe --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write(
+      "batch.yaml",
+      format!("mode: same-sat\nsat: 5000111111\ninscriptions:\n- file: inscription.txt\n  utxo: {utxo}\n"),
+    )
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: `sat` cannot be set when specifying the utxo to inscribe on for each inscription\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn batch_same_sat_fails_if_sat_utxo_cannot_cover_postage_and_reveal_fee() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  rpc_server.mine_blocks(2);
+
+  CommandBuilder::new("--index-sats wallet inscribe --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write("tulip.png", [0; 555])
+    .write(
+      "batch.yaml",
+      "mode: same-sat\nsat: 9999999000\ninscriptions:\n- file: inscription.txt\n- file: tulip.png\n"
+    )
+    .rpc_server(&rpc_server)
+    .stderr_regex(r"error: same-sat inscriptions need \d+ sats for postage 10000 plus reveal fee \d+, but utxo \w+:0 only has 1000 sats from sat \w+:0:4999999000 on, \d+ sats short\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn batch_inscribe_fails_if_pointer_is_past_reveal_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write("batch.yaml", "mode: separate-outputs\ninscriptions:\n- file: inscription.txt\n  pointer: 10000\n")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: inscription 0 has pointer 10000, but the reveal outputs only hold 10000 sats\n")
+    .run_and_extract_stdout();
+}
+
 #[test]
 fn batch_inscribe_works_with_some_destinations_set_and_others_not() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -1800,3 +2335,746 @@ fn server_can_decompress_brotli() {
   assert_eq!(response.status(), StatusCode::OK);
   assert_eq!(response.bytes().unwrap().deref(), [0; 350_000]);
 }
+
+#[test]
+fn inscribe_resume_from_records_progress() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let progress = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --file foo.txt --resume-from progress.json",
+  )
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .run_and_extract_file("progress.json");
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+
+  let json: serde_json::Value = serde_json::from_str(&progress).unwrap();
+  assert_eq!(json["commit_broadcast"], true);
+  assert_eq!(json["reveal_broadcast"], true);
+
+  let stdout = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --file foo.txt --resume-from progress.json",
+  )
+  .write("foo.txt", "FOO")
+  .write("progress.json", &progress)
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .expected_stderr("everything in progress.json has already been broadcast\n")
+  .run_and_extract_stdout();
+
+  let output: Inscribe = serde_json::from_str(&stdout).unwrap();
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+  assert_eq!(
+    output.reveal,
+    serde_json::from_value::<Inscribe>(json["output"].clone())
+      .unwrap()
+      .reveal
+  );
+}
+
+#[test]
+fn inscribe_export_signed_then_broadcast_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let exported = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --file foo.txt --export-signed signed.json",
+  )
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .run_and_extract_file("signed.json");
+
+  assert_eq!(rpc_server.mempool().len(), 0);
+
+  let json: serde_json::Value = serde_json::from_str(&exported).unwrap();
+  assert_eq!(json["commit_broadcast"], false);
+  assert_eq!(json["reveal_broadcast"], false);
+  assert!(json["output"]["recovery_descriptor"].is_string());
+
+  let output = CommandBuilder::new("wallet broadcast-file signed.json")
+    .write("signed.json", &exported)
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+  assert_eq!(
+    output.reveal,
+    serde_json::from_value::<Inscribe>(json["output"].clone())
+      .unwrap()
+      .reveal
+  );
+}
+
+#[test]
+fn batch_inscribe_with_per_inscription_postage() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet inscribe --fee-rate 1 --no-broadcast --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write("tulip.png", "TULIP")
+    .write(
+      "batch.yaml",
+      "mode: separate-outputs\npostage: 10000\ninscriptions:\n- file: inscription.txt\n  postage: 25000\n- file: tulip.png\n",
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>();
+
+  let reveal: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.reveal_hex.unwrap()).unwrap()).unwrap();
+
+  assert_eq!(reveal.output[0].value, 25_000);
+  assert_eq!(reveal.output[1].value, 10_000);
+}
+
+#[test]
+fn batch_inscribe_per_inscription_postage_requires_separate_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml")
+    .write("inscription.txt", "Hello World")
+    .write("tulip.png", "TULIP")
+    .write(
+      "batch.yaml",
+      "mode: shared-output\ninscriptions:\n- file: inscription.txt\n  postage: 25000\n- file: tulip.png\n",
+    )
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: individual inscription postage can only be set in `separate-outputs` mode\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn batch_fixture_pins_txids_vsizes_and_fees() {
+  let batch = "mode: separate-outputs\ninscriptions:\n- file: inscription.txt\n- file: tulip.png\n";
+  let files = [("inscription.txt", "Hello World"), ("tulip.png", "TULIP")];
+
+  let (_rpc_server, a) = batch_fixture(batch, &files, true);
+  let (_rpc_server, b) = batch_fixture(batch, &files, true);
+
+  assert!(a.commit.is_some());
+  assert!(a.reveal.is_some());
+  assert_eq!(
+    (a.commit, a.reveal, a.commit_vsize, a.reveal_vsize),
+    (b.commit, b.reveal, b.commit_vsize, b.reveal_vsize),
+  );
+  assert_eq!(
+    (a.commit_fee, a.reveal_fee, a.total_fees),
+    (b.commit_fee, b.reveal_fee, b.total_fees),
+  );
+  assert_eq!(a.inscriptions, b.inscriptions);
+
+  // the dry run's numbers are the ones the same batch really broadcasts
+  let (rpc_server, broadcast) = batch_fixture(batch, &files, false);
+
+  assert_eq!(broadcast.commit, a.commit);
+  assert_eq!(broadcast.reveal, a.reveal);
+  assert_eq!(broadcast.total_fees, a.total_fees);
+
+  let mempool = rpc_server.mempool();
+
+  assert_eq!(mempool.len(), 2);
+
+  let commit_tx = &mempool[0];
+  let reveal_tx = &mempool[1];
+
+  assert_eq!(Some(commit_tx.txid()), a.commit);
+  assert_eq!(Some(reveal_tx.txid()), a.reveal);
+  assert_eq!(
+    a.commit_vsize,
+    Some(u64::try_from(commit_tx.vsize()).unwrap())
+  );
+  assert_eq!(
+    a.reveal_vsize,
+    Some(u64::try_from(reveal_tx.vsize()).unwrap())
+  );
+
+  let commit_fee = commit_tx
+    .input
+    .iter()
+    .map(|input| {
+      rpc_server
+        .get_utxo_amount(&input.previous_output)
+        .unwrap()
+        .to_sat()
+    })
+    .sum::<u64>()
+    - commit_tx
+      .output
+      .iter()
+      .map(|output| output.value)
+      .sum::<u64>();
+
+  assert_eq!(reveal_tx.input.len(), 1);
+
+  let commit = reveal_tx.input[0].previous_output;
+
+  assert_eq!(commit.txid, commit_tx.txid());
+
+  let reveal_fee = commit_tx.output[usize::try_from(commit.vout).unwrap()].value
+    - reveal_tx
+      .output
+      .iter()
+      .map(|output| output.value)
+      .sum::<u64>();
+
+  assert_eq!(a.commit_fee, Some(commit_fee));
+  assert_eq!(a.reveal_fee, Some(reveal_fee));
+  assert_eq!(a.total_fees, commit_fee + reveal_fee);
+}
+
+#[test]
+fn chained_batches_spend_each_reveals_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --batch a.yaml"
+  ))
+  .write("a.yaml", "mode: separate-outputs\ninscriptions:\n- file: a.txt\n")
+  .write("a.txt", "A")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  let outputs = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commitment {commit}:0 --batch a.yaml --batch b.yaml"
+  ))
+  .write("a.yaml", "mode: separate-outputs\ninscriptions:\n- file: a.txt\n")
+  .write("b.yaml", "mode: separate-outputs\ninscriptions:\n- file: b.txt\n")
+  .write("a.txt", "A")
+  .write("b.txt", "B")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Vec<Inscribe>>();
+
+  assert_eq!(outputs.len(), 2);
+
+  let mempool = rpc_server.mempool();
+
+  assert_eq!(mempool.len(), 2);
+
+  let first = &mempool[0];
+  let second = &mempool[1];
+
+  assert_eq!(Some(first.txid()), outputs[0].reveal);
+  assert_eq!(Some(second.txid()), outputs[1].reveal);
+
+  assert_eq!(
+    first.input[0].previous_output,
+    OutPoint {
+      txid: commit,
+      vout: 0
+    }
+  );
+
+  assert_eq!(
+    second.input[0].previous_output,
+    OutPoint {
+      txid: first.txid(),
+      vout: u32::try_from(first.output.len() - 1).unwrap(),
+    }
+  );
+}
+
+#[test]
+fn chained_batches_require_commitment() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --batch a.yaml --batch b.yaml"
+  ))
+  .write("a.yaml", "mode: separate-outputs\ninscriptions:\n- file: a.txt\n")
+  .write("b.yaml", "mode: separate-outputs\ninscriptions:\n- file: b.txt\n")
+  .write("a.txt", "A")
+  .write("b.txt", "B")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr("error: several --batch files can only be chained starting from a --commitment\n")
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn offline_reveal_needs_no_bitcoind() {
+  let commit = "0000000000000000000000000000000000000000000000000000000000000001:0";
+  let destination = "bc1pxwww0ct9ue7e8tdnlmug5m2tamfn7q06sahstg39ys4c9f3340qqxrdu9k";
+
+  let output = CommandBuilder::new(format!(
+    "wallet inscribe --offline-reveal --file foo.txt --fee-rate 1 --key {FIXTURE_KEY} --commit-outpoint {commit} --commit-value 20000sat --destination {destination}"
+  ))
+  .write("foo.txt", "FOO")
+  .run_and_extract_stdout();
+
+  let output = serde_json::from_str::<serde_json::Value>(&output).unwrap();
+
+  assert_eq!(
+    output["postage"].as_u64().unwrap() + output["fee"].as_u64().unwrap(),
+    20000
+  );
+  assert_eq!(
+    output["inscription"].as_str().unwrap(),
+    format!("{}i0", output["reveal"].as_str().unwrap())
+  );
+  assert!(!output["reveal_psbt"].as_str().unwrap().is_empty());
+}
+
+#[test]
+fn batch_inscribe_collect_errors_reports_every_bad_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet inscribe --fee-rate 1 --batch batch.yaml --collect-errors")
+    .write("inscription.txt", "Hello World")
+    .write(
+      "batch.yaml",
+      "mode: separate-outputs\ninscriptions:\n- file: missing.txt\n- file: inscription.txt\n- file: gone.png\n",
+    )
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex("error: 2 of 3 batch entries failed:\ninscription 0 \\(missing.txt\\): .*\ninscription 2 \\(gone.png\\): .*\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn reveal_change_target_adds_wallet_output_as_reveal_input() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  let target = OutPoint {
+    txid: rpc_server.tx(2, 0).txid(),
+    vout: 0,
+  };
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commitment {commit}:0 --reveal-change-target {target}:100 --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  let reveal_tx = &rpc_server.mempool()[0];
+
+  assert_eq!(
+    reveal_tx
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<Vec<OutPoint>>(),
+    [OutPoint { txid: commit, vout: 0 }, target],
+  );
+  assert_eq!(reveal_tx.output.len(), 2);
+  assert_eq!(reveal_tx.output[0].value, 10_000);
+}
+
+#[test]
+fn reveal_change_target_must_be_in_a_reveal_input_or_wallet_output() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  let target = OutPoint {
+    txid: rpc_server.tx(1, 0).txid(),
+    vout: 0,
+  };
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commitment {commit}:0 --reveal-change-target {target}:100 --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .expected_stderr(format!("error: --reveal-change-target {target}:100 isn't in the commitment, a --reveal-input, or a wallet output\n"))
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn reveal_input_with_value_and_script_is_not_looked_up() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  let external = OutPoint {
+    txid: "1111111111111111111111111111111111111111111111111111111111111111"
+      .parse()
+      .unwrap(),
+    vout: 3,
+  };
+
+  let script = "51200000000000000000000000000000000000000000000000000000000000000000";
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commitment {commit}:0 --reveal-input {external} --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .stderr_regex("error: .*")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commitment {commit}:0 --reveal-input {external} --reveal-input-value 20000sat --reveal-input-script {script} --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  let reveal_tx = &rpc_server.mempool()[0];
+
+  assert_eq!(
+    reveal_tx
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<Vec<OutPoint>>(),
+    [OutPoint { txid: commit, vout: 0 }, external],
+  );
+}
+
+#[test]
+fn inspect_commit_lists_the_inscriptions_its_reveal_would_make() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --inspect-commit {commit} --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_extract_stdout();
+
+  let output = serde_json::from_str::<serde_json::Value>(&output).unwrap();
+
+  let commit_tx = rpc_server.tx(2, 1);
+
+  assert_eq!(commit_tx.txid(), commit);
+  assert_eq!(output["commit"].as_str().unwrap(), format!("{commit}:0"));
+  assert_eq!(output["value"].as_u64().unwrap(), commit_tx.output[0].value);
+  assert_eq!(
+    output["address"]
+      .as_str()
+      .unwrap()
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+      .script_pubkey(),
+    commit_tx.output[0].script_pubkey,
+  );
+  assert_eq!(
+    output["inscriptions"],
+    serde_json::json!([{
+      "body": hex::encode("FOO"),
+      "content_type": "text/plain;charset=utf-8",
+    }]),
+  );
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn inspect_commit_rejects_a_commit_to_other_inscriptions() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --inspect-commit {commit} --file foo.txt"
+  ))
+  .write("foo.txt", "BAR")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex(format!(
+    "error: commit tx {commit} has no output paying to .*; check --key and the inscription contents\n"
+  ))
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn reveal_psbt_out_writes_unsigned_reveal_when_signing() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let psbt = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --file foo.txt --reveal-psbt-out reveal.psbt",
+  )
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .run_and_extract_file("reveal.psbt");
+
+  let psbt = psbt.trim().parse::<bitcoin::psbt::Psbt>().unwrap();
+
+  let mempool = rpc_server.mempool();
+
+  assert_eq!(mempool.len(), 2);
+
+  let commit_tx = &mempool[0];
+  let reveal_tx = &mempool[1];
+
+  assert_eq!(psbt.unsigned_tx.txid(), reveal_tx.txid());
+  assert!(psbt
+    .unsigned_tx
+    .input
+    .iter()
+    .all(|input| input.witness.is_empty()));
+
+  let commit = psbt.unsigned_tx.input[0].previous_output;
+
+  assert_eq!(commit.txid, commit_tx.txid());
+  assert_eq!(
+    psbt.inputs[0].witness_utxo.as_ref(),
+    Some(&commit_tx.output[usize::try_from(commit.vout).unwrap()]),
+  );
+}
+
+#[test]
+fn reveal_psbt_out_conflicts_with_commit_only() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt --reveal-psbt-out reveal.psbt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr("error: --reveal-psbt-out doesn't work with --commit-only\n")
+  .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn fee_bump_reveal_pays_the_higher_rate_from_the_reveal_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  let commitment_value = rpc_server.tx(2, 1).output[0].value;
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --fee-bump-reveal 5 --key {FIXTURE_KEY} --commitment {commit}:0 --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  let reveal_tx = &rpc_server.mempool()[0];
+
+  assert_eq!(reveal_tx.output.len(), 2);
+  assert_eq!(reveal_tx.output[0].value, 10_000);
+
+  let fee = commitment_value
+    - reveal_tx
+      .output
+      .iter()
+      .map(|output| output.value)
+      .sum::<u64>();
+
+  assert_eq!(fee, 5 * u64::try_from(reveal_tx.vsize()).unwrap());
+}
+
+#[test]
+fn fee_bump_reveal_must_raise_the_fee() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 5 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 5 --fee-bump-reveal 2 --key {FIXTURE_KEY} --commitment {commit}:0 --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex(
+    "error: --fee-bump-reveal 2 sats/vB would pay [0-9]+ sats, less than the [0-9]+ sats the reveal pays already\n",
+  )
+  .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn fee_bump_reveal_cannot_spend_the_reveal_change_below_dust() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let commit = CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --key {FIXTURE_KEY} --commit-only --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>()
+  .commit
+  .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 1 --fee-bump-reveal 100000000 --key {FIXTURE_KEY} --commitment {commit}:0 --file foo.txt"
+  ))
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .stderr_regex(
+    "error: commitment and reveal inputs \\([0-9]+ sats\\) don't cover postage 10000 plus reveal fee [0-9]+ plus dust limit [0-9]+ for the reveal change\n",
+  )
+  .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn commit_input_is_spent_alongside_batch_fee_utxos() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let inscribed = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+  let fee = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+  let forced = OutPoint::new(rpc_server.mine_blocks(1)[0].txdata[0].txid(), 0);
+
+  CommandBuilder::new(format!(
+    "wallet inscribe --fee-rate 0 --batch batch.yaml --commit-input {forced} --commit-input {fee}"
+  ))
+  .write("inscription.txt", "Hello World")
+  .write(
+    "batch.yaml",
+    format!(
+      "mode: separate-outputs\nfees:\n- {fee}\ninscriptions:\n- file: inscription.txt\n  utxo: {inscribed}\n"
+    ),
+  )
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Inscribe>();
+
+  let mempool = rpc_server.mempool();
+
+  assert_eq!(mempool.len(), 2);
+
+  let commit_tx = &mempool[0];
+  let reveal_tx = &mempool[1];
+
+  let mut inputs = commit_tx
+    .input
+    .iter()
+    .map(|input| input.previous_output)
+    .collect::<Vec<OutPoint>>();
+
+  inputs.sort();
+
+  let mut expected = vec![inscribed, fee, forced];
+
+  expected.sort();
+
+  assert_eq!(inputs, expected);
+
+  // the commit's change all goes to fees, so it only pays the reveal, which only holds the inscription
+  assert_eq!(commit_tx.output.len(), 1);
+  assert_eq!(reveal_tx.output.len(), 1);
+  assert_eq!(reveal_tx.output[0].value, 50 * COIN_VALUE);
+
+  let fees = 150 * COIN_VALUE - reveal_tx.output[0].value;
+  let reveal_fee = commit_tx.output[0].value - reveal_tx.output[0].value;
+
+  // the pool after postage, which includes the forced input, is split by each transaction's share of the
+  // total vsize, give or take the difference between the estimated and signed vsizes
+  let share = reveal_fee as f64 / fees as f64;
+  let vsize_share = reveal_tx.vsize() as f64 / (commit_tx.vsize() + reveal_tx.vsize()) as f64;
+
+  assert!((share - vsize_share).abs() < 0.05, "{share} {vsize_share}");
+}
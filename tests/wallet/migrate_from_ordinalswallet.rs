@@ -0,0 +1,88 @@
+use super::*;
+
+#[test]
+fn migrating_empty_wallet_fails() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet migrate-from-ordinalswallet --to standard --fee-rate 1")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: wallet has nothing to migrate\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn migrating_funded_wallet_moves_inscription_and_balance() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, reveal) = inscribe(&rpc_server);
+
+  let before = CommandBuilder::new("wallet balance")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<ord::subcommand::wallet::balance::Output>();
+
+  let output = CommandBuilder::new(
+    "wallet migrate-from-ordinalswallet --to standard --fee-rate 1 --broadcast",
+  )
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<ord::subcommand::wallet::migrate_from_ordinalswallet::Output>();
+
+  assert_eq!(output.wallet, "standard");
+  assert_eq!(output.inscriptions, 1);
+
+  let tx = rpc_server.mempool()[0].clone();
+
+  assert_eq!(tx.txid().to_string(), output.tx);
+  assert_eq!(
+    tx.input[0].previous_output,
+    OutPoint {
+      txid: reveal,
+      vout: 0
+    }
+  );
+  assert_eq!(tx.output.len(), output.addresses.len());
+
+  for (tx_out, address) in tx.output.iter().zip(&output.addresses) {
+    assert_eq!(
+      tx_out.script_pubkey,
+      address.clone().assume_checked().script_pubkey()
+    );
+  }
+
+  let fee = before.total - tx.output.iter().map(|tx_out| tx_out.value).sum::<u64>();
+
+  rpc_server.mine_blocks(1);
+
+  let coinbase = rpc_server
+    .tx(usize::try_from(rpc_server.height()).unwrap(), 0)
+    .output
+    .iter()
+    .map(|tx_out| tx_out.value)
+    .sum::<u64>();
+
+  let after = CommandBuilder::new("wallet --name standard balance")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<ord::subcommand::wallet::balance::Output>();
+
+  assert_eq!(after.ordinal, before.ordinal);
+  assert_eq!(after.cardinal, before.cardinal - fee + coinbase);
+
+  let inscriptions = CommandBuilder::new("wallet --name standard inscriptions")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<ord::subcommand::wallet::inscriptions::Output>>();
+
+  assert_eq!(inscriptions.len(), 1);
+  assert_eq!(inscriptions[0].inscription, inscription);
+  assert_eq!(
+    inscriptions[0].location,
+    SatPoint {
+      outpoint: OutPoint {
+        txid: tx.txid(),
+        vout: 0
+      },
+      offset: 0,
+    }
+  );
+}
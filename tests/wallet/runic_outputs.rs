@@ -0,0 +1,45 @@
+use {super::*, ord::subcommand::wallet::runic_outputs::Output};
+
+#[test]
+fn runic_outputs() {
+  let rpc_server = test_bitcoincore_rpc::builder()
+    .network(Network::Regtest)
+    .build();
+
+  create_wallet(&rpc_server);
+
+  assert_eq!(
+    CommandBuilder::new("--regtest --index-runes wallet runic-outputs")
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Vec<Output>>(),
+    Vec::new()
+  );
+
+  let etch = etch(&rpc_server, Rune(RUNE));
+
+  assert_eq!(
+    CommandBuilder::new("--regtest --index-runes wallet runic-outputs")
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Vec<Output>>(),
+    vec![Output {
+      output: OutPoint {
+        txid: etch.transaction,
+        vout: 1,
+      },
+      amount: 10_000,
+      runes: vec![(Rune(RUNE), 1000)].into_iter().collect(),
+    }]
+  );
+}
+
+#[test]
+fn runic_outputs_requires_rune_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet runic-outputs")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: `wallet runic-outputs` requires index created with `--index-runes` flag\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
@@ -0,0 +1,262 @@
+use {
+  super::*,
+  bitcoin::psbt::Psbt,
+  ord::subcommand::wallet::sendmany::Output,
+};
+
+#[test]
+fn sendmany_psbt_is_unsigned_with_witness_utxos() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new("wallet send-many --fee-rate 1 --csv transfers.csv --psbt")
+    .write(
+      "transfers.csv",
+      format!("{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  let psbt = output.tx.parse::<Psbt>().unwrap();
+
+  assert_eq!(psbt.inputs.len(), output.input_count);
+  assert!(psbt.inputs.iter().all(|input| input.witness_utxo.is_some()));
+  assert!(psbt
+    .unsigned_tx
+    .input
+    .iter()
+    .all(|input| input.witness.is_empty()));
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn sendmany_psbt_conflicts_with_broadcast() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("wallet send-many --fee-rate 1 --csv transfers.csv --psbt --broadcast")
+    .rpc_server(&rpc_server)
+    .stderr_regex(".*--psbt.*cannot be used with.*--broadcast.*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn sendmany_reads_json_transfers() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, reveal) = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new("wallet send-many --fee-rate 1 --json transfers.json")
+    .write(
+      "transfers.json",
+      format!(
+        r#"[{{"inscription": "{inscription}", "destination": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"}}]"#
+      ),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  assert_eq!(tx.input[0].previous_output, OutPoint { txid: reveal, vout: 0 });
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn sendmany_requires_csv_or_json() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("wallet send-many --fee-rate 1")
+    .rpc_server(&rpc_server)
+    .stderr_regex(".*the following required arguments were not provided.*--csv <CSV>.*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn sendmany_csv_skips_comments_blank_lines_and_header() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, reveal) = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new("wallet send-many --fee-rate 1 --header --csv transfers.csv")
+    .write(
+      "transfers.csv",
+      format!("inscription,destination\n# collection airdrop\n\n{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  assert_eq!(tx.input[0].previous_output, OutPoint { txid: reveal, vout: 0 });
+}
+
+#[test]
+fn sendmany_csv_errors_point_at_source_lines() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  let csv = format!(
+    "inscription,destination\n# collection airdrop\n\n{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"
+  );
+
+  CommandBuilder::new("wallet send-many --fee-rate 1 --header --csv transfers.csv")
+    .write("transfers.csv", &csv)
+    .rpc_server(&rpc_server)
+    .expected_stderr(format!("error: duplicate entry for {inscription} on line 5\n"))
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("wallet send-many --fee-rate 1 --csv transfers.csv")
+    .write("transfers.csv", &csv)
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: bad inscriptionid on line 1: .*")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn sendmany_consolidate_sweeps_small_cardinals_into_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  rpc_server.mine_blocks(1);
+
+  let small = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(usize::try_from(rpc_server.height()).unwrap(), 0, 0, Default::default())],
+    outputs: 3,
+    output_values: &[1_000, 2_000, 49 * COIN_VALUE],
+    ..Default::default()
+  });
+
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(
+    "wallet send-many --fee-rate 1 --csv transfers.csv --consolidate --consolidate-threshold 5000sat",
+  )
+  .write(
+    "transfers.csv",
+    format!("{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"),
+  )
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  assert_eq!(output.input_count, tx.input.len());
+
+  for vout in [0, 1] {
+    assert!(tx
+      .input
+      .iter()
+      .any(|input| input.previous_output == OutPoint { txid: small, vout }));
+  }
+
+  assert!(!tx
+    .input
+    .iter()
+    .any(|input| input.previous_output == OutPoint { txid: small, vout: 2 }));
+}
+
+#[test]
+fn sendmany_consolidate_requires_threshold() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("wallet send-many --fee-rate 1 --csv transfers.csv --consolidate")
+    .rpc_server(&rpc_server)
+    .stderr_regex(".*the following required arguments were not provided.*--consolidate-threshold.*")
+    .expected_exit_code(2)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn sendmany_locks_inputs_unless_broadcast() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new("wallet send-many --fee-rate 1 --csv transfers.csv")
+    .write(
+      "transfers.csv",
+      format!("{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  assert_eq!(
+    output.locked,
+    tx.input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<Vec<OutPoint>>()
+  );
+
+  let output = CommandBuilder::new("wallet send-many --fee-rate 1 --csv transfers.csv --broadcast")
+    .write(
+      "transfers.csv",
+      format!("{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  assert!(output.locked.is_empty());
+}
+
+#[test]
+fn sendmany_dust_to_fee_drops_small_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  let small = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(usize::try_from(rpc_server.height()).unwrap(), 0, 0, Default::default())],
+    outputs: 2,
+    output_values: &[2_000, 49 * COIN_VALUE],
+    ..Default::default()
+  });
+
+  rpc_server.mine_blocks(1);
+
+  let transfers = format!("{inscription},bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n");
+
+  let output = CommandBuilder::new(format!(
+    "wallet send-many --fee-rate 1 --csv transfers.csv --cardinal {small}:0"
+  ))
+  .write("transfers.csv", &transfers)
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Output>();
+
+  assert!(output.change > 0);
+  assert_eq!(output.change + output.fee, 2_000);
+
+  let output = CommandBuilder::new(format!(
+    "wallet send-many --fee-rate 1 --csv transfers.csv --cardinal {small}:0 --dust-to-fee 5000sat"
+  ))
+  .write("transfers.csv", &transfers)
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  assert_eq!(tx.output.len(), 1);
+  assert_eq!(output.change, 0);
+  assert_eq!(output.fee, 2_000);
+}
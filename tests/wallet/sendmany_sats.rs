@@ -0,0 +1,110 @@
+use {super::*, ord::subcommand::wallet::sendmany::Output};
+
+#[test]
+fn sendmany_sats_pays_each_destination_and_returns_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet send-many-sats --fee-rate 1 --csv payments.csv")
+    .write(
+      "payments.csv",
+      "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,1000\nbc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297,2000\n",
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  assert_eq!(tx.input.len(), output.input_count);
+  assert_eq!(
+    tx.output
+      .iter()
+      .map(|output| output.value)
+      .collect::<Vec<u64>>(),
+    [1000, 2000, output.change],
+  );
+  assert_eq!(
+    tx.output[0].script_pubkey,
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+      .script_pubkey(),
+  );
+  assert_eq!(50 * COIN_VALUE - 1000 - 2000 - output.fee, output.change);
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn sendmany_sats_broadcast_sends_transaction() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output =
+    CommandBuilder::new("wallet send-many-sats --fee-rate 1 --csv payments.csv --broadcast")
+      .write(
+        "payments.csv",
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,1000\n",
+      )
+      .rpc_server(&rpc_server)
+      .run_and_deserialize_output::<Output>();
+
+  let mempool = rpc_server.mempool();
+
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid().to_string(), output.tx);
+}
+
+#[test]
+fn sendmany_sats_csv_skips_comments_and_blank_lines_and_trims_fields() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("wallet send-many-sats --fee-rate 1 --csv payments.csv")
+    .write(
+      "payments.csv",
+      "\u{feff}# payroll\n\n  bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 , 1000 \n# done\n",
+    )
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  let tx: bitcoin::Transaction =
+    bitcoin::consensus::encode::deserialize(&hex::decode(output.tx).unwrap()).unwrap();
+
+  assert_eq!(tx.output.len(), 2);
+  assert_eq!(tx.output[0].value, 1000);
+}
+
+#[test]
+fn sendmany_sats_csv_errors_point_at_source_lines() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet send-many-sats --fee-rate 1 --csv payments.csv")
+    .write(
+      "payments.csv",
+      "# payroll\n\nbc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,1000\nbc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,lots\n",
+    )
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: bad amount on line 4: invalid digit found in string\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn sendmany_sats_csv_without_destinations_is_an_error() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet send-many-sats --fee-rate 1 --csv payments.csv")
+    .write("payments.csv", "# nothing to pay yet\n\n")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: CSV file 'payments.csv' doesn't list any destinations\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
@@ -0,0 +1,76 @@
+use {super::*, ord::subcommand::wallet::sweep_commits::Output};
+
+#[test]
+fn sweep_commits_requires_commit_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "wallet sweep-commits --destination bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 --fee-rate 1",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stderr("error: wallet has no unspent commit outputs to sweep\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn sweep_commits_sweeps_unrevealed_commit_output() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let exported = CommandBuilder::new(
+    "wallet inscribe --fee-rate 1 --file foo.txt --export-signed signed.json",
+  )
+  .write("foo.txt", "FOO")
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .run_and_extract_file("signed.json");
+
+  // broadcast only the commit, as if the reveal had been lost, leaving its output to the recovery key
+  let mut progress: serde_json::Value = serde_json::from_str(&exported).unwrap();
+  progress["reveal_broadcast"] = true.into();
+
+  let commit = CommandBuilder::new("wallet broadcast-file signed.json")
+    .write("signed.json", progress.to_string())
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Inscribe>()
+    .commit
+    .unwrap();
+
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(
+    "wallet sweep-commits --destination bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 --fee-rate 1 --broadcast",
+  )
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.commits.len(), 1);
+  assert_eq!(output.commits[0].txid, commit);
+
+  assert_eq!(
+    output.value + output.fee,
+    rpc_server
+      .get_utxo_amount(&output.commits[0])
+      .unwrap()
+      .to_sat()
+  );
+
+  let mempool = rpc_server.mempool();
+
+  assert_eq!(mempool.len(), 1);
+  assert_eq!(mempool[0].txid().to_string(), output.tx.unwrap());
+  assert_eq!(mempool[0].input[0].previous_output, output.commits[0]);
+  assert_eq!(
+    mempool[0].output[0].script_pubkey,
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap()
+      .assume_checked()
+      .script_pubkey()
+  );
+  assert_eq!(mempool[0].output[0].value, output.value);
+}
@@ -0,0 +1,39 @@
+use {super::*, ord::subcommand::wallet::utxo_contents::Output};
+
+#[test]
+fn inscribed_output_is_not_cardinal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  let (inscription, _reveal) = inscribe(&rpc_server);
+
+  let output = CommandBuilder::new(format!("wallet utxo-contents {}:0", inscription.txid))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.inscriptions.len(), 1);
+  assert_eq!(output.inscriptions[0].id, inscription);
+  assert_eq!(output.inscriptions[0].offset, 0);
+  assert_eq!(output.runes, None);
+  assert!(!output.is_cardinal);
+}
+
+#[test]
+fn plain_output_is_cardinal_with_rune_index() {
+  let rpc_server = test_bitcoincore_rpc::builder()
+    .network(Network::Regtest)
+    .build();
+  create_wallet(&rpc_server);
+
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-runes wallet utxo-contents {txid}:0"
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Output>();
+
+  assert_eq!(output.inscriptions, Vec::new());
+  assert_eq!(output.runes, Some(BTreeMap::new()));
+  assert!(output.is_cardinal);
+}
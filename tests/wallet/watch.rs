@@ -0,0 +1,71 @@
+use {
+  super::*,
+  ord::subcommand::{wallet::outputs::Output, Empty},
+};
+
+const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+#[test]
+fn watch_xpub_imports_public_taproot_descriptors() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new(format!("wallet watch --xpub {XPUB}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Empty>();
+
+  let descriptors = rpc_server.descriptors();
+
+  assert_eq!(descriptors.len(), 2);
+  assert!(descriptors[0].starts_with(&format!("tr({XPUB}/0/*)#")));
+  assert!(descriptors[1].starts_with(&format!("tr({XPUB}/1/*)#")));
+}
+
+#[test]
+fn watch_xpub_must_match_chain() {
+  CommandBuilder::new(format!("--chain signet wallet watch --xpub {XPUB}"))
+    .rpc_server(&test_bitcoincore_rpc::builder().network(Network::Signet).build())
+    .expected_stderr("error: xpub is for bitcoin, but ord is running on signet\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn watch_only_wallet_cannot_sign() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new(format!("wallet watch --xpub {XPUB}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Empty>();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet send --fee-rate 1 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4 1btc")
+    .rpc_server(&rpc_server)
+    .expected_stderr(
+      "error: wallet \"ord\" is a watch-only wallet and can't sign transactions; use `--psbt` or `--no-wallet` to get an unsigned transaction to sign elsewhere\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn watched_wallet_sees_existing_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let coinbase_tx = &rpc_server.mine_blocks(1)[0].txdata[0];
+  let outpoint = OutPoint::new(coinbase_tx.txid(), 0);
+
+  CommandBuilder::new(format!("wallet watch --xpub {XPUB}"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Empty>();
+
+  let output = CommandBuilder::new("wallet outputs")
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Vec<Output>>();
+
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].output, outpoint);
+  assert_eq!(output[0].amount, coinbase_tx.output[0].value);
+}